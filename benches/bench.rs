@@ -0,0 +1,33 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Compares the [FetchBackend]s by fetching the licenses of this crate.
+//!
+//! Run with `cargo bench --features build`.
+
+use std::time::{Duration, Instant};
+
+use license_fetcher::build_script::{
+    generate_package_list_with_config, ConfigBuilder, FetchBackend,
+};
+
+const RUNS: u32 = 5;
+
+fn main() {
+    for backend in [FetchBackend::Std, FetchBackend::WalkDir] {
+        let config = ConfigBuilder::new(env!("CARGO_MANIFEST_DIR"), env!("CARGO_PKG_NAME"))
+            .fetch_backend(backend)
+            .build();
+
+        let mut total = Duration::ZERO;
+        for _ in 0..RUNS {
+            let start = Instant::now();
+            generate_package_list_with_config(config.clone());
+            total += start.elapsed();
+        }
+
+        println!("{:?}: {:?} per run", backend, total / RUNS);
+    }
+}
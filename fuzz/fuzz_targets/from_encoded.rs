@@ -0,0 +1,15 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Decodes arbitrary bytes. Mostly exercises the framing, as random input rarely has a valid checksum.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use license_fetcher::PackageList;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = PackageList::from_encoded(data);
+});
@@ -0,0 +1,58 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Decodes arbitrary payloads in a valid frame, so the input reaches decompression and deserialization.
+//!
+//! The first byte selects the format and compression, the rest is the payload.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use license_fetcher::build_script::EncodeOptions;
+use license_fetcher::{Compression, PackageList, SerializationFormat};
+
+/// Magic, version and flags.
+const HEADER_PREFIX_LENGTH: usize = 10;
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, payload)) = data.split_first() else {
+        return;
+    };
+    let format = match selector & 3 {
+        1 => SerializationFormat::Cbor,
+        2 => SerializationFormat::Postcard,
+        3 => SerializationFormat::Rkyv,
+        _ => SerializationFormat::Bincode,
+    };
+    let compression = if selector & 4 == 0 {
+        Compression::Deflate
+    } else {
+        Compression::None
+    };
+
+    // The header of an empty list carries the flags of the chosen format and compression.
+    let empty = PackageList(vec![]).encode(
+        &EncodeOptions::default()
+            .format(format)
+            .compression(compression),
+    );
+    let mut framed = empty[..HEADER_PREFIX_LENGTH].to_vec();
+    framed.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    framed.extend_from_slice(payload);
+    framed.extend_from_slice(&crc32(&framed).to_le_bytes());
+
+    let _ = PackageList::from_encoded(&framed);
+});
@@ -0,0 +1,118 @@
+#ifndef LICENSE_FETCHER_H
+#define LICENSE_FETCHER_H
+
+/* Generated with cbindgen. Do not edit manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Field of a package that can be read with [lf_package_field].
+ */
+typedef enum LfPackageField {
+  LF_PACKAGE_FIELD_NAME,
+  LF_PACKAGE_FIELD_VERSION,
+  LF_PACKAGE_FIELD_DESCRIPTION,
+  LF_PACKAGE_FIELD_HOMEPAGE,
+  LF_PACKAGE_FIELD_REPOSITORY,
+  LF_PACKAGE_FIELD_LICENSE_IDENTIFIER,
+  LF_PACKAGE_FIELD_LICENSE_TEXT,
+} LfPackageField;
+
+/**
+ * Opaque handle to a decoded package list.
+ */
+typedef struct LfPackageList LfPackageList;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Decodes embedded license data.
+ *
+ * Returns `NULL` if the data is empty or corrupted. Free the list with [lf_package_list_free].
+ *
+ * # Safety
+ *
+ * `data` must point to `len` readable bytes.
+ */
+struct LfPackageList *lf_package_list_decode(const uint8_t *data, size_t len);
+
+/**
+ * Frees a package list. Passing `NULL` is a no-op.
+ *
+ * # Safety
+ *
+ * `list` must be `NULL` or returned by [lf_package_list_decode] and not freed before.
+ */
+void lf_package_list_free(struct LfPackageList *list);
+
+/**
+ * Returns the number of packages. Returns `0` for `NULL`.
+ *
+ * # Safety
+ *
+ * `list` must be `NULL` or a valid list.
+ */
+size_t lf_package_list_len(const struct LfPackageList *list);
+
+/**
+ * Returns a field of the package at `index`.
+ *
+ * Returns `NULL` if the index is out of bounds or the field is not set.
+ *
+ * # Safety
+ *
+ * `list` must be `NULL` or a valid list.
+ */
+const char *lf_package_field(const struct LfPackageList *list,
+                             size_t index,
+                             enum LfPackageField field);
+
+/**
+ * Returns the number of authors of the package at `index`.
+ *
+ * # Safety
+ *
+ * `list` must be `NULL` or a valid list.
+ */
+size_t lf_package_authors_len(const struct LfPackageList *list, size_t index);
+
+/**
+ * Returns author `author_index` of the package at `index`, or `NULL` if out of bounds.
+ *
+ * # Safety
+ *
+ * `list` must be `NULL` or a valid list.
+ */
+const char *lf_package_author(const struct LfPackageList *list, size_t index, size_t author_index);
+
+/**
+ * Returns the number of annotations of the package at `index`.
+ *
+ * # Safety
+ *
+ * `list` must be `NULL` or a valid list.
+ */
+size_t lf_package_annotations_len(const struct LfPackageList *list, size_t index);
+
+/**
+ * Returns annotation `annotation_index` of the package at `index`, or `NULL` if out of bounds.
+ *
+ * # Safety
+ *
+ * `list` must be `NULL` or a valid list.
+ */
+const char *lf_package_annotation(const struct LfPackageList *list,
+                                  size_t index,
+                                  size_t annotation_index);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* LICENSE_FETCHER_H */
@@ -0,0 +1,105 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! License data serialized as rkyv archive, which can be accessed in place.
+
+use alloc::format;
+
+use bincode::error::DecodeError;
+use rkyv::rancor;
+use rkyv::util::AlignedVec;
+
+use crate::encoding::{self, ARCHIVE_ALIGNMENT, ARCHIVE_PADDING};
+use crate::error::UnpackError;
+use crate::{ArchivedPackageList, BuildInfo, Compression, PackageList, SerializationFormat};
+
+/// Build info and package list.
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+pub(crate) struct RkyvPayload {
+    pub build_info: Option<BuildInfo>,
+    pub packages: PackageList,
+}
+
+fn decode_error(e: rancor::Error) -> UnpackError {
+    UnpackError::DecodeError(DecodeError::OtherString(format!(
+        "Invalid rkyv archive: {}",
+        e
+    )))
+}
+
+/// The archive of a payload, without the padding in front of it.
+fn archive(payload: &[u8]) -> Result<&[u8], UnpackError> {
+    payload
+        .get(ARCHIVE_PADDING..)
+        .ok_or(UnpackError::InvalidFormat)
+}
+
+pub(crate) fn decode(payload: &[u8]) -> Result<(PackageList, Option<BuildInfo>), UnpackError> {
+    // Copied, as the payload is not necessarily aligned.
+    let archive = archive(payload)?;
+    let mut aligned = AlignedVec::<ARCHIVE_ALIGNMENT>::with_capacity(archive.len());
+    aligned.extend_from_slice(archive);
+    let payload = rkyv::from_bytes::<RkyvPayload, rancor::Error>(&aligned).map_err(decode_error)?;
+    Ok((payload.packages, payload.build_info))
+}
+
+impl PackageList {
+    /// Accesses license data serialized with [SerializationFormat::Rkyv] in place, without allocating or decoding.
+    ///
+    /// Meant for latency sensitive programs, which only occasionally show their license information. Checksum and
+    /// archive are validated, so corrupted data returns an [UnpackError]. The data has to be aligned to 16 bytes,
+    /// which [archived_package_list_macro](crate::archived_package_list_macro) takes care of. Data in another format
+    /// returns [UnpackError::UnsupportedFormat]. Needs the feature `rkyv`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use license_fetcher::archived_package_list_macro;
+    ///
+    /// fn main() {
+    ///     let package_list = archived_package_list_macro!().unwrap();
+    ///     for package in package_list.0.iter() {
+    ///         println!("{} {}", package.name, package.version);
+    ///     }
+    /// }
+    /// ```
+    pub fn archived(bytes: &[u8]) -> Result<&ArchivedPackageList, UnpackError> {
+        let frame = encoding::unframe(bytes)?;
+        if frame.format != SerializationFormat::Rkyv {
+            return Err(UnpackError::UnsupportedFormat);
+        }
+        if frame.compression != Compression::None {
+            return Err(UnpackError::InvalidFormat);
+        }
+        let payload = rkyv::access::<ArchivedRkyvPayload, rancor::Error>(archive(frame.payload)?)
+            .map_err(decode_error)?;
+        Ok(&payload.packages)
+    }
+}
+
+/// Calls [PackageList::archived] with the license data from `OUT_DIR` included with an alignment of 16 bytes.
+///
+/// The build step has to write the data with [SerializationFormat::Rkyv], e.g. with
+/// `LICENSE_FETCHER_FORMAT=rkyv`. Returns `Result<&'static ArchivedPackageList, UnpackError>`.
+///
+/// # Example
+/// ```no_run
+/// use license_fetcher::archived_package_list_macro;
+/// fn main() {
+///     let package_list = archived_package_list_macro!().unwrap();
+/// }
+/// ```
+#[macro_export]
+macro_rules! archived_package_list_macro {
+    () => {{
+        #[repr(C, align(16))]
+        struct Aligned<Bytes: ?Sized>(Bytes);
+
+        static LICENSE_FETCHER_DATA: &Aligned<[u8]> = &Aligned(*core::include_bytes!(
+            core::concat!(env!("OUT_DIR"), "/LICENSE-3RD-PARTY.bincode")
+        ));
+
+        license_fetcher::PackageList::archived(&LICENSE_FETCHER_DATA.0)
+    }};
+}
@@ -0,0 +1,153 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use bincode::{Decode, Encode};
+
+use crate::{Package, PackageList};
+
+/// Kind of a non-crate asset like a font or an icon set, see [Package::asset].
+#[derive(Encode, Decode, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[non_exhaustive]
+pub enum AssetKind {
+    /// Fonts, commonly licensed under `OFL-1.1`.
+    Font,
+    /// Icons and images.
+    Icon,
+    /// Data sets like dictionaries or time zone data.
+    Data,
+    /// Machine learning models.
+    Model,
+    Other,
+}
+
+impl fmt::Display for AssetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AssetKind::Font => "Font",
+            AssetKind::Icon => "Icon",
+            AssetKind::Data => "Data",
+            AssetKind::Model => "Model",
+            AssetKind::Other => "Other",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Package {
+    /// Returns `true` if the package is an asset instead of a crate, see [Package::asset].
+    pub fn is_asset(&self) -> bool {
+        self.asset.is_some()
+    }
+}
+
+impl PackageList {
+    /// Crates of the list, without assets.
+    pub fn crates(&self) -> impl Iterator<Item = &Package> {
+        self.iter().filter(|package| !package.is_asset())
+    }
+
+    /// Assets of the list, like fonts and icons, in order of the list.
+    pub fn assets(&self) -> impl Iterator<Item = &Package> {
+        self.iter().filter(|package| package.is_asset())
+    }
+
+    /// Crates first, followed by the assets grouped by kind. Empty groups are left out.
+    ///
+    /// Used by the text outputs, so assets are listed apart from crates.
+    pub(crate) fn asset_groups(&self) -> Vec<(Option<AssetKind>, Vec<&Package>)> {
+        let mut groups = vec![(None, self.crates().collect::<Vec<_>>())];
+        let mut assets: BTreeMap<AssetKind, Vec<&Package>> = BTreeMap::new();
+        for package in self.iter() {
+            if let Some(kind) = package.asset {
+                assets.entry(kind).or_default().push(package);
+            }
+        }
+        groups.extend(assets.into_iter().map(|(kind, group)| (Some(kind), group)));
+        groups.retain(|(_, group)| !group.is_empty());
+        groups
+    }
+}
+
+/// Builds an asset [Package] with the license text embedded via [include_str].
+///
+/// The path of the license file is relative to the current file, like with [include_str].
+/// Arguments are asset kind, name, optionally version, SPDX license identifier and license file.
+///
+/// # Example
+/// ```ignore
+/// use license_fetcher::{asset_package, get_package_list_macro};
+///
+/// fn main() {
+///     let mut package_list = get_package_list_macro!().unwrap();
+///     package_list.push(asset_package!(Font, "Fira Sans", "4.301", "OFL-1.1", "../assets/fonts/OFL.txt"));
+///     package_list.push(asset_package!(Icon, "Tabler Icons", "MIT", "../assets/icons/LICENSE"));
+/// }
+/// ```
+#[macro_export]
+macro_rules! asset_package {
+    ($kind:ident, $name:expr, $license:expr, $license_file:expr $(,)?) => {
+        $crate::Package::builder()
+            .name($name)
+            .asset($crate::AssetKind::$kind)
+            .license_identifier($license)
+            .license_text(include_str!($license_file))
+            .build()
+    };
+    ($kind:ident, $name:expr, $version:expr, $license:expr, $license_file:expr $(,)?) => {
+        $crate::Package::builder()
+            .name($name)
+            .version($version)
+            .asset($crate::AssetKind::$kind)
+            .license_identifier($license)
+            .license_text(include_str!($license_file))
+            .build()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_groups() {
+        let package_list = PackageList(vec![
+            Package::builder().name("log").build(),
+            Package::builder()
+                .name("Tabler Icons")
+                .asset(AssetKind::Icon)
+                .build(),
+            Package::builder()
+                .name("Fira Sans")
+                .asset(AssetKind::Font)
+                .build(),
+            Package::builder().name("serde").build(),
+        ]);
+        let groups: Vec<(Option<AssetKind>, Vec<&str>)> = package_list
+            .asset_groups()
+            .into_iter()
+            .map(|(kind, group)| (kind, group.iter().map(|p| p.name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                (None, vec!["log", "serde"]),
+                (Some(AssetKind::Font), vec!["Fira Sans"]),
+                (Some(AssetKind::Icon), vec!["Tabler Icons"]),
+            ]
+        );
+    }
+}
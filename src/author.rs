@@ -0,0 +1,126 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Package, PackageList};
+
+/// An author of [Package::authors] split into name and email address.
+///
+/// Parsed on demand, so the raw author strings stay untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AuthorInfo<'a> {
+    /// Name of the author. Empty if the author consists only of an email address.
+    pub name: &'a str,
+    pub email: Option<&'a str>,
+}
+
+impl<'a> AuthorInfo<'a> {
+    /// Parses authors like `Jane Doe <jane@example.com>`, `Jane Doe` or `jane@example.com`.
+    ///
+    /// # Example
+    /// ```
+    /// use license_fetcher::AuthorInfo;
+    ///
+    /// let author = AuthorInfo::parse("Jane Doe <jane@example.com>");
+    /// assert_eq!(author.name, "Jane Doe");
+    /// assert_eq!(author.email, Some("jane@example.com"));
+    /// ```
+    pub fn parse(author: &'a str) -> Self {
+        let author = author.trim();
+        if let Some((name, rest)) = author.split_once('<') {
+            if let Some((email, _)) = rest.split_once('>') {
+                return Self {
+                    name: name.trim(),
+                    email: Some(email.trim()),
+                };
+            }
+        }
+        if author.contains('@') && !author.contains(char::is_whitespace) {
+            return Self {
+                name: "",
+                email: Some(author),
+            };
+        }
+        Self {
+            name: author,
+            email: None,
+        }
+    }
+}
+
+impl fmt::Display for AuthorInfo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.name, self.email) {
+            ("", Some(email)) => write!(f, "<{}>", email),
+            (name, Some(email)) => write!(f, "{} <{}>", name, email),
+            (name, None) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl Package {
+    /// Iterates over the parsed [Package::authors].
+    pub fn author_infos(&self) -> impl Iterator<Item = AuthorInfo<'_>> {
+        self.authors.iter().map(|author| AuthorInfo::parse(author))
+    }
+}
+
+impl PackageList {
+    /// Authors of all packages, deduplicated by name, or by email address for authors without name.
+    ///
+    /// Authors are in the order they first occur. The first email address of an author is kept.
+    pub fn unique_authors(&self) -> Vec<AuthorInfo<'_>> {
+        let mut seen = BTreeSet::new();
+        self.iter()
+            .flat_map(Package::author_infos)
+            .filter(|author| {
+                let key = if author.name.is_empty() {
+                    author.email.unwrap_or_default()
+                } else {
+                    author.name
+                };
+                seen.insert(key)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn test_parse_author() {
+        assert_eq!(
+            AuthorInfo::parse("jane@example.com"),
+            AuthorInfo {
+                name: "",
+                email: Some("jane@example.com")
+            }
+        );
+        assert_eq!(AuthorInfo::parse(" Jane Doe ").name, "Jane Doe");
+        assert_eq!(AuthorInfo::parse("The Rust Project Developers").email, None);
+
+        let package_list = PackageList(vec![
+            Package::builder()
+                .author("Jane Doe <jane@example.com>")
+                .build(),
+            Package::builder()
+                .author("Jane Doe")
+                .author("<x@y.z>")
+                .build(),
+        ]);
+        let authors = package_list.unique_authors();
+        assert_eq!(authors.len(), 2);
+        assert_eq!(authors[0].to_string(), "Jane Doe <jane@example.com>");
+        assert_eq!(authors[1].to_string(), "<x@y.z>");
+    }
+}
@@ -0,0 +1,56 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::string::String;
+use core::fmt;
+
+use bincode::{Decode, Encode};
+
+/// Provenance of the embedded license data.
+///
+/// Optionally written alongside the [PackageList](crate::PackageList) in the build step,
+/// so that the exact dependency set of a shipped binary can be identified.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
+#[non_exhaustive]
+pub struct BuildInfo {
+    /// Version of `license-fetcher` used in the build step.
+    pub license_fetcher_version: String,
+    /// Output of `rustc -V`.
+    pub rustc_version: Option<String>,
+    /// Output of `cargo -V`.
+    pub cargo_version: Option<String>,
+    /// Target triple the program was built for.
+    pub target: Option<String>,
+    /// Unix timestamp (UTC) of the build step. Taken from `SOURCE_DATE_EPOCH` if set.
+    pub timestamp: u64,
+    /// Hex encoded SHA-256 hash of `Cargo.lock`.
+    pub lockfile_hash: Option<String>,
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "license-fetcher: {}", self.license_fetcher_version)?;
+        if let Some(rustc_version) = &self.rustc_version {
+            writeln!(f, "rustc:           {}", rustc_version)?;
+        }
+        if let Some(cargo_version) = &self.cargo_version {
+            writeln!(f, "cargo:           {}", cargo_version)?;
+        }
+        if let Some(target) = &self.target {
+            writeln!(f, "Target:          {}", target)?;
+        }
+        writeln!(f, "Timestamp:       {}", self.timestamp)?;
+        if let Some(lockfile_hash) = &self.lockfile_hash {
+            writeln!(f, "Cargo.lock:      {}", lockfile_hash)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Pluggable sources of license texts.
+
+use std::fmt;
+
+use log::info;
+
+use super::cargo_source::{
+    fetch_license_for_package, licenses_text_from_cargo_src_folder, registry_src_folders,
+};
+use super::config::Config;
+use super::report::FetchReport;
+use crate::{Package, PackageList};
+
+/// Source of license texts, like an internal mirror of crate sources.
+///
+/// Backends registered with [ConfigBuilder::license_source](super::ConfigBuilder::license_source) run after the
+/// cargo registry, in order of registration, for the packages still without license text.
+/// Backends accessing the network should respect [Config::network].
+///
+/// # Example
+/// ```no_run
+/// use std::path::PathBuf;
+///
+/// use license_fetcher::build_script::{fetch, Config, ConfigBuilder, FetchReport, LicenseSourceBackend};
+/// use license_fetcher::Package;
+///
+/// /// Crate sources unpacked to `<root>/<name>-<version>`.
+/// #[derive(Debug)]
+/// struct Mirror {
+///     root: PathBuf,
+/// }
+///
+/// impl LicenseSourceBackend for Mirror {
+///     fn fetch_package(&self, package: &mut Package, config: &Config, report: &mut FetchReport) {
+///         let folder = self.root.join(format!("{}-{}", package.name, package.version));
+///         if folder.is_dir() {
+///             report.failures.extend(fetch::scan_folder(package, folder, config).failures);
+///         }
+///     }
+/// }
+///
+/// let config = ConfigBuilder::from_build_env()
+///     .license_source(Mirror { root: "/srv/crates".into() })
+///     .build();
+/// ```
+pub trait LicenseSourceBackend: fmt::Debug + Send + Sync {
+    /// Tries to fill [Package::license_text] of a package without license text.
+    ///
+    /// Failures should be recorded in the report instead of panicking.
+    fn fetch_package(&self, package: &mut Package, config: &Config, report: &mut FetchReport);
+
+    /// Fetches all packages of the list without license text.
+    ///
+    /// Calls [Self::fetch_package] for each of them. Override it to batch requests.
+    fn fetch(&self, package_list: &mut PackageList, config: &Config, report: &mut FetchReport) {
+        for package in package_list
+            .iter_mut()
+            .filter(|package| package.license_text.is_none())
+        {
+            self.fetch_package(package, config, report);
+        }
+    }
+}
+
+/// Default backend reading license texts from the registry sources in the cargo home.
+///
+/// Panics if the cargo home or its registry folder does not exist.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RegistryBackend;
+
+impl LicenseSourceBackend for RegistryBackend {
+    fn fetch_package(&self, package: &mut Package, config: &Config, report: &mut FetchReport) {
+        if package.patched {
+            return;
+        }
+        for src_folder in registry_src_folders() {
+            let folder = src_folder.join(format!("{}-{}", package.name, package.version));
+            if package.license_text.is_none() && folder.is_dir() {
+                fetch_license_for_package(package, &folder, config, report);
+            }
+        }
+    }
+
+    /// Scans the registry in parallel.
+    fn fetch(&self, package_list: &mut PackageList, config: &Config, report: &mut FetchReport) {
+        licenses_text_from_cargo_src_folder(package_list, config, report);
+    }
+}
+
+/// Runs the backends registered in the config.
+pub(super) fn fetch_from_registered_backends(
+    package_list: &mut PackageList,
+    config: &Config,
+    report: &mut FetchReport,
+) {
+    for backend in config.license_sources.iter() {
+        if package_list.iter().all(|p| p.license_text.is_some()) {
+            return;
+        }
+        info!("Fetching licenses with backend: {:?}", backend);
+        backend.fetch(package_list, config, report);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::build_script::ConfigBuilder;
+
+    #[derive(Debug)]
+    struct Fixed;
+
+    impl LicenseSourceBackend for Fixed {
+        fn fetch_package(&self, package: &mut Package, _: &Config, _: &mut FetchReport) {
+            package.license_text = Some("Internal License".into());
+        }
+    }
+
+    #[test]
+    fn test_registered_backends() {
+        let config = ConfigBuilder::new(std::env::temp_dir(), "root")
+            .license_source(Fixed)
+            .build();
+        let mut package_list = PackageList(vec![
+            Package::builder().name("a").license_text("MIT").build(),
+            Package::builder().name("b").build(),
+        ]);
+        let mut report = FetchReport::default();
+        fetch_from_registered_backends(&mut package_list, &config, &mut report);
+
+        assert_eq!(package_list[0].license_text.as_deref(), Some("MIT"));
+        assert_eq!(
+            package_list[1].license_text.as_deref(),
+            Some("Internal License")
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Badges showing the attribution [Completeness] of a package list.
+
+use std::io;
+use std::path::Path;
+
+use serde_json::json;
+
+use super::fs::write;
+use crate::Completeness;
+
+const LABEL: &str = "attribution";
+
+fn color(percent: u8) -> (&'static str, &'static str) {
+    match percent {
+        100 => ("brightgreen", "#4c1"),
+        90..=99 => ("green", "#97ca00"),
+        75..=89 => ("yellow", "#dfb317"),
+        50..=74 => ("orange", "#fe7d37"),
+        _ => ("red", "#e05d44"),
+    }
+}
+
+/// Approximate width of a text in the badge font.
+fn text_width(text: &str) -> usize {
+    text.chars().count() * 7 + 10
+}
+
+impl Completeness {
+    /// Badge as JSON for the [shields.io endpoint](https://shields.io/badges/endpoint-badge).
+    pub fn badge_json(&self) -> String {
+        json!({
+            "schemaVersion": 1,
+            "label": LABEL,
+            "message": format!("{}%", self.percent()),
+            "color": color(self.percent()).0,
+        })
+        .to_string()
+    }
+
+    /// Badge as standalone SVG image.
+    pub fn badge_svg(&self) -> String {
+        let message = format!("{}%", self.percent());
+        let label_width = text_width(LABEL);
+        let message_width = text_width(&message);
+        let width = label_width + message_width;
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{LABEL}: {message}">
+<title>{LABEL}: {message}</title>
+<rect width="{label_width}" height="20" fill="#555"/>
+<rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>
+<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+<text x="{label_x}" y="14">{LABEL}</text>
+<text x="{message_x}" y="14">{message}</text>
+</g>
+</svg>
+"##,
+            color = color(self.percent()).1,
+            label_x = label_width / 2,
+            message_x = label_width + message_width / 2,
+        )
+    }
+
+    /// Writes [Completeness::badge_json] to a file.
+    pub fn write_badge_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write(path, self.badge_json())
+    }
+
+    /// Writes [Completeness::badge_svg] to a file.
+    pub fn write_badge_svg(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write(path, self.badge_svg())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Package, PackageList};
+
+    #[test]
+    fn test_badges() {
+        let completeness = PackageList(vec![Package::builder()
+            .name("a")
+            .license_identifier("MIT")
+            .build()])
+        .completeness();
+
+        assert_eq!(
+            completeness.badge_json(),
+            r#"{"color":"red","label":"attribution","message":"0%","schemaVersion":1}"#
+        );
+        assert!(completeness.badge_svg().contains("attribution: 0%"));
+    }
+}
@@ -0,0 +1,291 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! One package list per binary target of a workspace and merging of package lists of several binaries.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use log::{info, warn};
+
+use super::config::{Config, SortOrder};
+use super::encode::EncodeOptions;
+use super::fs::{read, write};
+use super::pipeline::{fetch_from_sources, post_process, restore_from_cache, Stage};
+use super::report::FetchReport;
+use super::{cargo_metadata, fetch_root_license, json_log, metadata, sort_packages};
+use crate::{get_package_list, MergeConflict, Package, PackageKey, PackageList};
+
+/// Generates a package list for every binary target of the workspace members in a single pass.
+///
+/// `cargo metadata` runs once and every license is fetched only once, even if it is used by several binaries.
+/// Each list holds only the dependencies of the package the binary belongs to. The package itself comes first,
+/// unless excluded with [ConfigBuilder::include_root](super::ConfigBuilder::include_root).
+/// [Config::root_license] is resolved against the manifest dir of each package.
+///
+/// The [Config::manifest_dir] should point to the workspace root. With [Config::skip] every list is empty.
+///
+/// # Example
+/// In `build.rs` of a package of the workspace:
+/// ```no_run
+/// use license_fetcher::build_script::{
+///     generate_package_lists_per_binary, write_package_lists_per_binary, ConfigBuilder, EncodeOptions,
+/// };
+///
+/// fn main() {
+///     let config = ConfigBuilder::new(concat!(env!("CARGO_MANIFEST_DIR"), "/.."), "my-package").build();
+///     let (package_lists, _report) = generate_package_lists_per_binary(config);
+///     write_package_lists_per_binary(
+///         &package_lists,
+///         concat!(env!("CARGO_MANIFEST_DIR"), "/../assets"),
+///         &EncodeOptions::default(),
+///     )
+///     .unwrap();
+/// }
+/// ```
+pub fn generate_package_lists_per_binary(
+    config: Config,
+) -> (BTreeMap<String, PackageList>, FetchReport) {
+    json_log::init(config.log_format);
+    let mut report = FetchReport::default();
+    let start = Instant::now();
+    let binaries = metadata::binary_package_lists_from_metadata(
+        &cargo_metadata(
+            &config,
+            &mut report.cargo_attempts,
+            metadata::parse_metadata,
+        )
+        .unwrap_or_else(|e| panic!("{}", e)),
+        config.dependency_depth,
+    );
+    report.record_timing(Stage::Resolve, start);
+    if config.skip {
+        info!("Skipping license fetching.");
+        let package_lists = binaries
+            .into_keys()
+            .map(|binary| (binary, PackageList(vec![])))
+            .collect();
+        json_log::finish(&config, 0, &report);
+        return (package_lists, report);
+    }
+
+    let mut union = BTreeMap::new();
+    for package in binaries
+        .values()
+        .flat_map(|(_, package_list)| package_list.iter())
+    {
+        union
+            .entry(package.key())
+            .or_insert_with(|| package.clone());
+    }
+    let mut union = PackageList(union.into_values().collect());
+    let package_count = union.len();
+    info!(
+        "Fetching licenses of {} packages for {} binaries.",
+        union.len(),
+        binaries.len()
+    );
+
+    let start = Instant::now();
+    let cache = restore_from_cache(&mut union, &config, &mut report);
+    report.record_timing(Stage::Cache, start);
+
+    let start = Instant::now();
+    fetch_from_sources(&mut union, &config, &mut report, cache);
+    report.record_timing(Stage::Registry, start);
+
+    let start = Instant::now();
+    let roots: BTreeSet<&PackageKey> = binaries.values().map(|(root, _)| root).collect();
+    for package in union.iter_mut() {
+        if !roots.contains(&package.key()) {
+            continue;
+        }
+        let Some(manifest_dir) = package
+            .manifest_path
+            .as_deref()
+            .and_then(|path| Path::new(path).parent())
+            .map(PathBuf::from)
+        else {
+            continue;
+        };
+        let root_config = Config {
+            manifest_dir,
+            ..config.clone()
+        };
+        info!("Fetching license for: {}", &package.name);
+        fetch_root_license(package, &root_config, &mut report);
+    }
+    report.record_timing(Stage::Root, start);
+
+    // Lists are assembled and sorted below.
+    let union_config = Config {
+        include_root: false,
+        sort: SortOrder::None,
+        ..config.clone()
+    };
+    let start = Instant::now();
+    post_process(&mut union, &union_config, &mut report);
+    report.record_timing(Stage::PostProcess, start);
+    let union = union.into_map();
+
+    let package_lists = binaries
+        .into_iter()
+        .map(|(binary, (root, package_list))| {
+            let mut dependencies: Vec<_> = package_list
+                .iter()
+                .map(Package::key)
+                .filter(|key| *key != root)
+                .map(|key| union[&key].clone())
+                .collect();
+            // Extra packages like assets are part of every list.
+            dependencies.extend(
+                config
+                    .extra_packages
+                    .iter()
+                    .filter_map(|extra| union.get(&extra.key()).cloned()),
+            );
+            sort_packages(&mut dependencies, config.sort);
+
+            let mut packages = vec![];
+            if config.include_root {
+                packages.push(union[&root].clone());
+            }
+            packages.extend(dependencies);
+            (binary, PackageList(packages))
+        })
+        .collect();
+
+    json_log::finish(&config, package_count, &report);
+    (package_lists, report)
+}
+
+/// Writes every package list of [generate_package_lists_per_binary] to `dir`
+/// as `LICENSE-3RD-PARTY-<binary>.bincode`.
+///
+/// Embed a list with [get_package_list_from_path_macro](crate::get_package_list_from_path_macro).
+pub fn write_package_lists_per_binary(
+    package_lists: &BTreeMap<String, PackageList>,
+    dir: impl AsRef<Path>,
+    options: &EncodeOptions,
+) -> io::Result<()> {
+    for (binary, package_list) in package_lists {
+        let path = dir
+            .as_ref()
+            .join(format!("LICENSE-3RD-PARTY-{}.bincode", binary));
+        info!("Writing to file: {:?}", &path);
+        write(&path, package_list.encode(options))?;
+    }
+    Ok(())
+}
+
+/// Reads encoded package lists and merges them with [PackageList::merge_all].
+///
+/// Meant for the build script of a launcher binary, which embeds the licenses of the binaries it ships.
+/// Conflicts are logged as warnings.
+///
+/// # Example
+/// In `build.rs` of the launcher:
+/// ```no_run
+/// use license_fetcher::build_script::merge_package_list_files;
+///
+/// fn main() {
+///     let (package_list, _conflicts) = merge_package_list_files([
+///         concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/LICENSE-3RD-PARTY-server.bincode"),
+///         concat!(env!("CARGO_MANIFEST_DIR"), "/../assets/LICENSE-3RD-PARTY-client.bincode"),
+///     ])
+///     .unwrap();
+///     package_list.write();
+/// }
+/// ```
+pub fn merge_package_list_files<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+) -> io::Result<(PackageList, Vec<MergeConflict>)> {
+    let package_lists = paths
+        .into_iter()
+        .map(|path| {
+            let path = path.as_ref();
+            get_package_list(&read(path)?).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("{:?}: {}", path, e))
+            })
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let (package_list, conflicts) = PackageList::merge_all(package_lists);
+    for conflict in conflicts.iter() {
+        warn!("{}", conflict);
+    }
+    Ok((package_list, conflicts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::build_script::fs::test_dir;
+
+    #[test]
+    fn test_write_package_lists_per_binary() {
+        let dir = test_dir("per-binary");
+
+        let package = |name: &str| Package::builder().name(name).version("1.0.0").build();
+        let package_lists = BTreeMap::from([
+            (
+                "cli".to_owned(),
+                PackageList(vec![package("a"), package("c")]),
+            ),
+            (
+                "gui".to_owned(),
+                PackageList(vec![package("a"), package("d")]),
+            ),
+        ]);
+        write_package_lists_per_binary(&package_lists, &dir, &EncodeOptions::default()).unwrap();
+
+        for (binary, package_list) in package_lists.iter() {
+            let path = dir.join(format!("LICENSE-3RD-PARTY-{}.bincode", binary));
+            let decoded = get_package_list(&std::fs::read(path).unwrap()).unwrap();
+            assert_eq!(&decoded, package_list);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_merge_package_list_files() {
+        let dir = test_dir("merge-files");
+
+        let package = |name: &str, text: &str| {
+            Package::builder()
+                .name(name)
+                .version("1.0.0")
+                .license_text(text)
+                .build()
+        };
+        let server = PackageList(vec![package("a", "MIT"), package("b", "MIT")]);
+        let client = PackageList(vec![package("b", "Apache"), package("c", "MIT")]);
+        let options = EncodeOptions::default();
+        std::fs::write(dir.join("server.bincode"), server.encode(&options)).unwrap();
+        std::fs::write(dir.join("client.bincode"), client.encode(&options)).unwrap();
+        std::fs::write(dir.join("broken.bincode"), b"broken").unwrap();
+
+        let (package_list, conflicts) =
+            merge_package_list_files([dir.join("server.bincode"), dir.join("client.bincode")])
+                .unwrap();
+        let names: Vec<&str> = package_list.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!((conflicts[0].kept, conflicts[0].dropped), (0, 1));
+
+        let error =
+            merge_package_list_files([dir.join("server.bincode"), dir.join("broken.bincode")])
+                .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("broken.bincode"));
+
+        let error = merge_package_list_files([dir.join("missing.bincode")]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,73 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use std::env::var_os;
+use std::ffi::OsString;
+use std::fs::read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::warn;
+use sha2::{Digest, Sha256};
+
+use crate::BuildInfo;
+
+fn tool_version(tool: Option<OsString>, default: &str) -> Option<String> {
+    let tool = tool.unwrap_or_else(|| OsString::from(default));
+    let output = Command::new(&tool).arg("-V").output().ok()?;
+    if !output.status.success() {
+        warn!("Failed getting version of {:?}", &tool);
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+pub(super) fn find_lockfile(manifest_dir: &Path) -> Option<PathBuf> {
+    manifest_dir
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| path.is_file())
+}
+
+fn build_timestamp() -> u64 {
+    if let Some(epoch) = var_os("SOURCE_DATE_EPOCH") {
+        if let Some(epoch) = epoch.to_str().and_then(|e| e.parse().ok()) {
+            return epoch;
+        }
+        warn!("Ignoring invalid SOURCE_DATE_EPOCH: {:?}", epoch);
+    }
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl BuildInfo {
+    /// Gathers build information from the env variables cargo supplies to build scripts.
+    ///
+    /// Reads `RUSTC`, `CARGO`, `TARGET` and `CARGO_MANIFEST_DIR`. `Cargo.lock` is searched for in the manifest dir
+    /// and its parents, so workspace members are supported.
+    pub fn from_build_env() -> Self {
+        let lockfile_hash = var_os("CARGO_MANIFEST_DIR")
+            .and_then(|dir| find_lockfile(Path::new(&dir)))
+            .and_then(|path| read(path).ok())
+            .map(|lockfile| {
+                Sha256::digest(lockfile)
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect()
+            });
+
+        Self {
+            license_fetcher_version: env!("CARGO_PKG_VERSION").to_owned(),
+            rustc_version: tool_version(var_os("RUSTC"), "rustc"),
+            cargo_version: tool_version(var_os("CARGO"), "cargo"),
+            target: var_os("TARGET").map(|t| t.to_string_lossy().into_owned()),
+            timestamp: build_timestamp(),
+            lockfile_hash,
+        }
+    }
+}
@@ -0,0 +1,551 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Cache of fetched license texts, so the registry does not need to be walked on every build.
+//!
+//! Entries are keyed by package name and version and validated with the checksum of the crate in `Cargo.lock`.
+//! Packages without checksum, like path or git dependencies, are never cached.
+//!
+//! The cache is opt-in with [ConfigBuilder::cache](super::ConfigBuilder::cache).
+//! For CI pipelines it can be moved between machines with [export] and [import].
+//!
+//! Build scripts of several workspace members may use the cache concurrently. Writes are atomic and guarded by
+//! an advisory lock on `licenses.bincode.lock` next to the cache. While holding it, a writer merges its entries
+//! into those on disk, so entries written by concurrent builds are kept.
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+
+use bincode::{Decode, Encode};
+use directories::ProjectDirs;
+use fs4::{FileExt, TryLockError};
+use log::{info, warn};
+use serde::Deserialize;
+
+use super::build_info::find_lockfile;
+use super::compare_versions;
+use super::config::{CachePolicy, Config};
+use super::fs::{create_dir_all, read, read_to_string, with_path, write_atomic};
+use super::report::LicenseChange;
+use crate::{LicenseFile, LicenseTextSource, LicenseTranslation, Package, PackageList};
+
+const CACHE_VERSION: u8 = 3;
+
+/// Attempts of acquiring the lock for reading while another build writes the cache.
+const LOCK_RETRIES: u32 = 50;
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+struct CacheEntry {
+    checksum: String,
+    license_text: String,
+    license_text_source: Option<LicenseTextSource>,
+    license_files: Vec<LicenseFile>,
+    license_translations: Vec<LicenseTranslation>,
+    /// License identifier declared by the package, used for detecting relicensing.
+    license_identifier: Option<String>,
+}
+
+#[derive(Encode, Decode, Debug, Default, PartialEq, Eq)]
+pub(super) struct LicenseCache {
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+pub(super) fn cache_key(package: &Package) -> String {
+    format!("{} {}", package.name, package.version)
+}
+
+pub(super) fn default_cache_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "license-fetcher")
+        .map(|dirs| dirs.cache_dir().join("licenses.bincode"))
+}
+
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockfilePackage>,
+}
+
+#[derive(Deserialize)]
+struct LockfilePackage {
+    name: String,
+    version: String,
+    checksum: Option<String>,
+}
+
+/// Reads the checksums of all packages in the `Cargo.lock` of the manifest dir or one of its parents.
+///
+/// Keys are the same as of the cache entries.
+pub(super) fn lockfile_checksums(manifest_dir: &Path) -> BTreeMap<String, String> {
+    let Some(lockfile) = find_lockfile(manifest_dir).and_then(|path| read_to_string(path).ok())
+    else {
+        warn!("Failed reading Cargo.lock. License cache is disabled.");
+        return BTreeMap::new();
+    };
+
+    match toml::from_str::<Lockfile>(&lockfile) {
+        Ok(lockfile) => lockfile
+            .package
+            .into_iter()
+            .filter_map(|p| Some((format!("{} {}", p.name, p.version), p.checksum?)))
+            .collect(),
+        Err(e) => {
+            warn!("Failed parsing Cargo.lock: {}", e);
+            BTreeMap::new()
+        }
+    }
+}
+
+/// Advisory lock of a cache file, released when dropped.
+///
+/// A separate `<file>.lock` is locked, as the cache file itself is replaced on every write.
+struct CacheLock {
+    _file: File,
+}
+
+impl CacheLock {
+    fn open(path: &Path) -> io::Result<File> {
+        let mut lock_path = path.as_os_str().to_owned();
+        lock_path.push(".lock");
+        let lock_path = PathBuf::from(lock_path);
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| with_path(&lock_path, e))
+    }
+
+    /// Blocks until no other process reads or writes the cache.
+    fn exclusive(path: &Path) -> io::Result<Self> {
+        let file = Self::open(path)?;
+        // Called through the trait, as `File::lock` of newer Rust versions would shadow it.
+        FileExt::lock(&file).map_err(|e| with_path(path, e))?;
+        Ok(Self { _file: file })
+    }
+
+    /// Waits while another process writes the cache.
+    ///
+    /// Returns `None` if the lock can't be acquired. Reading is still safe then, as writes are atomic.
+    fn shared(path: &Path) -> Option<Self> {
+        let file = Self::open(path).ok()?;
+        for _ in 0..LOCK_RETRIES {
+            match FileExt::try_lock_shared(&file) {
+                Ok(()) => return Some(Self { _file: file }),
+                Err(TryLockError::WouldBlock) => sleep(LOCK_RETRY_DELAY),
+                Err(TryLockError::Error(e)) => {
+                    warn!("Failed locking license cache: {}", with_path(path, e));
+                    return None;
+                }
+            }
+        }
+        warn!(
+            "License cache {:?} is still locked by another build. Reading it anyway.",
+            path
+        );
+        None
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl LicenseCache {
+    fn read(path: &Path) -> io::Result<Self> {
+        let bytes = read(path)?;
+        let cache = match bytes.split_first() {
+            Some((&CACHE_VERSION, data)) => {
+                bincode::decode_from_slice(data, bincode::config::standard())
+                    .map(|(cache, _)| cache)
+                    .map_err(|e| invalid_data(format!("Corrupted license cache: {}", e)))
+            }
+            Some((version, _)) => Err(invalid_data(format!(
+                "Unsupported license cache version: {}",
+                version
+            ))),
+            None => Err(invalid_data("Empty license cache.".to_owned())),
+        };
+        cache.map_err(|e| with_path(path, e))
+    }
+
+    fn write(&self, path: &Path) -> io::Result<()> {
+        let mut bytes = vec![CACHE_VERSION];
+        bytes.extend(bincode::encode_to_vec(self, bincode::config::standard()).unwrap());
+
+        if let Some(folder) = path.parent() {
+            create_dir_all(folder)?;
+        }
+        write_atomic(path, bytes)
+    }
+
+    /// Merges the cache into the one on disk while holding the exclusive lock.
+    ///
+    /// Entries of `self` replace those of the same package on disk.
+    fn write_locked(&self, path: &Path) -> io::Result<()> {
+        if let Some(folder) = path.parent() {
+            create_dir_all(folder)?;
+        }
+        let _lock = CacheLock::exclusive(path)?;
+        let mut cache = Self::load_unlocked(path);
+        cache
+            .entries
+            .extend(self.entries.iter().map(|(k, v)| (k.clone(), v.clone())));
+        cache.write(path)
+    }
+
+    /// Loads the cache of the user's cache directory if the [CachePolicy] reads it and fills it up with the
+    /// entries of the seed file.
+    ///
+    /// Returns the path to save the cache to, which is `None` if the policy does not write it.
+    /// Returns `None` if neither cache nor seed is configured.
+    pub(super) fn load_with_seed(config: &Config) -> Option<(Option<PathBuf>, Self)> {
+        let path = (config.cache != CachePolicy::Off)
+            .then(default_cache_path)
+            .flatten();
+        if path.is_none() && config.cache_seed_path.is_none() {
+            return None;
+        }
+
+        let mut cache = path
+            .as_deref()
+            .filter(|_| config.cache.reads())
+            .map(Self::load)
+            .unwrap_or_default();
+        if let Some(seed_path) = &config.cache_seed_path {
+            match Self::read(seed_path) {
+                Ok(seed) => {
+                    info!(
+                        "Seeding license cache with {} entries of {:?}.",
+                        seed.entries.len(),
+                        seed_path
+                    );
+                    for (key, entry) in seed.entries {
+                        cache.entries.entry(key).or_insert(entry);
+                    }
+                }
+                Err(e) => warn!("Ignoring license cache seed: {}", e),
+            }
+        }
+        Some((path.filter(|_| config.cache.writes()), cache))
+    }
+
+    /// Loads the cache, waiting for concurrent writes to finish.
+    /// A missing, outdated or corrupted cache results in an empty one.
+    pub(super) fn load(path: &Path) -> Self {
+        let _lock = CacheLock::shared(path);
+        Self::load_unlocked(path)
+    }
+
+    fn load_unlocked(path: &Path) -> Self {
+        match Self::read(path) {
+            Ok(cache) => cache,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                warn!("Ignoring license cache: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    pub(super) fn save(&self, path: &Path) {
+        match self.write_locked(path) {
+            Ok(()) => info!("Wrote license cache: {:?}", path),
+            Err(e) => warn!("Failed writing license cache: {}", e),
+        }
+    }
+
+    /// Restores the license text of a package if an entry with matching checksum exists.
+    pub(super) fn restore(
+        &self,
+        package: &mut Package,
+        checksums: &BTreeMap<String, String>,
+    ) -> bool {
+        let key = cache_key(package);
+        let (Some(entry), Some(checksum)) = (self.entries.get(&key), checksums.get(&key)) else {
+            return false;
+        };
+        if &entry.checksum != checksum {
+            warn!(
+                "Checksum of cached license of {} changed. Fetching again.",
+                key
+            );
+            return false;
+        }
+
+        package.license_text = Some(entry.license_text.as_str().into());
+        package.license_text_source = entry.license_text_source;
+        package.license_files = entry.license_files.clone();
+        package.license_translations = entry.license_translations.clone();
+        true
+    }
+
+    /// Stores the license text of a package. Packages without license text or checksum are skipped.
+    ///
+    /// Returns `true` if an entry was added or changed.
+    pub(super) fn insert(
+        &mut self,
+        package: &Package,
+        checksums: &BTreeMap<String, String>,
+    ) -> bool {
+        let key = cache_key(package);
+        let (Some(license_text), Some(checksum)) = (&package.license_text, checksums.get(&key))
+        else {
+            return false;
+        };
+
+        let entry = CacheEntry {
+            checksum: checksum.clone(),
+            license_text: license_text.to_string(),
+            license_text_source: package.license_text_source,
+            license_files: package.license_files.clone(),
+            license_translations: package.license_translations.clone(),
+            license_identifier: package.license_identifier.clone(),
+        };
+        if self.entries.get(&key) == Some(&entry) {
+            return false;
+        }
+        self.entries.insert(key, entry);
+        true
+    }
+
+    /// Compares the license identifiers of packages with those of their closest older version in the cache.
+    pub(super) fn license_changes(&self, package_list: &PackageList) -> Vec<LicenseChange> {
+        let mut changes = vec![];
+        for package in package_list.iter() {
+            let Some(license_identifier) = &package.license_identifier else {
+                continue;
+            };
+            let prefix = format!("{} ", package.name);
+            let previous = self
+                .entries
+                .range(prefix.clone()..)
+                .take_while(|(key, _)| key.starts_with(&prefix))
+                .map(|(key, entry)| (&key[prefix.len()..], entry))
+                .filter(|(version, _)| compare_versions(version, &package.version).is_lt())
+                .max_by(|(a, _), (b, _)| compare_versions(a, b));
+            let Some((previous_version, entry)) = previous else {
+                continue;
+            };
+            if let Some(previous_license_identifier) = &entry.license_identifier {
+                if previous_license_identifier != license_identifier {
+                    changes.push(LicenseChange {
+                        package_name: package.name.clone(),
+                        previous_version: previous_version.to_owned(),
+                        previous_license_identifier: previous_license_identifier.clone(),
+                        package_version: package.version.clone(),
+                        license_identifier: license_identifier.clone(),
+                    });
+                }
+            }
+        }
+        changes
+    }
+}
+
+fn cache_path() -> io::Result<PathBuf> {
+    default_cache_path().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "Failed finding the cache directory.",
+        )
+    })
+}
+
+/// Exports the license cache into a single portable file.
+///
+/// Meant for CI pipelines to store the cache as artifact. Restore it with [import].
+pub fn export(path: impl AsRef<Path>) -> io::Result<()> {
+    LicenseCache::load(&cache_path()?).write(path.as_ref())
+}
+
+/// Imports a file written by [export] into the license cache.
+///
+/// Entries of the file replace existing entries of the same package. Returns the number of imported entries.
+pub fn import(path: impl AsRef<Path>) -> io::Result<usize> {
+    let imported = LicenseCache::read(path.as_ref())?;
+    let count = imported.entries.len();
+
+    imported.write_locked(&cache_path()?)?;
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::build_script::fs::test_dir;
+    use crate::build_script::ConfigBuilder;
+
+    #[test]
+    fn test_restore_checks_checksum() {
+        let package = Package::builder()
+            .name("a")
+            .version("1.0.0")
+            .license_file("LICENSE", "MIT")
+            .build();
+        let mut checksums = BTreeMap::from([("a 1.0.0".to_owned(), "abc".to_owned())]);
+
+        let mut cache = LicenseCache::default();
+        cache.insert(&package, &checksums);
+
+        let mut restored = Package::builder().name("a").version("1.0.0").build();
+        assert!(cache.restore(&mut restored, &checksums));
+        assert_eq!(restored, package);
+
+        checksums.insert("a 1.0.0".to_owned(), "def".to_owned());
+        let mut restored = Package::builder().name("a").version("1.0.0").build();
+        assert!(!cache.restore(&mut restored, &checksums));
+        assert_eq!(restored.license_text, None);
+    }
+
+    #[test]
+    fn test_load_with_seed() {
+        let package = Package::builder()
+            .name("a")
+            .version("1.0.0")
+            .license_text("MIT")
+            .build();
+        let checksums = BTreeMap::from([("a 1.0.0".to_owned(), "abc".to_owned())]);
+        let mut seed = LicenseCache::default();
+        seed.insert(&package, &checksums);
+        let seed_path = test_dir("seed").join("seed.bincode");
+        seed.write(&seed_path).unwrap();
+
+        let config = ConfigBuilder::new(std::env::temp_dir(), "root")
+            .cache_seed_path(&seed_path)
+            .build();
+        let (path, cache) = LicenseCache::load_with_seed(&config).unwrap();
+        assert_eq!(path, None);
+        assert_eq!(cache, seed);
+
+        let config = ConfigBuilder::new(std::env::temp_dir(), "root").build();
+        assert!(LicenseCache::load_with_seed(&config).is_none());
+
+        let config = ConfigBuilder::new(std::env::temp_dir(), "root")
+            .cache(CachePolicy::ReadOnly)
+            .cache_seed_path(&seed_path)
+            .build();
+        let (path, _) = LicenseCache::load_with_seed(&config).unwrap();
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn test_insert_reports_changes() {
+        let package = Package::builder()
+            .name("a")
+            .version("1.0.0")
+            .license_text("MIT")
+            .build();
+        let checksums = BTreeMap::from([("a 1.0.0".to_owned(), "abc".to_owned())]);
+
+        let mut cache = LicenseCache::default();
+        assert!(cache.insert(&package, &checksums));
+        assert!(!cache.insert(&package, &checksums));
+        assert!(!cache.insert(&Package::builder().name("b").build(), &checksums));
+    }
+
+    #[test]
+    fn test_concurrent_save_and_load() {
+        let path = test_dir("locked-cache").join("cache.bincode");
+        let mut cache = LicenseCache::default();
+        cache.insert(
+            &Package::builder()
+                .name("a")
+                .version("1.0.0")
+                .license_text("MIT")
+                .build(),
+            &BTreeMap::from([("a 1.0.0".to_owned(), "abc".to_owned())]),
+        );
+        cache.save(&path);
+
+        let lock = CacheLock::exclusive(&path).unwrap();
+        let reader = std::thread::spawn({
+            let path = path.clone();
+            move || LicenseCache::load(&path)
+        });
+        sleep(LOCK_RETRY_DELAY * 2);
+        drop(lock);
+        assert_eq!(reader.join().unwrap(), cache);
+
+        let writers: Vec<_> = (0..4)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || LicenseCache::default().write_locked(&path))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+        assert_eq!(LicenseCache::load(&path), cache);
+    }
+
+    #[test]
+    fn test_concurrent_saves_merge() {
+        let path = test_dir("merged-cache").join("cache.bincode");
+        let cache_of = |name: &'static str| {
+            let mut cache = LicenseCache::default();
+            for version in ["1.0.0", "2.0.0"] {
+                cache.insert(
+                    &Package::builder()
+                        .name(name)
+                        .version(version)
+                        .license_text("MIT")
+                        .build(),
+                    &BTreeMap::from([(format!("{} {}", name, version), "abc".to_owned())]),
+                );
+            }
+            cache
+        };
+
+        let writers: Vec<_> = ["a", "b"]
+            .into_iter()
+            .map(|name| {
+                let path = path.clone();
+                std::thread::spawn(move || cache_of(name).write_locked(&path))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+
+        let mut expected = cache_of("a");
+        expected.entries.extend(cache_of("b").entries);
+        assert_eq!(LicenseCache::load(&path), expected);
+    }
+
+    #[test]
+    fn test_license_changes() {
+        let package = |version: &str, license: &str| {
+            Package::builder()
+                .name("a")
+                .version(version)
+                .license_identifier(license)
+                .license_text(license)
+                .build()
+        };
+        let checksums = BTreeMap::from([
+            ("a 1.0.0".to_owned(), "abc".to_owned()),
+            ("a 1.9.0".to_owned(), "def".to_owned()),
+        ]);
+        let mut cache = LicenseCache::default();
+        cache.insert(&package("1.0.0", "GPL-3.0-only"), &checksums);
+        cache.insert(&package("1.9.0", "MIT"), &checksums);
+
+        let changes = cache.license_changes(&PackageList(vec![package("1.10.0", "BUSL-1.1")]));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].previous_version, "1.9.0");
+        assert_eq!(changes[0].previous_license_identifier, "MIT");
+
+        assert!(cache
+            .license_changes(&PackageList(vec![package("1.10.0", "MIT")]))
+            .is_empty());
+    }
+}
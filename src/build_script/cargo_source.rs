@@ -3,28 +3,42 @@
 //         (See accompanying file LICENSE or copy at
 //          https://www.boost.org/LICENSE_1_0.txt)
 
+use std::collections::BTreeSet;
 use std::env::var_os;
-use std::fs::{read_dir, read_to_string};
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::fs::{metadata, read_dir, read_to_string};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
 
 use directories::BaseDirs;
-use log::{info, trace, warn};
+use log::{debug, info, warn};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use spdx::{Expression, ParseMode};
 
-use crate::PackageList;
+use super::build_info::find_lockfile;
+use super::cache::lockfile_checksums;
+use super::config::{Config, FetchBackend};
+use super::fs::{simplified, with_path};
+use super::report::{FetchFailure, FetchReport, SourceWarning, SourceWarningKind};
+use super::scan::{folder_licenses, ScanOptions};
+use crate::error::ErrorCode;
+use crate::{LicenseFile, LicenseTextSource, LicenseTranslation, Package, PackageList};
 
 fn cargo_folder() -> PathBuf {
     if let Some(path) = var_os("CARGO_HOME") {
-        path.into()
+        simplified(Path::new(&path))
     } else {
-        let base_dir = BaseDirs::new().expect("Failed to find home dir.");
+        let base_dir = BaseDirs::new()
+            .unwrap_or_else(|| panic!("{}: Failed to find home dir.", ErrorCode::CargoHomeMissing));
         let home_dir = base_dir.home_dir();
         let mut cargo_dir = home_dir.to_path_buf();
         cargo_dir.push(".cargo");
         if !cargo_dir.exists() {
             panic!(
-                "Failed finding cargo dir: {:#?}. Set it manually with CARGO_HOME variable.",
+                "{}: Failed finding cargo dir: {:#?}. Set it manually with CARGO_HOME variable.",
+                ErrorCode::CargoHomeMissing,
                 &cargo_dir
             );
         }
@@ -32,75 +46,711 @@ fn cargo_folder() -> PathBuf {
     }
 }
 
-fn src_registry_folders(path: PathBuf) -> Vec<PathBuf> {
-    let src_subfolder = PathBuf::from("registry/src");
-    let src_dir = path.join(src_subfolder);
-    read_dir(src_dir)
-        .expect("Src path is not a dir.")
+/// Source folders of all registries in a cargo home.
+pub(super) fn src_registry_folders(path: &Path) -> io::Result<Vec<PathBuf>> {
+    // Joined separately, as verbatim Windows paths do not accept `/` as separator.
+    let src_dir = simplified(path).join("registry").join("src");
+    Ok(read_dir(&src_dir)
+        .map_err(|e| with_path(&src_dir, e))?
         .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().map_or(false, |ft| ft.is_dir()))
         .map(|e| e.path())
-        .collect()
+        .filter(|path| path.is_dir())
+        .collect())
 }
 
-pub(super) fn license_text_from_folder(path: &PathBuf) -> Option<String> {
-    trace!("Fetching license in folder: {:?}", &path);
+/// Lists the files (or folders with `dirs`) of a folder whose name matches `filter`.
+///
+/// [FetchBackend::Std] stats every entry before matching the name, while [FetchBackend::WalkDir]
+/// matches the name first and takes the file type from the directory listing.
+///
+/// With `follow_symlinks` the type of the symlink target counts. Broken symlinks and symlink loops are skipped.
+pub(super) fn list_folder(
+    path: &Path,
+    backend: FetchBackend,
+    dirs: bool,
+    follow_symlinks: bool,
+    filter: impl Fn(&str) -> bool,
+) -> io::Result<Vec<PathBuf>> {
+    let mut paths = vec![];
 
-    let entries = read_dir(&path).unwrap();
+    match backend {
+        FetchBackend::Std => {
+            for entry in read_dir(path)?.flatten() {
+                let metadata = if follow_symlinks {
+                    metadata(entry.path())
+                } else {
+                    entry.metadata()
+                };
+                let matches_type = match metadata {
+                    Ok(metadata) if dirs => metadata.is_dir(),
+                    Ok(metadata) => metadata.is_file(),
+                    Err(e) => {
+                        debug!("Skipping {:?}: {}", entry.path(), e);
+                        false
+                    }
+                };
+                if matches_type && filter(&entry.file_name().to_string_lossy()) {
+                    paths.push(entry.path());
+                }
+            }
+        }
+        FetchBackend::WalkDir => {
+            // Walkdir detects symlink loops and yields them as errors, which are skipped below.
+            let walker = walkdir::WalkDir::new(path)
+                .min_depth(1)
+                .max_depth(1)
+                .follow_links(follow_symlinks)
+                .into_iter()
+                .filter_entry(|entry| filter(&entry.file_name().to_string_lossy()));
+            for entry in walker {
+                match entry {
+                    Ok(entry) if entry.file_type().is_dir() == dirs => {
+                        if dirs || entry.file_type().is_file() {
+                            paths.push(entry.into_path());
+                        }
+                    }
+                    Ok(_) => {}
+                    // Failing to read the folder itself is an error, unreadable entries are skipped.
+                    Err(e) if e.depth() == 0 => return Err(e.into()),
+                    Err(_) => {}
+                }
+            }
+        }
+    }
 
-    static LICENSE_FILE_NAME_REGEX: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"(?i).*(license|copying|authors|notice|eula).*").unwrap());
+    Ok(paths)
+}
 
-    let mut potential_license_files = vec![];
+/// Source folders of all registries in the cargo home.
+pub(super) fn registry_src_folders() -> Vec<PathBuf> {
+    src_registry_folders(&cargo_folder()).unwrap_or_else(|e| {
+        panic!(
+            "{}: Src path is not a dir: {}",
+            ErrorCode::RegistryMissing,
+            e
+        )
+    })
+}
 
-    for entry in entries {
-        if let Ok(entry) = entry {
-            if let Ok(metadata) = entry.metadata() {
-                if !metadata.is_file() {
-                    continue;
-                }
-                if LICENSE_FILE_NAME_REGEX.is_match(&entry.file_name().to_string_lossy()) {
-                    potential_license_files.push(entry.path());
-                }
+/// Fetches the licenses of packages replaced via `[patch]` or `[replace]` from the folder of their manifest.
+///
+/// The registry may contain the original package with the same name and version, so it is not used for these.
+pub(super) fn licenses_text_from_patched_sources(
+    package_list: &mut PackageList,
+    config: &Config,
+    report: &mut FetchReport,
+) {
+    for package in package_list.iter_mut() {
+        if !package.patched || package.license_text.is_some() {
+            continue;
+        }
+        let Some(folder) = package
+            .manifest_path
+            .as_deref()
+            .and_then(|path| Path::new(path).parent())
+            .map(Path::to_path_buf)
+        else {
+            continue;
+        };
+        info!(
+            "Fetching license of patched package {} from: {:?}",
+            &package.name, &folder
+        );
+        fetch_license_for_package(package, &folder, config, report);
+    }
+}
+
+/// Registry packages of the `Cargo.lock` that are not extracted in any registry source folder,
+/// formatted as `name version`. These would have to be downloaded.
+///
+/// Returns `None` if there is no `Cargo.lock`.
+pub(super) fn packages_missing_locally(manifest_dir: &Path) -> Option<Vec<String>> {
+    find_lockfile(manifest_dir)?;
+    let src_folders = registry_src_folders();
+    let missing = lockfile_checksums(manifest_dir)
+        .into_keys()
+        .filter(|key| {
+            let folder_name = key.replacen(' ', "-", 1);
+            !src_folders
+                .iter()
+                .any(|folder| folder.join(&folder_name).is_dir())
+        })
+        .collect();
+    Some(missing)
+}
+
+/// Finds packages with pre-release versions and packages whose manifest is outside the cargo registry.
+///
+/// The package itself is skipped. Packages without known manifest path are not checked against the registry.
+pub(super) fn source_warnings(package_list: &PackageList, config: &Config) -> Vec<SourceWarning> {
+    let src_folders = registry_src_folders();
+    let mut warnings = vec![];
+    for package in package_list.iter() {
+        if package.name == config.package_name {
+            continue;
+        }
+        let warning = |kind| SourceWarning {
+            package_name: package.name.clone(),
+            package_version: package.version.clone(),
+            kind,
+        };
+
+        let without_build_metadata = package.version.split('+').next().unwrap_or_default();
+        if without_build_metadata.contains('-') {
+            warnings.push(warning(SourceWarningKind::PreRelease));
+        }
+        if let Some(manifest_path) = &package.manifest_path {
+            let manifest_path = simplified(Path::new(manifest_path));
+            if !src_folders
+                .iter()
+                .any(|folder| manifest_path.starts_with(folder))
+            {
+                warnings.push(warning(SourceWarningKind::NotInRegistry));
             }
         }
     }
+    warnings
+}
 
-    let mut license_text_vec = vec![];
+fn license_text_from_folder(
+    path: &PathBuf,
+    config: &Config,
+    failures: &mut Vec<(PathBuf, io::Error)>,
+) -> Option<(String, Vec<LicenseFile>, Vec<LicenseTranslation>)> {
+    let options = ScanOptions::default()
+        .fetch_backend(config.fetch_backend)
+        .follow_symlinks(config.follow_symlinks);
 
-    for license_file in potential_license_files {
-        if let Ok(license_text) = read_to_string(license_file) {
-            license_text_vec.push(license_text);
+    let licenses = match folder_licenses(path, &options) {
+        Ok(licenses) => licenses,
+        Err(e) => {
+            warn!("Failed reading folder {:?}: {}", &path, e);
+            failures.push((path.clone(), e));
+            return None;
         }
+    };
+
+    for (license_file, e) in &licenses.unreadable {
+        warn!("Failed reading license file {:?}: {}", license_file, e);
     }
+    failures.extend(licenses.unreadable);
 
-    if license_text_vec.is_empty() {
+    if licenses.license_files.is_empty() {
         warn!("Found no licenses in folder: {:?}", &path);
         return None;
     }
 
-    Some(license_text_vec.join("\n\n"))
+    Some((
+        licenses.license_text,
+        licenses.license_files,
+        licenses.license_translations,
+    ))
 }
 
-pub(super) fn licenses_text_from_cargo_src_folder(package_list: &mut PackageList) {
-    for src_folder in src_registry_folders(cargo_folder()) {
-        info!("src folder: {:?}", &src_folder);
+/// Standard texts of the licenses of an SPDX expression, separated by empty lines.
+///
+/// Licenses missing in the SPDX license list, like `LicenseRef-` identifiers, are left out.
+/// Returns `None` if no license is known.
+fn license_text_from_spdx_list(expression: &str) -> Option<String> {
+    let expression = Expression::parse_mode(expression, ParseMode::LAX).ok()?;
+    let mut ids = vec![];
+    for id in expression
+        .requirements()
+        .filter_map(|req| req.req.license.id())
+    {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    let texts: Vec<_> = ids.into_iter().map(|id| id.text().trim()).collect();
+    (!texts.is_empty()).then(|| texts.join("\n\n"))
+}
+
+/// Scans the head of `.rs` files in the package root and its `src` folder for
+/// `SPDX-License-Identifier:` tags.
+///
+/// Last resort for crates that ship neither a license file nor a license field. Their license text is taken
+/// from the SPDX license list with [license_text_from_spdx_list], without copyright notices.
+/// Multiple differing identifiers are joined with `AND`.
+fn license_identifier_from_source_headers(path: &Path) -> Option<String> {
+    const HEADER_LINES: usize = 20;
 
-        for folder in read_dir(src_folder)
-            .expect("Failed reading source folder.")
+    static SPDX_HEADER_REGEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"SPDX-License-Identifier:\s*(.+?)\s*(?:\*/)?\s*$").unwrap());
+
+    let mut identifiers = BTreeSet::new();
+
+    for folder in [path.to_path_buf(), path.join("src")] {
+        let Ok(entries) = read_dir(&folder) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_path = entry.path();
+            if !file_path.is_file() || file_path.extension().is_none_or(|ext| ext != "rs") {
+                continue;
+            }
+            let Ok(source) = read_to_string(&file_path) else {
+                continue;
+            };
+            for line in source.lines().take(HEADER_LINES) {
+                if let Some(captures) = SPDX_HEADER_REGEX.captures(line) {
+                    identifiers.insert(captures[1].trim().to_owned());
+                }
+            }
+        }
+    }
+
+    if identifiers.is_empty() {
+        return None;
+    }
+
+    info!("Found SPDX headers in folder: {:?}", path);
+
+    if identifiers.len() == 1 {
+        return identifiers.pop_first();
+    }
+
+    Some(
+        identifiers
             .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.path().is_dir())
-            .map(|e| e.path())
+            .map(|id| format!("({})", id))
+            .collect::<Vec<_>>()
+            .join(" AND "),
+    )
+}
+
+/// Extracts the `License` section of `README` files in a folder.
+///
+/// The section spans from a heading containing `License` or `Licence` to the next heading of the same or a higher level.
+fn license_text_from_readme(path: &Path) -> Option<String> {
+    static README_FILE_NAME_REGEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?i)^readme(\..*)?$").unwrap());
+    static LICENSE_HEADING_REGEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?i)^(#{1,6})\s*licen[cs](e|es|ing)\b").unwrap());
+    static HEADING_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(#{1,6})\s").unwrap());
+
+    let entries = read_dir(path).ok()?;
+
+    for entry in entries.flatten() {
+        if !README_FILE_NAME_REGEX.is_match(&entry.file_name().to_string_lossy())
+            || !entry.path().is_file()
         {
-            let folder_name = folder.as_path().iter().last().unwrap().to_str().unwrap();
-            for package in package_list.iter_mut().filter(|p| p.license_text.is_none()) {
-                if folder_name.starts_with(&package.name) && folder_name.ends_with(&package.version)
-                {
-                    info!("Fetching license for: {}", &package.name);
-                    package.license_text = license_text_from_folder(&folder);
+            continue;
+        }
+        let Ok(readme) = read_to_string(entry.path()) else {
+            continue;
+        };
+
+        let mut section: Option<(usize, Vec<&str>)> = None;
+        for line in readme.lines() {
+            match &mut section {
+                None => {
+                    if let Some(captures) = LICENSE_HEADING_REGEX.captures(line) {
+                        section = Some((captures[1].len(), vec![]));
+                    }
+                }
+                Some((level, lines)) => {
+                    if let Some(captures) = HEADING_REGEX.captures(line) {
+                        if captures[1].len() <= *level {
+                            break;
+                        }
+                    }
+                    lines.push(line);
+                }
+            }
+        }
+
+        if let Some((_, lines)) = section {
+            let text = lines.join("\n").trim().to_owned();
+            if !text.is_empty() {
+                warn!("Using license section of README in folder: {:?}", path);
+                return Some(text);
+            }
+        }
+    }
+
+    None
+}
+
+/// Fills license text and, if missing, license identifier of a package from its source folder.
+///
+/// Tries license files first, then falls back to the README (if enabled) and SPDX source headers.
+/// Licenses of SPDX headers get their standard text from the SPDX license list, without copyright notices.
+///
+/// Read errors are recorded in the report and do not abort fetching.
+pub(super) fn fetch_license_for_package(
+    package: &mut Package,
+    folder: &PathBuf,
+    config: &Config,
+    report: &mut FetchReport,
+) {
+    let mut failures = vec![];
+
+    (
+        package.license_text,
+        package.license_files,
+        package.license_translations,
+    ) = match license_text_from_folder(folder, config, &mut failures) {
+        Some((text, files, translations)) => (Some(text.into()), files, translations),
+        None => (None, vec![], vec![]),
+    };
+    package.license_text_source = package
+        .license_text
+        .as_ref()
+        .map(|_| LicenseTextSource::LicenseFiles);
+
+    if package.license_text.is_none() && config.readme_fallback {
+        package.license_text = license_text_from_readme(folder).map(Into::into);
+        package.license_text_source = package
+            .license_text
+            .as_ref()
+            .map(|_| LicenseTextSource::Readme);
+    }
+
+    if package.license_text.is_none() && package.license_identifier.is_none() {
+        package.license_identifier = license_identifier_from_source_headers(folder);
+        package.license_text = package
+            .license_identifier
+            .as_deref()
+            .and_then(license_text_from_spdx_list)
+            .map(Into::into);
+        package.license_text_source = package
+            .license_text
+            .as_ref()
+            .map(|_| LicenseTextSource::SpdxHeader);
+    }
+
+    report
+        .failures
+        .extend(failures.into_iter().map(|(path, cause)| FetchFailure {
+            package_name: package.name.clone(),
+            package_version: package.version.clone(),
+            path,
+            cause,
+        }));
+}
+
+/// Name of the folder of a package in the registry, like `log-0.4.22`.
+fn package_folder_name(package: &Package) -> String {
+    format!("{}-{}", package.name, package.version)
+}
+
+/// Returns `true` if a registry folder belongs to the package.
+///
+/// Case is ignored, as crate names are case insensitive and folders may be renamed on case-insensitive file systems.
+/// Crate names and versions are ASCII, so no Unicode normalization is needed.
+fn is_package_folder(folder_name: &str, package: &Package) -> bool {
+    folder_name.eq_ignore_ascii_case(&package_folder_name(package))
+}
+
+/// Up to this many packages without license text, their folders are looked up directly
+/// instead of listing the whole registry.
+const DIRECT_LOOKUP_THRESHOLD: usize = 64;
+
+pub(super) fn licenses_text_from_cargo_src_folder(
+    package_list: &mut PackageList,
+    config: &Config,
+    report: &mut FetchReport,
+) {
+    licenses_text_from_src_folders(package_list, &registry_src_folders(), config, report);
+}
+
+/// Fetches the licenses of packages from the given registry source folders.
+fn licenses_text_from_src_folders(
+    package_list: &mut PackageList,
+    src_folders: &[PathBuf],
+    config: &Config,
+    report: &mut FetchReport,
+) {
+    let mut candidate_folders: Vec<Vec<PathBuf>> = vec![vec![]; package_list.len()];
+    let wanted = |package: &Package| package.license_text.is_none() && !package.patched;
+
+    let needed = package_list
+        .iter()
+        .filter(|package| package.license_text.is_none())
+        .count();
+
+    if needed <= DIRECT_LOOKUP_THRESHOLD {
+        for src_folder in src_folders.iter() {
+            info!("src folder: {:?}", &src_folder);
+            for (package, candidates) in package_list.iter().zip(candidate_folders.iter_mut()) {
+                if !wanted(package) {
+                    continue;
+                }
+                let folder = src_folder.join(package_folder_name(package));
+                if folder.is_dir() {
+                    candidates.push(folder);
                 }
             }
         }
     }
+
+    // Packages not found directly may still be present with different case on case-sensitive file systems.
+    // Packages whose manifest is known to be outside the registry, like the package itself, are not searched.
+    let in_registry = |package: &Package| {
+        package.manifest_path.as_deref().is_none_or(|path| {
+            let path = simplified(Path::new(path));
+            src_folders.iter().any(|folder| path.starts_with(folder))
+        })
+    };
+    let listing_needed =
+        package_list
+            .iter()
+            .zip(candidate_folders.iter())
+            .any(|(package, candidates)| {
+                wanted(package) && candidates.is_empty() && in_registry(package)
+            });
+    if listing_needed {
+        for src_folder in src_folders.iter() {
+            info!("Listing src folder: {:?}", &src_folder);
+            for folder in list_folder(
+                src_folder,
+                config.fetch_backend,
+                true,
+                config.follow_symlinks,
+                |_| true,
+            )
+            .unwrap_or_else(|e| {
+                panic!(
+                    "{}: Failed reading source folder: {}",
+                    ErrorCode::RegistryMissing,
+                    e
+                )
+            }) {
+                let Some(folder_name) = folder.file_name().and_then(OsStr::to_str) else {
+                    continue;
+                };
+                for (package, candidates) in package_list.iter().zip(candidate_folders.iter_mut()) {
+                    if wanted(package)
+                        && !candidates.contains(&folder)
+                        && is_package_folder(folder_name, package)
+                    {
+                        candidates.push(folder.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    let mut jobs: Vec<(&mut Package, Vec<PathBuf>)> = package_list
+        .iter_mut()
+        .zip(candidate_folders)
+        .filter(|(_, candidates)| !candidates.is_empty())
+        .collect();
+    if jobs.is_empty() {
+        return;
+    }
+
+    let threads = config.parallelism().min(jobs.len());
+    let chunk_size = jobs.len().div_ceil(threads);
+    info!("Fetching {} licenses with {} threads.", jobs.len(), threads);
+
+    let reports: Vec<FetchReport> = thread::scope(|scope| {
+        let handles: Vec<_> = jobs
+            .chunks_mut(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut report = FetchReport::default();
+                    for (package, candidates) in chunk {
+                        for folder in candidates.iter() {
+                            if package.license_text.is_some() {
+                                break;
+                            }
+                            info!("Fetching license for: {}", &package.name);
+                            fetch_license_for_package(package, folder, config, &mut report);
+                        }
+                    }
+                    report
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("License fetching thread panicked."))
+            .collect()
+    });
+
+    for thread_report in reports {
+        report.failures.extend(thread_report.failures);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::build_script::fs::test_dir;
+    use crate::build_script::ConfigBuilder;
+
+    #[test]
+    fn test_unreadable_license_file_is_reported() {
+        let dir = test_dir("unreadable-license");
+        std::fs::write(dir.join("LICENSE-MIT"), "MIT License").unwrap();
+        std::fs::write(dir.join("LICENSE-BROKEN"), [0xff, 0xfe, 0xfd]).unwrap();
+
+        let config = ConfigBuilder::new(&dir, "a").build();
+        let mut package = Package::builder().name("a").version("1.0.0").build();
+        let mut report = FetchReport::default();
+        fetch_license_for_package(&mut package, &dir, &config, &mut report);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(package.license_text.as_deref(), Some("MIT License"));
+        assert!(!report.is_clean());
+        assert_eq!(report.failures.len(), 1);
+        let failure = &report.failures[0];
+        assert_eq!(
+            (
+                failure.package_name.as_str(),
+                failure.package_version.as_str()
+            ),
+            ("a", "1.0.0")
+        );
+        assert_eq!(failure.path, dir.join("LICENSE-BROKEN"));
+        assert_eq!(failure.cause.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_spdx_header_fallback() {
+        let dir = test_dir("spdx-header");
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(
+            dir.join("src/lib.rs"),
+            "// SPDX-License-Identifier: MIT OR LicenseRef-Custom\n\npub fn f() {}\n",
+        )
+        .unwrap();
+
+        let config = ConfigBuilder::new(&dir, "a").build();
+        let mut package = Package {
+            name: "a".to_owned(),
+            version: "1.0.0".to_owned(),
+            ..Default::default()
+        };
+        let mut report = FetchReport::default();
+        fetch_license_for_package(&mut package, &dir, &config, &mut report);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            package.license_identifier.as_deref(),
+            Some("MIT OR LicenseRef-Custom")
+        );
+        assert_eq!(
+            package.license_text_source,
+            Some(LicenseTextSource::SpdxHeader)
+        );
+        let license_text = package.license_text.unwrap();
+        assert!(license_text.starts_with("MIT License"));
+        assert!(license_text.contains("Permission is hereby granted"));
+        assert!(report.failures.is_empty());
+
+        assert_eq!(license_text_from_spdx_list("LicenseRef-Custom"), None);
+        assert!(license_text_from_spdx_list("(MIT AND Apache-2.0+)")
+            .unwrap()
+            .contains("Apache License"));
+    }
+
+    #[test]
+    fn test_direct_folder_lookup() {
+        let dir = test_dir("direct-lookup");
+        let src_folders = [dir.join("index.crates.io-0000000000000000")];
+        let src_folder = &src_folders[0];
+        for (folder, license) in [("direct-1.0.0", "MIT"), ("listed-1.0.0", "Apache")] {
+            std::fs::create_dir_all(src_folder.join(folder)).unwrap();
+            std::fs::write(src_folder.join(folder).join("LICENSE"), license).unwrap();
+        }
+        let outside = dir.join("outside").join("Cargo.toml");
+        let config = ConfigBuilder::new(&dir, "direct").build();
+
+        // Packages with a manifest outside the registry are only found by the direct lookup.
+        let mut package_list = PackageList(vec![
+            Package::builder()
+                .name("direct")
+                .version("1.0.0")
+                .manifest_path(outside.to_string_lossy())
+                .build(),
+            Package::builder()
+                .name("Listed")
+                .version("1.0.0")
+                .manifest_path(outside.to_string_lossy())
+                .build(),
+        ]);
+        let mut report = FetchReport::default();
+        licenses_text_from_src_folders(&mut package_list, &src_folders, &config, &mut report);
+        assert_eq!(package_list[0].license_text.as_deref(), Some("MIT"));
+        assert_eq!(package_list[1].license_text, None);
+
+        // Folders differing in case are found by listing the source folder.
+        let mut package_list = PackageList(vec![Package::builder()
+            .name("Listed")
+            .version("1.0.0")
+            .build()]);
+        licenses_text_from_src_folders(&mut package_list, &src_folders, &config, &mut report);
+        assert_eq!(package_list[0].license_text.as_deref(), Some("Apache"));
+
+        // Above the threshold the source folders are listed instead.
+        let mut packages: Vec<Package> = (0..DIRECT_LOOKUP_THRESHOLD)
+            .map(|i| {
+                Package::builder()
+                    .name(format!("missing{}", i))
+                    .version("1.0.0")
+                    .build()
+            })
+            .collect();
+        packages.push(
+            Package::builder()
+                .name("Listed")
+                .version("1.0.0")
+                .manifest_path(outside.to_string_lossy())
+                .build(),
+        );
+        let mut package_list = PackageList(packages);
+        licenses_text_from_src_folders(&mut package_list, &src_folders, &config, &mut report);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            package_list[DIRECT_LOOKUP_THRESHOLD]
+                .license_text
+                .as_deref(),
+            Some("Apache")
+        );
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn test_is_package_folder() {
+        let package = Package::builder()
+            .name("Inflector")
+            .version("0.11.4")
+            .build();
+        assert!(is_package_folder("Inflector-0.11.4", &package));
+        assert!(is_package_folder("inflector-0.11.4", &package));
+        assert!(!is_package_folder("inflector-0.11.40", &package));
+        assert!(!is_package_folder("inflector-extra-0.11.4", &package));
+
+        let package = Package::builder()
+            .name("log")
+            .version("1.0.0+Build")
+            .build();
+        assert!(is_package_folder("LOG-1.0.0+build", &package));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_list_folder_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let dir = test_dir("symlinks");
+        std::fs::write(dir.join("original"), "MIT").unwrap();
+        symlink(dir.join("original"), dir.join("LICENSE")).unwrap();
+        symlink(dir.join("missing"), dir.join("LICENSE-BROKEN")).unwrap();
+        symlink(dir.join("LICENSE-LOOP"), dir.join("LICENSE-LOOP")).unwrap();
+
+        for backend in [FetchBackend::Std, FetchBackend::WalkDir] {
+            let followed = list_folder(&dir, backend, false, true, |name| name.contains("LICENSE"));
+            assert_eq!(followed.unwrap(), [dir.join("LICENSE")]);
+
+            let not_followed =
+                list_folder(&dir, backend, false, false, |name| name.contains("LICENSE"));
+            assert!(not_followed.unwrap().is_empty());
+        }
+    }
 }
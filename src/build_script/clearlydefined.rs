@@ -0,0 +1,146 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Enrichment of license identifiers with the [ClearlyDefined](https://clearlydefined.io) API.
+//!
+//! Requests are made with `curl`, so no HTTP client ends up in the build dependencies.
+//! Responses are cached in the user's cache directory.
+
+use std::fs::{create_dir_all, read, write};
+use std::path::PathBuf;
+use std::process::Command;
+
+use directories::ProjectDirs;
+use log::{info, warn};
+use serde::Deserialize;
+
+use crate::spdx::Expression;
+use crate::{Package, PackageList};
+
+const API_URL: &str = "https://api.clearlydefined.io/definitions/crate/cratesio/-";
+
+#[derive(Deserialize, Debug, Default)]
+struct Definition {
+    #[serde(default)]
+    licensed: Licensed,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct Licensed {
+    declared: Option<String>,
+    #[serde(default)]
+    score: Score,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct Score {
+    #[serde(default)]
+    total: u32,
+}
+
+impl Definition {
+    /// Returns the declared license, unless ClearlyDefined could not determine one.
+    fn declared_license(&self) -> Option<&str> {
+        self.licensed
+            .declared
+            .as_deref()
+            .filter(|declared| !matches!(*declared, "NOASSERTION" | "NONE" | "OTHER"))
+    }
+}
+
+fn cache_folder() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "license-fetcher").map(|dirs| dirs.cache_dir().join("clearlydefined"))
+}
+
+fn fetch_definition(package: &Package) -> Option<Definition> {
+    let cache_file = cache_folder()
+        .map(|folder| folder.join(format!("{}-{}.json", package.name, package.version)));
+
+    if let Some(cached) = cache_file.as_ref().and_then(|file| read(file).ok()) {
+        return serde_json::from_slice(&cached).ok();
+    }
+
+    let url = format!("{}/{}/{}", API_URL, package.name, package.version);
+    info!("Querying ClearlyDefined: {}", &url);
+
+    let output = match Command::new("curl")
+        .args(["--silent", "--fail", "--location", "--max-time", "30", &url])
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            warn!(
+                "Failed querying ClearlyDefined for {} {}: {}",
+                package.name,
+                package.version,
+                String::from_utf8_lossy(&output.stderr)
+            );
+            return None;
+        }
+        Err(e) => {
+            warn!("Failed executing curl: {}", e);
+            return None;
+        }
+    };
+
+    let definition = serde_json::from_slice(&output.stdout).ok()?;
+
+    if let Some(cache_file) = cache_file {
+        if let Some(folder) = cache_file.parent() {
+            let _ = create_dir_all(folder);
+        }
+        if let Err(e) = write(&cache_file, &output.stdout) {
+            warn!(
+                "Failed caching ClearlyDefined response {:?}: {}",
+                cache_file, e
+            );
+        }
+    }
+
+    Some(definition)
+}
+
+/// Fills missing or unparsable license identifiers with the license declared on ClearlyDefined.
+pub(super) fn enrich_package_list(package_list: &mut PackageList) {
+    for package in package_list.iter_mut() {
+        let low_confidence = package
+            .license_identifier
+            .as_deref()
+            .is_none_or(|id| Expression::parse(id).is_none());
+        if !low_confidence {
+            continue;
+        }
+
+        let Some(definition) = fetch_definition(package) else {
+            continue;
+        };
+        if let Some(declared) = definition.declared_license() {
+            info!(
+                "Using license {} (score {}) of ClearlyDefined for {} {}",
+                declared, definition.licensed.score.total, package.name, package.version
+            );
+            package.license_identifier = Some(declared.to_owned());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_definition() {
+        let definition: Definition = serde_json::from_str(
+            r#"{"licensed": {"declared": "MIT OR Apache-2.0", "score": {"total": 87}}}"#,
+        )
+        .unwrap();
+        assert_eq!(definition.declared_license(), Some("MIT OR Apache-2.0"));
+        assert_eq!(definition.licensed.score.total, 87);
+
+        let definition: Definition =
+            serde_json::from_str(r#"{"licensed": {"declared": "NOASSERTION"}}"#).unwrap();
+        assert_eq!(definition.declared_license(), None);
+    }
+}
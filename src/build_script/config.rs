@@ -0,0 +1,1008 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use std::env::{var, var_os};
+use std::ffi::OsString;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread::available_parallelism;
+
+use log::warn;
+
+use super::backend::LicenseSourceBackend;
+use super::sidecar::Output;
+use crate::Package;
+
+/// Default of [Config::metadata_size_warning].
+const DEFAULT_METADATA_SIZE_WARNING: u64 = 100 * 1024 * 1024;
+
+/// Replaces the license identifier of matching packages.
+///
+/// Used to clarify crates with missing or wrong license metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LicenseOverride {
+    pub name: String,
+    /// Exact version to match. `None` matches all versions.
+    pub version: Option<String>,
+    /// SPDX license expression to use instead.
+    pub expression: String,
+}
+
+impl LicenseOverride {
+    pub fn new(
+        name: impl Into<String>,
+        version: Option<String>,
+        expression: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            version,
+            expression: expression.into(),
+        }
+    }
+
+    pub(super) fn matches(&self, name: &str, version: &str) -> bool {
+        self.name == name && self.version.as_deref().is_none_or(|v| v == version)
+    }
+}
+
+/// Replaces the license text of a package, see [ConfigBuilder::override_license].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LicenseTextOverride {
+    /// A single file. Relative paths are resolved against the manifest dir.
+    File(PathBuf),
+    /// A fixed text.
+    Text(String),
+}
+
+/// Implementation used for listing folders of the cargo registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum FetchBackend {
+    /// [std::fs::read_dir] with a `stat` call per entry.
+    #[default]
+    Std,
+    /// [walkdir](https://docs.rs/walkdir), which takes the file type from the directory listing
+    /// and filters entries by name before looking at them.
+    WalkDir,
+}
+
+/// License text embedded for the package whose dependencies are fetched.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum RootLicense {
+    /// Every license like file in the manifest dir, same as for dependencies.
+    #[default]
+    Auto,
+    /// A single file. Relative paths are resolved against the manifest dir.
+    File(PathBuf),
+    /// A fixed text.
+    Text(String),
+    /// No license text.
+    Skip,
+}
+
+/// Whether the license fetching may access the network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum NetworkPolicy {
+    /// Cargo may update the index and download packages if `--frozen` fails.
+    #[default]
+    Allowed,
+    /// Cargo is only called with `--frozen` and features querying web services are disabled.
+    ///
+    /// Meant for private registries, where network access may trigger authentication.
+    Forbidden,
+}
+
+/// Order of the dependencies in the package list. The package itself always stays first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum SortOrder {
+    /// By name and then version.
+    NameVersion,
+    /// By license identifier, then name and version. Packages without license identifier come last.
+    License,
+    /// Order of `cargo metadata`.
+    #[default]
+    None,
+}
+
+/// Use of the license cache in the user's cache directory, see [ConfigBuilder::cache].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum CachePolicy {
+    /// Restores license texts from the cache and writes it after fetching.
+    ReadWrite,
+    /// Restores license texts from the cache, but never writes it. For local builds sharing a cache with others.
+    ReadOnly,
+    /// Fetches all license texts and writes a cache with only these entries.
+    /// For CI producing a cache artifact without reading possibly stale data.
+    WriteOnly,
+    /// No cache.
+    #[default]
+    Off,
+    /// Like [Self::ReadWrite], but the cache is only written if entries were added or changed.
+    /// Keeps the file untouched if no dependency changed, e.g. to skip uploading a CI artifact.
+    RefreshChanged,
+}
+
+impl CachePolicy {
+    /// Whether license texts are restored from the cache.
+    pub(super) fn reads(self) -> bool {
+        matches!(
+            self,
+            Self::ReadWrite | Self::ReadOnly | Self::RefreshChanged
+        )
+    }
+
+    /// Whether the cache is written after fetching.
+    pub(super) fn writes(self) -> bool {
+        matches!(
+            self,
+            Self::ReadWrite | Self::WriteOnly | Self::RefreshChanged
+        )
+    }
+}
+
+/// `true` is [CachePolicy::ReadWrite] and `false` is [CachePolicy::Off].
+impl From<bool> for CachePolicy {
+    fn from(enable: bool) -> Self {
+        if enable {
+            Self::ReadWrite
+        } else {
+            Self::Off
+        }
+    }
+}
+
+/// How far the dependency graph is walked from the package itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum DependencyDepth {
+    /// All transitive dependencies.
+    #[default]
+    All,
+    /// Only the direct dependencies.
+    Direct,
+    /// Dependencies at most this many edges away from the package. `MaxDepth(1)` is [Self::Direct].
+    MaxDepth(usize),
+}
+
+impl DependencyDepth {
+    /// Limits the walk to dependencies at most `depth` edges away from the package.
+    pub fn max_depth(depth: usize) -> Self {
+        Self::MaxDepth(depth)
+    }
+
+    /// Maximum number of edges between the package and a dependency, or `None` if unlimited.
+    pub(super) fn limit(self) -> Option<usize> {
+        match self {
+            Self::All => None,
+            Self::Direct => Some(1),
+            Self::MaxDepth(depth) => Some(depth),
+        }
+    }
+}
+
+/// Format of the log output of the build step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum LogFormat {
+    /// Human-readable output of the logger installed by the user, if any.
+    #[default]
+    Text,
+    /// JSON lines on stderr, ending with a summary of the [FetchReport](super::FetchReport).
+    ///
+    /// Log records look like `{"type":"log","level":"warn","target":"...","message":"..."}`.
+    /// The last line has the type `summary` and lists all problems of the report as strings.
+    Json,
+}
+
+/// Handling of the [Package::authors](crate::Package::authors) field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum AuthorsPolicy {
+    /// Keep authors as declared.
+    #[default]
+    Keep,
+    /// Remove email addresses like in `Jane Doe <jane@example.com>`. Authors consisting only of an email are dropped.
+    StripEmails,
+    /// Drop all authors.
+    Drop,
+}
+
+/// Configuration of the license fetching in the build step.
+///
+/// Use [ConfigBuilder] to construct it.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Config {
+    /// Path to the cargo executable. If `None`, `cargo` is looked up in `PATH`.
+    pub cargo_path: Option<OsString>,
+    /// Manifest dir of the package whose dependencies are fetched.
+    pub manifest_dir: PathBuf,
+    /// Name of the package whose dependencies are fetched.
+    pub package_name: String,
+    /// Extract a `License` section from `README` files if a package ships no license file.
+    pub readme_fallback: bool,
+    /// Preferred licenses for choosing between alternatives like `MIT OR Apache-2.0`. Most preferred first.
+    pub license_preference: Vec<String>,
+    /// Overrides of license identifiers. The first matching override is applied.
+    pub license_overrides: Vec<LicenseOverride>,
+    /// Overrides of license texts by package name. The first matching override is applied.
+    pub license_text_overrides: Vec<(String, LicenseTextOverride)>,
+    /// Notes for legal review by package name, added to [Package::annotations](crate::Package::annotations).
+    pub annotations: Vec<(String, String)>,
+    /// Packages added to the list in addition to the dependencies, like fonts and icons.
+    /// See [Package::asset](crate::Package::asset).
+    pub extra_packages: Vec<Package>,
+    /// Human readable files written along with the package list, like JSON or Markdown.
+    pub sidecar_outputs: Vec<Output>,
+    /// Licenses allowed by policy. Packages that can't be used under these licenses are reported.
+    /// Empty allows everything.
+    pub allowed_licenses: Vec<String>,
+    /// Licenses whose packages are dropped from the list, like public domain equivalents.
+    pub excluded_licenses: Vec<String>,
+    /// Use of the license cache in the user's cache directory.
+    pub cache: CachePolicy,
+    /// File written by [cache::export](super::cache::export) to restore license texts from. It is never written to.
+    pub cache_seed_path: Option<PathBuf>,
+    /// Maximum number of threads for running cargo and scanning the registry.
+    /// If `None`, the available parallelism of the machine is used.
+    pub max_parallelism: Option<NonZeroUsize>,
+    /// Implementation used for listing folders of the cargo registry.
+    pub fetch_backend: FetchBackend,
+    /// Follow symlinked license files and package folders.
+    pub follow_symlinks: bool,
+    /// License text embedded for the package itself.
+    pub root_license: RootLicense,
+    /// Include the package itself as first entry of the list.
+    pub include_root: bool,
+    /// Order of the dependencies in the list.
+    pub sort: SortOrder,
+    /// How far the dependency graph is walked.
+    pub dependency_depth: DependencyDepth,
+    /// Size in bytes of the `cargo metadata` output above which a warning is logged. `None` disables the warning.
+    pub metadata_size_warning: Option<u64>,
+    /// Target triple like `x86_64-pc-windows-msvc` the dependencies are resolved for.
+    /// If `None`, `cargo tree` filters for the host and `cargo metadata` includes all platforms.
+    pub target: Option<String>,
+    /// Handling of the authors of packages.
+    pub authors: AuthorsPolicy,
+    /// Retry failed `--frozen` cargo calls with `--offline` instead of allowing network access.
+    pub offline: bool,
+    /// Directives cargo is invoked with in turn until an invocation succeeds. `None` invokes cargo without.
+    /// If unset, they follow from [Self::offline].
+    pub cargo_directives: Option<Vec<Option<String>>>,
+    /// Read the packages from `Cargo.lock` if cargo can not be run.
+    pub lockfile_fallback: bool,
+    /// Skip fetching entirely and produce an empty package list.
+    pub skip: bool,
+    /// Whether cargo and other features may access the network.
+    pub network: NetworkPolicy,
+    /// Report packages that may not correspond to published sources.
+    pub source_warnings: bool,
+    /// Capture [ExtendedMetadata](crate::ExtendedMetadata) of packages.
+    pub extended_metadata: bool,
+    /// Format of the log output.
+    pub log_format: LogFormat,
+    /// Additional sources of license texts, tried after the cargo registry.
+    pub license_sources: Vec<Arc<dyn LicenseSourceBackend>>,
+    /// Query ClearlyDefined for packages with missing or unparsable license identifiers.
+    #[cfg(feature = "clearlydefined")]
+    pub clearlydefined: bool,
+}
+
+/// Builder for [Config].
+///
+/// # Example
+/// In `build.rs`:
+/// ```no_run
+/// use license_fetcher::build_script::{generate_package_list_with_config, ConfigBuilder};
+///
+/// fn main() {
+///     let config = ConfigBuilder::from_build_env()
+///         .readme_fallback(true)
+///         .build();
+///     generate_package_list_with_config(config).write();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    config: Config,
+    apply_env: bool,
+}
+
+impl ConfigBuilder {
+    /// Creates a builder for the package `package_name` located at `manifest_dir`.
+    pub fn new(manifest_dir: impl Into<PathBuf>, package_name: impl Into<String>) -> Self {
+        Self {
+            config: Config {
+                cargo_path: None,
+                manifest_dir: manifest_dir.into(),
+                package_name: package_name.into(),
+                readme_fallback: false,
+                license_preference: vec![],
+                license_overrides: vec![],
+                license_text_overrides: vec![],
+                annotations: vec![],
+                extra_packages: vec![],
+                sidecar_outputs: vec![],
+                allowed_licenses: vec![],
+                excluded_licenses: vec![],
+                cache: CachePolicy::Off,
+                cache_seed_path: None,
+                max_parallelism: None,
+                fetch_backend: FetchBackend::Std,
+                follow_symlinks: true,
+                root_license: RootLicense::Auto,
+                include_root: true,
+                sort: SortOrder::None,
+                dependency_depth: DependencyDepth::All,
+                metadata_size_warning: Some(DEFAULT_METADATA_SIZE_WARNING),
+                target: None,
+                authors: AuthorsPolicy::Keep,
+                offline: false,
+                cargo_directives: None,
+                lockfile_fallback: false,
+                skip: false,
+                network: NetworkPolicy::Allowed,
+                source_warnings: false,
+                extended_metadata: false,
+                license_sources: vec![],
+                log_format: LogFormat::Text,
+                #[cfg(feature = "clearlydefined")]
+                clearlydefined: false,
+            },
+            apply_env: false,
+        }
+    }
+
+    /// Creates a builder from the env variables cargo supplies to build scripts.
+    ///
+    /// Reads `CARGO`, `CARGO_MANIFEST_DIR` and `CARGO_PKG_NAME`.
+    /// Panics if called outside of a build script.
+    ///
+    /// Additionally the following env variables are applied when building the [Config], on top of
+    /// the settings of the builder. This way CI can tweak the behavior without changing `build.rs`.
+    ///
+    /// | Variable                           | Value                                  |
+    /// | ---------------------------------- | -------------------------------------- |
+    /// | `LICENSE_FETCHER_CACHE`            | `true`, `false` or e.g. `read-only`    |
+    /// | `LICENSE_FETCHER_CACHE_SEED`       | Path, see [Self::cache_seed_path]      |
+    /// | `LICENSE_FETCHER_OFFLINE`          | `true` or `false`, see [Self::offline] |
+    /// | `LICENSE_FETCHER_DIRECTIVES`       | See [Self::cargo_directives]           |
+    /// | `LICENSE_FETCHER_LOCKFILE_FALLBACK`| `true` or `false`                      |
+    /// | `LICENSE_FETCHER_README_FALLBACK`  | `true` or `false`                      |
+    /// | `LICENSE_FETCHER_INCLUDE_ROOT`     | `true` or `false`                      |
+    /// | `LICENSE_FETCHER_MAX_PARALLELISM`  | Number of threads                      |
+    /// | `LICENSE_FETCHER_FETCH_BACKEND`    | `std` or `walkdir`                     |
+    /// | `LICENSE_FETCHER_CLEARLYDEFINED`   | `true` or `false`                      |
+    /// | `LICENSE_FETCHER_SKIP`             | `true` or `false`, see [Self::skip]    |
+    /// | `LICENSE_FETCHER_NETWORK`          | `allowed` or `forbidden`               |
+    /// | `LICENSE_FETCHER_LOG_FORMAT`       | `text` or `json`                       |
+    /// | `LICENSE_FETCHER_DEPENDENCY_DEPTH` | `all`, `direct` or a number of edges   |
+    ///
+    /// Invalid values are ignored with a warning. Cargo does not rerun build scripts on changes
+    /// of these variables, unless `cargo::rerun-if-env-changed` is printed for them.
+    pub fn from_build_env() -> Self {
+        let manifest_dir = var_os("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set.");
+        let package_name = var("CARGO_PKG_NAME").expect("CARGO_PKG_NAME not set.");
+
+        let mut builder = Self::new(manifest_dir, package_name).cargo_path(var_os("CARGO"));
+        builder.apply_env = true;
+        builder
+    }
+
+    /// Sets the path to the cargo executable.
+    pub fn cargo_path(mut self, cargo_path: Option<OsString>) -> Self {
+        self.config.cargo_path = cargo_path;
+        self
+    }
+
+    /// Enables the low confidence fallback of extracting the `License` section of `README` files.
+    ///
+    /// Only used for packages without any license file. Texts gathered this way are marked
+    /// with [LicenseTextSource::Readme](crate::LicenseTextSource::Readme).
+    pub fn readme_fallback(mut self, enable: bool) -> Self {
+        self.config.readme_fallback = enable;
+        self
+    }
+
+    /// Sets the preference order of licenses, most preferred first.
+    ///
+    /// The chosen license of each package is recorded in [Package::effective_license](crate::Package::effective_license).
+    /// Without a preference no license is chosen.
+    pub fn license_preference<I, S>(mut self, preference: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config.license_preference = preference.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Adds a [LicenseOverride].
+    pub fn license_override(mut self, license_override: LicenseOverride) -> Self {
+        self.config.license_overrides.push(license_override);
+        self
+    }
+
+    /// Replaces the license text of all versions of the package `name` after fetching.
+    ///
+    /// Meant for crates whose actual license is not shipped with the crate, like many `-sys` crates.
+    /// Overridden texts are not cached. Failing to read an override file is recorded in the [FetchReport](super::FetchReport).
+    ///
+    /// # Example
+    /// In `build.rs`:
+    /// ```no_run
+    /// use license_fetcher::build_script::{ConfigBuilder, LicenseTextOverride};
+    ///
+    /// let config = ConfigBuilder::from_build_env()
+    ///     .override_license(
+    ///         "openssl-sys",
+    ///         LicenseTextOverride::File("third_party/openssl/LICENSE".into()),
+    ///     )
+    ///     .build();
+    /// ```
+    pub fn override_license(mut self, name: impl Into<String>, text: LicenseTextOverride) -> Self {
+        self.config.license_text_overrides.push((name.into(), text));
+        self
+    }
+
+    /// Adds a note for legal review to all versions of the package `name`, like `statically linked`,
+    /// `used only on Windows` or `exception granted by vendor`.
+    ///
+    /// Notes are kept in [Package::annotations](crate::Package::annotations) and rendered by all output formats.
+    ///
+    /// # Example
+    /// In `build.rs`:
+    /// ```no_run
+    /// use license_fetcher::build_script::ConfigBuilder;
+    ///
+    /// let config = ConfigBuilder::from_build_env()
+    ///     .annotate("openssl-sys", "Statically linked.")
+    ///     .build();
+    /// ```
+    pub fn annotate(mut self, name: impl Into<String>, annotation: impl Into<String>) -> Self {
+        self.config
+            .annotations
+            .push((name.into(), annotation.into()));
+        self
+    }
+
+    /// Adds a package that is not a dependency to the list, like a font or an icon set.
+    ///
+    /// Extra packages go through the same post processing as dependencies, so overrides, annotations and the
+    /// license policy apply. Mark assets with [PackageBuilder::asset](crate::PackageBuilder::asset), so output
+    /// formats list them apart from the crates. See also [ConfigBuilder::import_assets].
+    ///
+    /// # Example
+    /// In `build.rs`:
+    /// ```no_run
+    /// use license_fetcher::build_script::ConfigBuilder;
+    /// use license_fetcher::{AssetKind, Package};
+    ///
+    /// let config = ConfigBuilder::from_build_env()
+    ///     .extra_package(
+    ///         Package::builder()
+    ///             .name("Fira Sans")
+    ///             .version("4.301")
+    ///             .license_identifier("OFL-1.1")
+    ///             .license_text(std::fs::read_to_string("assets/fonts/OFL.txt").unwrap())
+    ///             .asset(AssetKind::Font)
+    ///             .build(),
+    ///     )
+    ///     .build();
+    /// ```
+    pub fn extra_package(mut self, package: Package) -> Self {
+        self.config.extra_packages.push(package);
+        self
+    }
+
+    /// Writes human readable files like JSON or Markdown right after fetching, in addition to the package list
+    /// embedded with [PackageList::write](crate::PackageList::write).
+    ///
+    /// Saves running a separate tool in CI to publish the licenses next to release artifacts.
+    /// The build script panics if a file can not be written.
+    ///
+    /// # Example
+    /// In `build.rs`:
+    /// ```no_run
+    /// use license_fetcher::build_script::{generate_package_list_with_config, ConfigBuilder, Output};
+    ///
+    /// fn main() {
+    ///     let config = ConfigBuilder::from_build_env()
+    ///         .sidecar_outputs([
+    ///             Output::Json("target/licenses.json".into()),
+    ///             Output::Markdown("target/LICENSES.md".into()),
+    ///         ])
+    ///         .build();
+    ///     generate_package_list_with_config(config).write();
+    /// }
+    /// ```
+    pub fn sidecar_outputs(mut self, outputs: impl IntoIterator<Item = Output>) -> Self {
+        self.config.sidecar_outputs.extend(outputs);
+        self
+    }
+
+    /// Adds licenses allowed by policy.
+    ///
+    /// Violations are recorded in [FetchReport::policy_violations](super::FetchReport::policy_violations).
+    pub fn allowed_licenses<I, S>(mut self, allowed: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config
+            .allowed_licenses
+            .extend(allowed.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds licenses whose packages are dropped from the list, like `CC0-1.0` or `Unlicense`.
+    ///
+    /// A dependency is dropped if its [effective license](crate::Package::effective_license), or its license
+    /// identifier without [license preference](Self::license_preference), can be fulfilled using only the excluded
+    /// licenses. The package itself is never dropped.
+    pub fn exclude_licenses<I, S>(mut self, excluded: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config
+            .excluded_licenses
+            .extend(excluded.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the [CachePolicy] of the license cache in the user's cache directory.
+    ///
+    /// `true` and `false` are accepted for [CachePolicy::ReadWrite] and [CachePolicy::Off]. In
+    /// `LICENSE_FETCHER_CACHE` the policies are written as `read-write`, `read-only`, `write-only` and
+    /// `refresh-changed`. Cached texts are only used if the checksum of the crate in `Cargo.lock` still matches.
+    pub fn cache(mut self, policy: impl Into<CachePolicy>) -> Self {
+        self.config.cache = policy.into();
+        self
+    }
+
+    /// Restores license texts from a file written by [cache::export](super::cache::export), like a checked-in
+    /// `licenses.cache` or a file restored by CI.
+    ///
+    /// The seed is read-only and works without [Self::cache]. If the cache is enabled, entries of the cache take
+    /// precedence and entries of the seed are copied into it. Like cached texts, seeded texts are only used if the
+    /// checksum of the crate in `Cargo.lock` matches.
+    pub fn cache_seed_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.cache_seed_path = Some(path.into());
+        self
+    }
+
+    /// Fills missing or unparsable license identifiers with the license declared on [ClearlyDefined](https://clearlydefined.io).
+    ///
+    /// Needs the feature `clearlydefined` and `curl` in `PATH`. Responses are cached in the user's cache directory.
+    #[cfg(feature = "clearlydefined")]
+    pub fn clearlydefined(mut self, enable: bool) -> Self {
+        self.config.clearlydefined = enable;
+        self
+    }
+
+    /// Limits the number of threads used for running cargo and scanning the registry.
+    ///
+    /// Useful for slow network file systems, where many concurrent reads are slower than a few.
+    pub fn max_parallelism(mut self, max_parallelism: NonZeroUsize) -> Self {
+        self.config.max_parallelism = Some(max_parallelism);
+        self
+    }
+
+    /// Sets the [FetchBackend] used for listing folders of the cargo registry.
+    pub fn fetch_backend(mut self, fetch_backend: FetchBackend) -> Self {
+        self.config.fetch_backend = fetch_backend;
+        self
+    }
+
+    /// Follows symlinked license files and package folders. Enabled by default.
+    ///
+    /// Vendored setups sometimes symlink `LICENSE` files or whole packages. Broken symlinks and symlink loops are
+    /// skipped.
+    pub fn follow_symlinks(mut self, enable: bool) -> Self {
+        self.config.follow_symlinks = enable;
+        self
+    }
+
+    /// Sets the license text embedded for the package itself.
+    ///
+    /// By default every license like file in the manifest dir is used, which may include
+    /// unrelated files like `NOTICE`s of vendored code.
+    pub fn root_license(mut self, root_license: RootLicense) -> Self {
+        self.config.root_license = root_license;
+        self
+    }
+
+    /// Includes the package itself as first entry of the list. Enabled by default.
+    ///
+    /// Disable it to only embed third party attributions, for example if the own license is shipped separately.
+    pub fn include_root(mut self, enable: bool) -> Self {
+        self.config.include_root = enable;
+        self
+    }
+
+    /// Sets the [SortOrder] of the dependencies. The package itself always stays first.
+    pub fn sort(mut self, sort: SortOrder) -> Self {
+        self.config.sort = sort;
+        self
+    }
+
+    /// Limits the dependencies to those within a [DependencyDepth] of the package.
+    ///
+    /// For policies that only require attributing the libraries used directly.
+    /// Only normal dependencies are walked either way.
+    pub fn dependency_depth(mut self, dependency_depth: DependencyDepth) -> Self {
+        self.config.dependency_depth = dependency_depth;
+        self
+    }
+
+    /// Sets the size in bytes of the `cargo metadata` output above which a warning is logged.
+    ///
+    /// The output is parsed while cargo writes it, but the parsed metadata of huge workspaces still takes a lot of
+    /// memory. Defaults to 100 MiB. `None` disables the warning.
+    pub fn metadata_size_warning(mut self, bytes: Option<u64>) -> Self {
+        self.config.metadata_size_warning = bytes;
+        self
+    }
+
+    /// Resolves the dependencies for a target triple like `x86_64-pc-windows-msvc` instead of the host.
+    ///
+    /// Passes `--filter-platform` to `cargo metadata` and `--target` to `cargo tree`. Not applied when reading
+    /// `Cargo.lock` with [Self::lockfile_fallback]. For one list covering several targets see
+    /// [generate_package_list_for_targets](super::generate_package_list_for_targets).
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.config.target = Some(target.into());
+        self
+    }
+
+    /// Sets the [AuthorsPolicy], for example to keep email addresses out of distributed binaries.
+    pub fn authors(mut self, authors: AuthorsPolicy) -> Self {
+        self.config.authors = authors;
+        self
+    }
+
+    /// Retries failed `--frozen` cargo calls with `--offline` instead of allowing network access.
+    pub fn offline(mut self, enable: bool) -> Self {
+        self.config.offline = enable;
+        self
+    }
+
+    /// Reads the packages from `Cargo.lock` if cargo can not be run, instead of panicking with
+    /// [CargoNotFound](super::CargoNotFound).
+    ///
+    /// `Cargo.lock` does not tell normal, build and dev dependencies or target platforms apart, so all of them
+    /// are listed.
+    /// License identifiers and authors are read from the manifests in the cargo registry. Packages
+    /// outside the registry, except the package itself, have no license information.
+    pub fn lockfile_fallback(mut self, enable: bool) -> Self {
+        self.config.lockfile_fallback = enable;
+        self
+    }
+
+    /// Sets the directives cargo is invoked with in turn until an invocation succeeds, overriding [Self::offline].
+    ///
+    /// `None` invokes cargo without directive. Allowed are `--frozen`, `--locked` and `--offline`.
+    /// `LICENSE_FETCHER_DIRECTIVES` takes them comma separated with `none` for no directive, e.g. `--frozen,none`.
+    /// With [NetworkPolicy::Forbidden] or the feature `frozen` cargo is only invoked with `--frozen`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use license_fetcher::build_script::ConfigBuilder;
+    ///
+    /// let config = ConfigBuilder::from_build_env()
+    ///     .cargo_directives([Some("--locked"), Some("--offline")])
+    ///     .build();
+    /// ```
+    pub fn cargo_directives<'a>(
+        mut self,
+        directives: impl IntoIterator<Item = Option<&'a str>>,
+    ) -> Self {
+        let directives: Vec<_> = directives
+            .into_iter()
+            .map(|directive| directive.map(str::to_owned))
+            .collect();
+        self.config.cargo_directives = (!directives.is_empty()).then_some(directives);
+        self
+    }
+
+    /// Skips fetching entirely. The resulting package list is empty.
+    ///
+    /// Meant for development builds, where fetching licenses only slows down compilation. Write the list with
+    /// [EncodeOptions::skipped](super::EncodeOptions::skipped), which `LICENSE_FETCHER_SKIP` also sets, so the
+    /// program can tell with [get_package_list_or_empty](crate::get_package_list_or_empty) that nothing was fetched.
+    pub fn skip(mut self, enable: bool) -> Self {
+        self.config.skip = enable;
+        self
+    }
+
+    /// Sets the [NetworkPolicy].
+    ///
+    /// With [NetworkPolicy::Forbidden] cargo is never retried without `--frozen`, regardless of [Self::offline],
+    /// and ClearlyDefined is not queried. If cargo fails, the build panics with a list of the packages of the
+    /// `Cargo.lock` that are missing in the local registry.
+    pub fn network(mut self, network: NetworkPolicy) -> Self {
+        self.config.network = network;
+        self
+    }
+
+    /// Records pre-release packages and packages outside the cargo registry in
+    /// [FetchReport::source_warnings](super::FetchReport::source_warnings).
+    ///
+    /// Packages outside the registry are path or git dependencies, patched crates or crates that were yanked
+    /// and vendored. Their sources may differ from what is published on the registry.
+    pub fn source_warnings(mut self, enable: bool) -> Self {
+        self.config.source_warnings = enable;
+        self
+    }
+
+    /// Captures documentation URL, keywords, categories, edition and rust version of packages in
+    /// [Package::extended_metadata](crate::Package::extended_metadata).
+    ///
+    /// Disabled by default, as it increases the size of the embedded package list.
+    pub fn extended_metadata(mut self, enable: bool) -> Self {
+        self.config.extended_metadata = enable;
+        self
+    }
+
+    /// Emits log records and a final summary as JSON lines on stderr with [LogFormat::Json].
+    ///
+    /// The JSON logger is installed when fetching starts, unless the build script already installed a logger.
+    /// Meant for CI systems that annotate problems of the license fetching.
+    pub fn log_format(mut self, log_format: LogFormat) -> Self {
+        self.config.log_format = log_format;
+        self
+    }
+
+    /// Registers an additional source of license texts. See [LicenseSourceBackend].
+    ///
+    /// Backends run after the cargo registry, in order of registration, for packages still without license text.
+    pub fn license_source(mut self, backend: impl LicenseSourceBackend + 'static) -> Self {
+        self.config.license_sources.push(Arc::new(backend));
+        self
+    }
+
+    pub fn build(mut self) -> Config {
+        if self.apply_env {
+            self.config.apply_env(|name| var(name).ok());
+        }
+        self.config
+    }
+}
+
+/// Reads and parses an env variable. Invalid values are ignored with a warning.
+pub(super) fn env_value<T>(name: &str, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    lookup_value(&|name| var(name).ok(), name, parse)
+}
+
+/// Like [env_value], but reads the variable with `lookup`.
+fn lookup_value<T>(
+    lookup: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<T> {
+    let value = lookup(name)?;
+    let parsed = parse(value.trim());
+    if parsed.is_none() {
+        warn!("Ignoring invalid value {:?} of {}.", value, name);
+    }
+    parsed
+}
+
+pub(super) fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_log_format(value: &str) -> Option<LogFormat> {
+    match value.to_ascii_lowercase().as_str() {
+        "text" => Some(LogFormat::Text),
+        "json" => Some(LogFormat::Json),
+        _ => None,
+    }
+}
+
+/// Reads `LICENSE_FETCHER_LOG_FORMAT` without warning, for installing the logger before the [Config] is built.
+pub(super) fn log_format_from_env() -> LogFormat {
+    var("LICENSE_FETCHER_LOG_FORMAT")
+        .ok()
+        .and_then(|value| parse_log_format(value.trim()))
+        .unwrap_or_default()
+}
+
+/// Parses comma separated cargo directives like `--frozen,none`.
+fn parse_directives(value: &str) -> Option<Vec<Option<String>>> {
+    value
+        .split(',')
+        .map(
+            |directive| match directive.trim().to_ascii_lowercase().as_str() {
+                "none" => Some(None),
+                directive @ ("--frozen" | "--locked" | "--offline") => {
+                    Some(Some(directive.to_owned()))
+                }
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+impl Config {
+    /// Applies the env variables documented at [ConfigBuilder::from_build_env], reading them with `lookup`.
+    fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) {
+        if let Some(cache) = lookup_value(&lookup, "LICENSE_FETCHER_CACHE", |value| {
+            match value.to_ascii_lowercase().as_str() {
+                "read-write" => Some(CachePolicy::ReadWrite),
+                "read-only" => Some(CachePolicy::ReadOnly),
+                "write-only" => Some(CachePolicy::WriteOnly),
+                "refresh-changed" => Some(CachePolicy::RefreshChanged),
+                value => parse_bool(value).map(CachePolicy::from),
+            }
+        }) {
+            self.cache = cache;
+        }
+        if let Some(cache_seed_path) =
+            lookup_value(&lookup, "LICENSE_FETCHER_CACHE_SEED", |value| {
+                (!value.is_empty()).then(|| PathBuf::from(value))
+            })
+        {
+            self.cache_seed_path = Some(cache_seed_path);
+        }
+        if let Some(offline) = lookup_value(&lookup, "LICENSE_FETCHER_OFFLINE", parse_bool) {
+            self.offline = offline;
+        }
+        if let Some(lockfile_fallback) =
+            lookup_value(&lookup, "LICENSE_FETCHER_LOCKFILE_FALLBACK", parse_bool)
+        {
+            self.lockfile_fallback = lockfile_fallback;
+        }
+        if let Some(readme_fallback) =
+            lookup_value(&lookup, "LICENSE_FETCHER_README_FALLBACK", parse_bool)
+        {
+            self.readme_fallback = readme_fallback;
+        }
+        if let Some(include_root) =
+            lookup_value(&lookup, "LICENSE_FETCHER_INCLUDE_ROOT", parse_bool)
+        {
+            self.include_root = include_root;
+        }
+        if let Some(max_parallelism) =
+            lookup_value(&lookup, "LICENSE_FETCHER_MAX_PARALLELISM", |value| {
+                value.parse().ok()
+            })
+        {
+            self.max_parallelism = Some(max_parallelism);
+        }
+        if let Some(fetch_backend) = lookup_value(
+            &lookup,
+            "LICENSE_FETCHER_FETCH_BACKEND",
+            |value| match value.to_ascii_lowercase().as_str() {
+                "std" => Some(FetchBackend::Std),
+                "walkdir" => Some(FetchBackend::WalkDir),
+                _ => None,
+            },
+        ) {
+            self.fetch_backend = fetch_backend;
+        }
+        if let Some(cargo_directives) =
+            lookup_value(&lookup, "LICENSE_FETCHER_DIRECTIVES", parse_directives)
+        {
+            self.cargo_directives = Some(cargo_directives);
+        }
+        if let Some(skip) = lookup_value(&lookup, "LICENSE_FETCHER_SKIP", parse_bool) {
+            self.skip = skip;
+        }
+        if let Some(network) = lookup_value(&lookup, "LICENSE_FETCHER_NETWORK", |value| match value
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "allowed" => Some(NetworkPolicy::Allowed),
+            "forbidden" => Some(NetworkPolicy::Forbidden),
+            _ => None,
+        }) {
+            self.network = network;
+        }
+        if let Some(dependency_depth) = lookup_value(
+            &lookup,
+            "LICENSE_FETCHER_DEPENDENCY_DEPTH",
+            |value| match value.to_ascii_lowercase().as_str() {
+                "all" => Some(DependencyDepth::All),
+                "direct" => Some(DependencyDepth::Direct),
+                depth => depth.parse().ok().map(DependencyDepth::MaxDepth),
+            },
+        ) {
+            self.dependency_depth = dependency_depth;
+        }
+        if let Some(log_format) =
+            lookup_value(&lookup, "LICENSE_FETCHER_LOG_FORMAT", parse_log_format)
+        {
+            self.log_format = log_format;
+        }
+        #[cfg(feature = "clearlydefined")]
+        if let Some(clearlydefined) =
+            lookup_value(&lookup, "LICENSE_FETCHER_CLEARLYDEFINED", parse_bool)
+        {
+            self.clearlydefined = clearlydefined;
+        }
+    }
+
+    /// Number of threads to use. At least `1`.
+    pub(super) fn parallelism(&self) -> usize {
+        self.max_parallelism
+            .or_else(|| available_parallelism().ok())
+            .map_or(1, NonZeroUsize::get)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    /// Applies the variables `env` to `config`.
+    fn apply(mut config: Config, env: &[(&str, &str)]) -> Config {
+        let env: BTreeMap<&str, &str> = env.iter().copied().collect();
+        config.apply_env(|name| env.get(name).map(|value| value.to_string()));
+        config
+    }
+
+    #[test]
+    fn test_env_skip() {
+        let config = || ConfigBuilder::new(".", "root").build();
+        let skipped = apply(config(), &[("LICENSE_FETCHER_SKIP", "1")]);
+        let invalid = apply(
+            ConfigBuilder::new(".", "root").skip(true).build(),
+            &[("LICENSE_FETCHER_SKIP", "maybe")],
+        );
+
+        assert!(skipped.skip);
+        assert!(invalid.skip);
+        assert!(!apply(config(), &[]).skip);
+    }
+
+    #[test]
+    fn test_env_directives() {
+        assert_eq!(
+            parse_directives("--locked, none"),
+            Some(vec![Some("--locked".to_owned()), None])
+        );
+        assert_eq!(parse_directives("--frozen,--manifest-path"), None);
+        assert_eq!(parse_directives(""), None);
+
+        let with_directives = |directives: &[Option<&str>]| {
+            ConfigBuilder::new(".", "root")
+                .offline(true)
+                .cargo_directives(directives.iter().copied())
+                .build()
+        };
+        let env = |value| [("LICENSE_FETCHER_DIRECTIVES", value)];
+        let config = apply(with_directives(&[Some("--offline")]), &env("--frozen,none"));
+        let invalid = apply(
+            with_directives(&[Some("--offline")]),
+            &env("--frozen,--verbose"),
+        );
+
+        assert_eq!(
+            config.cargo_directives,
+            Some(vec![Some("--frozen".to_owned()), None])
+        );
+        assert_eq!(
+            invalid.cargo_directives,
+            Some(vec![Some("--offline".to_owned())])
+        );
+        assert_eq!(apply(with_directives(&[]), &[]).cargo_directives, None);
+    }
+}
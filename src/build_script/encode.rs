@@ -0,0 +1,782 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use std::cmp::Reverse;
+use std::io::{self, Write};
+use std::ops::{BitOr, Sub};
+use std::time::Instant;
+
+use log::info;
+
+#[cfg(feature = "compress")]
+use miniz_oxide::deflate::core::{
+    compress_to_output, create_comp_flags_from_zip_params, CompressorOxide, TDEFLFlush, TDEFLStatus,
+};
+
+use super::config::{env_value, parse_bool};
+use super::report::PackageSize;
+#[cfg(feature = "rkyv")]
+use crate::archive::RkyvPayload;
+use crate::encoding;
+#[cfg(any(feature = "cbor", feature = "postcard"))]
+use crate::payload::Payload;
+use crate::{BuildInfo, Compression, Package, PackageList, SerializationFormat};
+
+/// Set of optional [Package] fields to embed.
+///
+/// Name, version and license information are always embedded. The mask is set on the [EncodeOptions] instead of
+/// the [Config](super::Config), since it only shrinks the encoded output and reports are made from the full
+/// [PackageList].
+///
+/// # Example
+/// ```
+/// use license_fetcher::build_script::{EncodeOptions, FieldMask};
+///
+/// let options = EncodeOptions::default().embed_fields(FieldMask::ALL - FieldMask::DESCRIPTION);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldMask(u8);
+
+impl FieldMask {
+    pub const NONE: Self = Self(0);
+    pub const DESCRIPTION: Self = Self(1);
+    pub const HOMEPAGE: Self = Self(1 << 1);
+    pub const REPOSITORY: Self = Self(1 << 2);
+    pub const AUTHORS: Self = Self(1 << 3);
+    /// All optional fields, currently `0b1111`.
+    ///
+    /// Fields added in later versions get new bits, which are then part of `ALL`. A mask like
+    /// `FieldMask::ALL - FieldMask::DESCRIPTION` embeds them, a mask like `FieldMask::HOMEPAGE | FieldMask::AUTHORS`
+    /// does not.
+    pub const ALL: Self = Self(0b1111);
+
+    /// Returns `true` if all fields of `other` are contained.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl Default for FieldMask {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl BitOr for FieldMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl Sub for FieldMask {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 & !rhs.0)
+    }
+}
+
+/// Options for encoding a [PackageList] into embeddable bytes.
+///
+/// # Example
+/// In `build.rs`:
+/// ```no_run
+/// use license_fetcher::BuildInfo;
+/// use license_fetcher::build_script::{generate_package_list_with_licenses, EncodeOptions};
+///
+/// fn main() {
+///     let options = EncodeOptions::default().build_info(BuildInfo::from_build_env());
+///     generate_package_list_with_licenses().write_with_options(&options);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EncodeOptions {
+    build_info: Option<BuildInfo>,
+    format: SerializationFormat,
+    compression: Compression,
+    fields: FieldMask,
+    skipped: bool,
+    #[cfg(feature = "signature")]
+    signing_key: Option<[u8; 32]>,
+}
+
+impl EncodeOptions {
+    /// Creates default options with the following env variables applied.
+    ///
+    /// | Variable                      | Value                                             |
+    /// | ----------------------------- | ------------------------------------------------- |
+    /// | `LICENSE_FETCHER_COMPRESSION` | `none`, `best` or `deflate` (feature `compress`)  |
+    /// | `LICENSE_FETCHER_FORMAT`      | `bincode`, `cbor`, `postcard` or `rkyv` (features of the same name) |
+    /// | `LICENSE_FETCHER_SKIP`        | `true` or `false`, see [Self::skipped]            |
+    ///
+    /// Used by [PackageList::write]. Invalid values are ignored with a warning.
+    pub fn from_build_env() -> Self {
+        let mut options = Self::default();
+        if let Some(compression) = env_value("LICENSE_FETCHER_COMPRESSION", |value| {
+            match value.to_ascii_lowercase().as_str() {
+                "none" => Some(Compression::None),
+                #[cfg(feature = "compress")]
+                "deflate" => Some(Compression::Deflate),
+                "best" => Some(Compression::Best),
+                _ => None,
+            }
+        }) {
+            options.compression = compression;
+        }
+        if let Some(format) = env_value("LICENSE_FETCHER_FORMAT", |value| {
+            match value.to_ascii_lowercase().as_str() {
+                "bincode" => Some(SerializationFormat::Bincode),
+                #[cfg(feature = "cbor")]
+                "cbor" => Some(SerializationFormat::Cbor),
+                #[cfg(feature = "postcard")]
+                "postcard" => Some(SerializationFormat::Postcard),
+                #[cfg(feature = "rkyv")]
+                "rkyv" => Some(SerializationFormat::Rkyv),
+                _ => None,
+            }
+        }) {
+            options.format = format;
+        }
+        if let Some(skipped) = env_value("LICENSE_FETCHER_SKIP", parse_bool) {
+            options.skipped = skipped;
+        }
+        options
+    }
+
+    /// Embeds a [BuildInfo] alongside the package list.
+    pub fn build_info(mut self, build_info: BuildInfo) -> Self {
+        self.build_info = Some(build_info);
+        self
+    }
+
+    /// Sets the [SerializationFormat]. Defaults to bincode.
+    pub fn format(mut self, format: SerializationFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the [Compression]. Defaults to deflate if the feature `compress` is enabled.
+    ///
+    /// Ignored for rkyv archives, which are never compressed.
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets the optional fields to embed. Defaults to [FieldMask::ALL].
+    ///
+    /// Omitting fields shrinks the embedded data. The [PackageList] itself keeps all fields.
+    pub fn embed_fields(mut self, fields: FieldMask) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Writes an empty artifact instead of the package list.
+    ///
+    /// Decoding it returns [UnpackError::Empty](crate::error::UnpackError::Empty), so
+    /// [get_package_list_or_empty](crate::get_package_list_or_empty) can tell that no license data was fetched.
+    /// Meant for lists generated with [ConfigBuilder::skip](super::ConfigBuilder::skip).
+    pub fn skipped(mut self, enable: bool) -> Self {
+        self.skipped = enable;
+        self
+    }
+
+    /// Signs the encoded data with an ed25519 secret key.
+    ///
+    /// Needs the feature `signature`.
+    #[cfg(feature = "signature")]
+    pub fn signing_key(mut self, secret_key: [u8; 32]) -> Self {
+        self.signing_key = Some(secret_key);
+        self
+    }
+}
+
+/// Writer compressing everything written to it into an in memory buffer.
+#[cfg(feature = "compress")]
+struct DeflateWriter {
+    compressor: CompressorOxide,
+    compressed: Vec<u8>,
+    uncompressed_length: usize,
+}
+
+#[cfg(feature = "compress")]
+impl DeflateWriter {
+    fn new(level: u8) -> Self {
+        Self {
+            compressor: CompressorOxide::new(create_comp_flags_from_zip_params(level.into(), 0, 0)),
+            compressed: vec![],
+            uncompressed_length: 0,
+        }
+    }
+
+    fn compress(&mut self, mut input: &[u8], flush: TDEFLFlush) -> io::Result<()> {
+        loop {
+            let compressed = &mut self.compressed;
+            let (status, bytes_in) =
+                compress_to_output(&mut self.compressor, input, flush, |out| {
+                    compressed.extend_from_slice(out);
+                    true
+                });
+            input = &input[bytes_in..];
+            match status {
+                TDEFLStatus::Done => return Ok(()),
+                TDEFLStatus::Okay if input.is_empty() && flush == TDEFLFlush::None => return Ok(()),
+                TDEFLStatus::Okay => continue,
+                _ => return Err(io::Error::other("Failed compressing license data.")),
+            }
+        }
+    }
+
+    fn finish(mut self) -> io::Result<Vec<u8>> {
+        self.compress(&[], TDEFLFlush::Finish)?;
+        Ok(self.compressed)
+    }
+}
+
+#[cfg(feature = "compress")]
+impl Write for DeflateWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.compress(buf, TDEFLFlush::None)?;
+        self.uncompressed_length += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Fails if the serialized data is too large to be decoded, so no undecodable data is embedded.
+fn check_decodable_length(length: usize) -> io::Result<()> {
+    if length > encoding::MAX_DECODED_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "License data of {} Bytes exceeds the decodable maximum of {} Bytes.",
+                length,
+                encoding::MAX_DECODED_LENGTH
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Copy of a package with only the fields to embed.
+fn embedded_package(package: &Package, fields: FieldMask) -> Package {
+    let mut package = package.clone();
+    package.manifest_path = None;
+    if !fields.contains(FieldMask::DESCRIPTION) {
+        package.description = None;
+    }
+    if !fields.contains(FieldMask::HOMEPAGE) {
+        package.homepage = None;
+    }
+    if !fields.contains(FieldMask::REPOSITORY) {
+        package.repository = None;
+    }
+    if !fields.contains(FieldMask::AUTHORS) {
+        package.authors.clear();
+    }
+    package
+}
+
+impl PackageList {
+    /// Serializes, compresses and frames the [PackageList] with a checksum.
+    ///
+    /// The output can be decoded with [PackageList::from_encoded].
+    ///
+    /// # Panics
+    /// Panics if the serialized data exceeds the 64 MiB that decoding accepts.
+    pub fn encode(&self, options: &EncodeOptions) -> Vec<u8> {
+        let mut encoded = vec![];
+        self.encode_to_writer(options, &mut encoded)
+            .expect("Failed encoding license data.");
+        encoded
+    }
+
+    fn serialize(&self, options: &EncodeOptions, writer: &mut impl Write) -> io::Result<()> {
+        // Manifest paths are specific to the build machine and must not end up in the binary.
+        let stripped;
+        let package_list =
+            if options.fields != FieldMask::ALL || self.iter().any(|p| p.manifest_path.is_some()) {
+                stripped = PackageList(
+                    self.iter()
+                        .map(|package| embedded_package(package, options.fields))
+                        .collect(),
+                );
+                &stripped
+            } else {
+                self
+            };
+
+        match options.format {
+            SerializationFormat::Bincode => bincode::encode_into_std_write(
+                (&options.build_info, package_list),
+                writer,
+                bincode::config::standard(),
+            )
+            .map(|_| ())
+            .map_err(io::Error::other),
+            #[cfg(feature = "rkyv")]
+            SerializationFormat::Rkyv => {
+                let archive = rkyv::to_bytes::<rkyv::rancor::Error>(&RkyvPayload {
+                    build_info: options.build_info.clone(),
+                    packages: PackageList(package_list.to_vec()),
+                })
+                .map_err(io::Error::other)?;
+                writer.write_all(&[0; encoding::ARCHIVE_PADDING])?;
+                writer.write_all(&archive)
+            }
+            #[cfg(feature = "cbor")]
+            SerializationFormat::Cbor => ciborium::into_writer(
+                &Payload {
+                    build_info: &options.build_info,
+                    packages: package_list,
+                },
+                writer,
+            )
+            .map_err(io::Error::other),
+            #[cfg(feature = "postcard")]
+            SerializationFormat::Postcard => postcard::to_io(
+                &Payload {
+                    build_info: &options.build_info,
+                    packages: package_list,
+                },
+                writer,
+            )
+            .map(|_| ())
+            .map_err(io::Error::other),
+        }
+    }
+
+    /// Reports how many bytes the license text of each package contributes, largest first.
+    ///
+    /// Useful for finding packages that bloat the binary, like crates bundling font licenses.
+    /// Texts are compressed on their own, so texts shared by many packages appear larger than they
+    /// end up in the compressed data.
+    pub fn size_report(&self) -> Vec<PackageSize> {
+        let mut sizes: Vec<PackageSize> = self
+            .iter()
+            .map(|package| {
+                let text = package.license_text.as_deref().unwrap_or_default();
+                #[cfg(feature = "compress")]
+                let compressed_bytes = {
+                    let mut deflate_writer = DeflateWriter::new(10);
+                    deflate_writer
+                        .write_all(text.as_bytes())
+                        .and_then(|_| deflate_writer.finish())
+                        .map_or(text.len(), |compressed| compressed.len())
+                };
+                #[cfg(not(feature = "compress"))]
+                let compressed_bytes = text.len();
+
+                PackageSize {
+                    package_name: package.name.clone(),
+                    package_version: package.version.clone(),
+                    license_text_bytes: text.len(),
+                    compressed_bytes,
+                }
+            })
+            .collect();
+        sizes.sort_by_key(|size| Reverse(size.compressed_bytes));
+        sizes
+    }
+
+    /// Same as [PackageList::encode], but writes into a writer.
+    ///
+    /// Serialization and compression are done incrementally, so the uncompressed data is never held in memory.
+    /// Only the compressed data is buffered, as its length is part of the header.
+    /// Fails instead of panicking if the serialized data is too large to be decoded.
+    pub fn encode_to_writer(
+        &self,
+        options: &EncodeOptions,
+        mut writer: impl Write,
+    ) -> io::Result<()> {
+        if options.skipped {
+            info!("Skipped fetching, writing empty license data.");
+            return writer.flush();
+        }
+        let instant_before_compression = Instant::now();
+
+        // Archives are accessed in place, which compression would prevent.
+        #[cfg(feature = "rkyv")]
+        let compression = if options.format == SerializationFormat::Rkyv {
+            Compression::None
+        } else {
+            options.compression
+        };
+        #[cfg(not(feature = "rkyv"))]
+        let compression = options.compression;
+        let (compressed_data, compression) = match compression {
+            #[cfg(feature = "compress")]
+            Compression::Deflate => {
+                let mut deflate_writer = DeflateWriter::new(10);
+                self.serialize(options, &mut deflate_writer)?;
+                info!(
+                    "License data size: {} Bytes",
+                    deflate_writer.uncompressed_length
+                );
+                check_decodable_length(deflate_writer.uncompressed_length)?;
+                (deflate_writer.finish()?, Compression::Deflate)
+            }
+            Compression::None => {
+                let mut data = vec![];
+                self.serialize(options, &mut data)?;
+                info!("License data size: {} Bytes", data.len());
+                check_decodable_length(data.len())?;
+                (data, Compression::None)
+            }
+            Compression::Best => {
+                let mut data = vec![];
+                self.serialize(options, &mut data)?;
+                info!("License data size: {} Bytes", data.len());
+                check_decodable_length(data.len())?;
+
+                #[cfg(feature = "compress")]
+                {
+                    let mut deflate_writer = DeflateWriter::new(10);
+                    deflate_writer.write_all(&data)?;
+                    let deflated = deflate_writer.finish()?;
+                    if deflated.len() < data.len() {
+                        (deflated, Compression::Deflate)
+                    } else {
+                        (data, Compression::None)
+                    }
+                }
+                #[cfg(not(feature = "compress"))]
+                (data, Compression::None)
+            }
+        };
+        info!("Chosen compression: {:?}", compression);
+
+        info!(
+            "Compressed data size: {} Bytes in {}ms",
+            compressed_data.len(),
+            instant_before_compression.elapsed().as_millis()
+        );
+
+        #[cfg(feature = "signature")]
+        let signature = options.signing_key.map(|secret_key| {
+            use ed25519_dalek::{Signer, SigningKey};
+            SigningKey::from_bytes(&secret_key)
+                .sign(&compressed_data)
+                .to_bytes()
+        });
+        #[cfg(not(feature = "signature"))]
+        let signature: Option<[u8; encoding::SIGNATURE_LENGTH]> = None;
+
+        encoding::write_frame(
+            &mut writer,
+            options.format.flags() | compression.flags(),
+            &compressed_data,
+            signature.as_ref().map(|s| &s[..]),
+        )?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use crate::build_script::{generate_package_list_with_config, ConfigBuilder};
+    use crate::error::UnpackError;
+    use crate::{get_package_list_or_empty, get_package_list_with_build_info, AssetKind, Package};
+
+    #[test]
+    fn test_encode_round_trip() {
+        let package_list = PackageList(vec![Package::builder()
+            .name("license-fetcher")
+            .version("0.6.3")
+            .license_identifier("BSL-1.0")
+            .author("Me")
+            .license_file("LICENSE", "Boost Software License")
+            .asset(AssetKind::Data)
+            .build()]);
+        let build_info = BuildInfo {
+            license_fetcher_version: "0.6.3".to_owned(),
+            ..Default::default()
+        };
+
+        let encoded = package_list.encode(&EncodeOptions::default().build_info(build_info.clone()));
+        let (decoded, decoded_build_info) = get_package_list_with_build_info(&encoded).unwrap();
+
+        assert_eq!(decoded, package_list);
+        assert_eq!(decoded_build_info, Some(build_info.clone()));
+
+        for format in [
+            #[cfg(feature = "cbor")]
+            SerializationFormat::Cbor,
+            #[cfg(feature = "postcard")]
+            SerializationFormat::Postcard,
+            #[cfg(feature = "rkyv")]
+            SerializationFormat::Rkyv,
+        ] {
+            for compression in [Compression::None, Compression::Best] {
+                let encoded = package_list.encode(
+                    &EncodeOptions::default()
+                        .build_info(build_info.clone())
+                        .format(format)
+                        .compression(compression),
+                );
+                let (decoded, decoded_build_info) =
+                    get_package_list_with_build_info(&encoded).unwrap();
+
+                assert_eq!(decoded, package_list);
+                assert_eq!(decoded_build_info, Some(build_info.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_skipped_is_not_embedded() {
+        let config = ConfigBuilder::new(".", "license-fetcher")
+            .skip(true)
+            .build();
+        let package_list = generate_package_list_with_config(config);
+
+        let encoded = package_list.encode(&EncodeOptions::default().skipped(true));
+        assert!(encoded.is_empty());
+        let (decoded, embedded) = get_package_list_or_empty(&encoded).unwrap();
+        assert!(decoded.is_empty());
+        assert!(!embedded);
+
+        let encoded = package_list.encode(&EncodeOptions::default());
+        let (decoded, embedded) = get_package_list_or_empty(&encoded).unwrap();
+        assert!(decoded.is_empty());
+        assert!(embedded);
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn test_archived() {
+        let package_list = PackageList(vec![
+            Package::builder().name("log").version("0.4.22").build(),
+            Package::builder()
+                .name("serde")
+                .license_text("MIT License")
+                .build(),
+        ]);
+        let encoded = package_list.encode(
+            &EncodeOptions::default()
+                .format(SerializationFormat::Rkyv)
+                .compression(Compression::Best),
+        );
+        let mut aligned = rkyv::util::AlignedVec::<16>::new();
+        aligned.extend_from_slice(&encoded);
+
+        let archived = PackageList::archived(&aligned).unwrap();
+        assert_eq!(archived.0.len(), 2);
+        assert_eq!(archived.0[0].name, "log");
+        assert_eq!(archived.0[0].version, "0.4.22");
+        assert_eq!(
+            archived.0[1].license_text.as_ref().map(|text| &**text),
+            Some("MIT License")
+        );
+
+        let mut misaligned = rkyv::util::AlignedVec::<16>::new();
+        misaligned.extend_from_slice(&[0]);
+        misaligned.extend_from_slice(&encoded);
+        assert!(PackageList::archived(&misaligned[1..]).is_err());
+
+        let bincode = package_list.encode(&EncodeOptions::default());
+        assert!(matches!(
+            PackageList::archived(&bincode),
+            Err(UnpackError::UnsupportedFormat)
+        ));
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor_is_readable_by_generic_parsers() {
+        let package_list = PackageList(vec![Package::builder().name("log").build()]);
+        let encoded = package_list.encode(
+            &EncodeOptions::default()
+                .format(SerializationFormat::Cbor)
+                .compression(Compression::None),
+        );
+        let payload = encoding::unframe(&encoded).unwrap().payload;
+
+        let value: ciborium::Value = ciborium::from_reader(payload).unwrap();
+        let field = |value: &ciborium::Value, key: &str| {
+            value
+                .as_map()
+                .unwrap()
+                .iter()
+                .find(|(k, _)| k.as_text() == Some(key))
+                .map(|(_, v)| v.clone())
+                .unwrap()
+        };
+        let packages = field(&value, "packages");
+        let name = field(&packages.as_array().unwrap()[0], "name");
+        assert_eq!(name.as_text(), Some("log"));
+        assert!(field(&value, "build_info").is_null());
+    }
+
+    #[cfg(feature = "signature")]
+    #[test]
+    fn test_signature_verification() {
+        use ed25519_dalek::SigningKey;
+
+        let package_list = PackageList(vec![Package::builder().name("signed").build()]);
+        let secret_key = [7; 32];
+        let public_key = SigningKey::from_bytes(&secret_key)
+            .verifying_key()
+            .to_bytes();
+
+        let signed = package_list.encode(&EncodeOptions::default().signing_key(secret_key));
+        assert_eq!(
+            PackageList::from_encoded_verified(&signed, &public_key).unwrap(),
+            package_list
+        );
+
+        let other_key = SigningKey::from_bytes(&[8; 32]).verifying_key().to_bytes();
+        assert!(matches!(
+            PackageList::from_encoded_verified(&signed, &other_key),
+            Err(UnpackError::SignatureInvalid)
+        ));
+
+        let unsigned = package_list.encode(&EncodeOptions::default());
+        assert!(matches!(
+            PackageList::from_encoded_verified(&unsigned, &public_key),
+            Err(UnpackError::SignatureMissing)
+        ));
+    }
+
+    #[test]
+    fn test_embedded_fields() {
+        let package = Package::builder()
+            .name("a")
+            .version("1.0.0")
+            .author("Me")
+            .description("A crate.")
+            .manifest_path("/home/user/.cargo/registry/src/a-1.0.0/Cargo.toml")
+            .build();
+        let package_list = PackageList(vec![package]);
+
+        let encoded = package_list.encode(&EncodeOptions::default());
+        let (decoded, _) = get_package_list_with_build_info(&encoded).unwrap();
+        assert_eq!(decoded[0].manifest_path, None);
+        assert_eq!(decoded[0].authors, ["Me"]);
+
+        let encoded = package_list
+            .encode(&EncodeOptions::default().embed_fields(FieldMask::ALL - FieldMask::AUTHORS));
+        let (decoded, _) = get_package_list_with_build_info(&encoded).unwrap();
+        assert!(decoded[0].authors.is_empty());
+        assert_eq!(decoded[0].description.as_deref(), Some("A crate."));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let package_list = PackageList(vec![
+            Package::builder()
+                .name("a")
+                .version("1.0.0")
+                .author("Me")
+                .license_identifier("MIT")
+                .license_text("MIT License")
+                .build(),
+            Package::builder().name("b").renamed_as("c").build(),
+        ]);
+
+        for format in [
+            SerializationFormat::Bincode,
+            #[cfg(feature = "cbor")]
+            SerializationFormat::Cbor,
+            #[cfg(feature = "postcard")]
+            SerializationFormat::Postcard,
+            #[cfg(feature = "rkyv")]
+            SerializationFormat::Rkyv,
+        ] {
+            for compression in [Compression::None, Compression::Best] {
+                let encoded = package_list.encode(
+                    &EncodeOptions::default()
+                        .format(format)
+                        .compression(compression),
+                );
+                assert_eq!(PackageList::from_encoded(&encoded).unwrap(), package_list);
+
+                for end in 0..encoded.len() {
+                    assert!(PackageList::from_encoded(&encoded[..end]).is_err());
+                }
+
+                // Framed again with a valid checksum, so the corruption reaches the decoder.
+                let frame = encoding::unframe(&encoded).unwrap();
+                let flags = format.flags() | frame.compression.flags();
+                for i in 0..frame.payload.len() {
+                    for byte in [0x00, 0xFF, frame.payload[i] ^ 0x80] {
+                        let mut payload = frame.payload.to_vec();
+                        payload[i] = byte;
+                        let _ = PackageList::from_encoded(&encoding::frame(flags, &payload, None));
+                    }
+                    let truncated = encoding::frame(flags, &frame.payload[..i], None);
+                    let decoded = PackageList::from_encoded(&truncated);
+                    // rkyv archives are read from their end, so a prefix can be a valid archive on its own.
+                    #[cfg(feature = "rkyv")]
+                    if format == SerializationFormat::Rkyv {
+                        continue;
+                    }
+                    assert!(decoded.is_err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_corrupted_lengths_are_bounded() {
+        // No build info, followed by 2^40 packages.
+        let mut payload = vec![0, 0xFD];
+        payload.extend_from_slice(&(1u64 << 40).to_le_bytes());
+        let flags = SerializationFormat::Bincode.flags() | Compression::None.flags();
+        assert!(matches!(
+            PackageList::from_encoded(&encoding::frame(flags, &payload, None)),
+            Err(UnpackError::DecodeError(_))
+        ));
+
+        #[cfg(feature = "cbor")]
+        {
+            let mut payload = vec![0xA1, 0x68];
+            payload.extend_from_slice(b"packages");
+            payload.push(0x9B);
+            payload.extend_from_slice(&(1u64 << 40).to_be_bytes());
+            let flags = SerializationFormat::Cbor.flags() | Compression::None.flags();
+            assert!(matches!(
+                PackageList::from_encoded(&encoding::frame(flags, &payload, None)),
+                Err(UnpackError::DecodeError(_))
+            ));
+        }
+
+        // No build info, followed by 2^40 packages as varint.
+        #[cfg(feature = "postcard")]
+        {
+            let payload = [0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x20];
+            let flags = SerializationFormat::Postcard.flags() | Compression::None.flags();
+            assert!(matches!(
+                PackageList::from_encoded(&encoding::frame(flags, &payload, None)),
+                Err(UnpackError::DecodeError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_decoded_license_texts_are_shared() {
+        let package_list = PackageList(vec![
+            Package::builder().name("a").license_text("MIT").build(),
+            Package::builder().name("b").license_text("MIT").build(),
+        ]);
+        let encoded = package_list.encode(&EncodeOptions::default());
+        let (decoded, _) = get_package_list_with_build_info(&encoded).unwrap();
+
+        let texts: Vec<_> = decoded
+            .iter()
+            .map(|p| p.license_text.clone().unwrap())
+            .collect();
+        assert!(Arc::ptr_eq(&texts[0], &texts[1]));
+    }
+}
@@ -0,0 +1,92 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use std::io;
+use std::path::Path;
+
+use log::info;
+
+use super::fs::{create_dir_all, write};
+use crate::PackageList;
+
+impl PackageList {
+    /// Writes the license files of every package into `<dir>/<name>-<version>/`.
+    ///
+    /// The original file names of [Package::license_files](crate::Package::license_files) and
+    /// [Package::license_translations](crate::Package::license_translations) are kept.
+    /// Packages with a license text, but without recorded files (e.g. taken from a README) get a single `LICENSE` file.
+    /// Meant for installers that need to ship license files on disk.
+    pub fn export_license_files(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        for package in self.iter() {
+            let Some(license_text) = &package.license_text else {
+                continue;
+            };
+
+            let package_dir = dir
+                .as_ref()
+                .join(format!("{}-{}", package.name, package.version));
+            create_dir_all(&package_dir)?;
+
+            if package.license_files.is_empty() {
+                write(package_dir.join("LICENSE"), license_text.as_bytes())?;
+                continue;
+            }
+
+            let translations = package
+                .license_translations
+                .iter()
+                .map(|translation| (&translation.file_name, translation.text.as_str()));
+            for (file_name, text) in package
+                .license_file_texts()
+                .map(|(file, text)| (&file.file_name, text))
+                .chain(translations)
+            {
+                let Some(file_name) = Path::new(file_name).file_name() else {
+                    continue;
+                };
+                write(package_dir.join(file_name), text)?;
+            }
+        }
+
+        info!("Exported license files to: {:?}", dir.as_ref());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::build_script::fs::test_dir;
+    use crate::Package;
+
+    #[test]
+    fn test_export_license_files() {
+        let package_list = PackageList(vec![
+            Package::builder()
+                .name("a")
+                .version("1.0.0")
+                .license_file("LICENSE-MIT", "MIT")
+                .license_file("LICENSE-APACHE", "Apache")
+                .build(),
+            Package::builder()
+                .name("b")
+                .version("0.1.0")
+                .license_text("Readme section")
+                .build(),
+        ]);
+
+        let dir = test_dir("export");
+        package_list.export_license_files(&dir).unwrap();
+
+        let read = |path: &str| std::fs::read_to_string(dir.join(path)).unwrap();
+        assert_eq!(read("a-1.0.0/LICENSE-MIT"), "MIT");
+        assert_eq!(read("a-1.0.0/LICENSE-APACHE"), "Apache");
+        assert_eq!(read("b-0.1.0/LICENSE"), "Readme section");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
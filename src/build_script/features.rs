@@ -0,0 +1,140 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Features that pulled optional dependencies into the dependency graph.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Normal dependency edge between two packages in use.
+pub(super) struct FeatureEdge<'a> {
+    pub parent: &'a str,
+    pub child: &'a str,
+    /// Features like `reqwest/native-tls` that activated an optional dependency. `None` if it is not optional.
+    pub activated_by: Option<Vec<String>>,
+}
+
+/// Returns `true` if a value of a feature table like `dep:openssl` or `openssl/vendored` enables the dependency.
+///
+/// `dependency?/feature` only enables a feature if the dependency is enabled otherwise.
+fn activates(value: &str, dependency: &str) -> bool {
+    let name = value.strip_prefix("dep:").unwrap_or(value);
+    let name = name.split_once('/').map_or(name, |(name, _)| name);
+    name == dependency
+}
+
+/// Enabled features of a package that activate its optional dependency, formatted like `reqwest/native-tls`.
+///
+/// `dependency` is the name of the dependency in the manifest, which is the rename if it is renamed.
+/// Falls back to the implicit feature named after the dependency.
+pub(super) fn activating_features(
+    package_name: &str,
+    features: &BTreeMap<String, Vec<String>>,
+    enabled: &[String],
+    dependency: &str,
+) -> Vec<String> {
+    let activating: Vec<String> = enabled
+        .iter()
+        .filter(|feature| {
+            features
+                .get(*feature)
+                .is_some_and(|values| values.iter().any(|value| activates(value, dependency)))
+        })
+        .map(|feature| format!("{}/{}", package_name, feature))
+        .collect();
+    if activating.is_empty() {
+        vec![format!("{}/{}", package_name, dependency)]
+    } else {
+        activating
+    }
+}
+
+/// Features each package depends on, by package id. Packages in use regardless of features are left out.
+///
+/// A package is unconditional if a package that is unconditional itself depends on it without it being optional.
+/// Otherwise it inherits the features of the packages depending on it, plus the features activating it where
+/// it is optional.
+pub(super) fn enabled_by_features(
+    root: &str,
+    edges: &[FeatureEdge],
+) -> BTreeMap<String, Vec<String>> {
+    // `None` marks packages that are in use regardless of features.
+    let mut conditions: BTreeMap<&str, Option<BTreeSet<String>>> = BTreeMap::from([(root, None)]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(parent) = queue.pop_front() {
+        let parent_condition = conditions[parent].clone();
+        for edge in edges.iter().filter(|edge| edge.parent == parent) {
+            let condition = match &edge.activated_by {
+                Some(features) => Some(features.iter().cloned().collect()),
+                None => parent_condition.clone(),
+            };
+            let changed = match (conditions.get_mut(edge.child), condition) {
+                (Some(None), _) => false,
+                (Some(existing), None) => {
+                    *existing = None;
+                    true
+                }
+                (Some(Some(existing)), Some(features)) => {
+                    let len = existing.len();
+                    existing.extend(features);
+                    existing.len() != len
+                }
+                (None, condition) => {
+                    conditions.insert(edge.child, condition);
+                    true
+                }
+            };
+            if changed {
+                queue.push_back(edge.child);
+            }
+        }
+    }
+
+    conditions
+        .into_iter()
+        .filter_map(|(id, condition)| Some((id.to_owned(), condition?.into_iter().collect())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enabled_by_features() {
+        let features = BTreeMap::from([
+            ("default".to_owned(), vec!["tls".to_owned()]),
+            ("tls".to_owned(), vec!["dep:openssl".to_owned()]),
+            ("log".to_owned(), vec!["dep:log".to_owned()]),
+            ("serde".to_owned(), vec!["url?/serde".to_owned()]),
+        ]);
+        let enabled = ["default", "tls", "serde"].map(String::from);
+        assert_eq!(
+            activating_features("app", &features, &enabled, "openssl"),
+            ["app/tls"]
+        );
+        assert_eq!(
+            activating_features("app", &features, &enabled, "url"),
+            ["app/url"]
+        );
+
+        // app -> openssl (optional) -> openssl-sys, app -> log -> cfg-if, openssl -> cfg-if
+        let edge = |parent, child, activated_by: Option<&str>| FeatureEdge {
+            parent,
+            child,
+            activated_by: activated_by.map(|feature| vec![feature.to_owned()]),
+        };
+        let edges = [
+            edge("app", "openssl", Some("app/tls")),
+            edge("openssl", "openssl-sys", None),
+            edge("openssl", "cfg-if", None),
+            edge("app", "log", None),
+            edge("log", "cfg-if", None),
+        ];
+        let conditions = enabled_by_features("app", &edges);
+        assert_eq!(conditions.len(), 2);
+        assert_eq!(conditions["openssl"], ["app/tls"]);
+        assert_eq!(conditions["openssl-sys"], ["app/tls"]);
+    }
+}
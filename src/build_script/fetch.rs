@@ -0,0 +1,118 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Building blocks of the license fetching for tools outside of build scripts.
+//!
+//! These functions are part of the public API and follow semantic versioning like the rest of the crate.
+//! Unlike [generate_package_list_with_config](super::generate_package_list_with_config) they do not run cargo,
+//! so they can be used with package lists from other sources.
+//!
+//! # Example
+//! ```no_run
+//! use license_fetcher::build_script::{fetch, ConfigBuilder};
+//! use license_fetcher::{Package, PackageList};
+//!
+//! let config = ConfigBuilder::new(".", "my-tool").build();
+//! let mut package_list = PackageList(vec![Package::builder().name("log").version("0.4.22").build()]);
+//!
+//! let report = fetch::fetch_licenses(&mut package_list, &config);
+//! assert!(report.is_clean());
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::backend::{fetch_from_registered_backends, LicenseSourceBackend, RegistryBackend};
+use super::cargo_source::{
+    fetch_license_for_package, licenses_text_from_patched_sources, src_registry_folders,
+};
+use super::config::Config;
+use super::report::FetchReport;
+use super::resolve_packages;
+use crate::{Package, PackageList};
+
+/// Source folders of all registries in a cargo home, like `~/.cargo/registry/src/index.crates.io-6f17d22bba15001f`.
+pub fn registry_src_folders(cargo_home: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    src_registry_folders(cargo_home.as_ref())
+}
+
+/// Fills license text and, if missing, license identifier of a package from a folder containing its sources.
+///
+/// Tries license files first, then falls back to the README if enabled with
+/// [ConfigBuilder::readme_fallback](super::ConfigBuilder::readme_fallback), and SPDX source headers.
+/// Licenses of SPDX headers get their standard text from the SPDX license list, without copyright notices.
+pub fn scan_folder(
+    package: &mut Package,
+    folder: impl AsRef<Path>,
+    config: &Config,
+) -> FetchReport {
+    let mut report = FetchReport::default();
+    fetch_license_for_package(package, &folder.as_ref().to_path_buf(), config, &mut report);
+    report
+}
+
+/// Fetches the licenses of all packages without license text from the registries of the cargo home.
+///
+/// The cargo home is taken from `CARGO_HOME` or defaults to `~/.cargo`. Packages marked as
+/// [patched](crate::Package::patched) are read from the folder of their manifest instead.
+/// Afterwards the [registered backends](super::ConfigBuilder::license_source) run. No cache is used.
+///
+/// Panics if the cargo home or its registry folder does not exist.
+pub fn fetch_licenses(package_list: &mut PackageList, config: &Config) -> FetchReport {
+    let mut report = FetchReport::default();
+    licenses_text_from_patched_sources(package_list, config, &mut report);
+    RegistryBackend.fetch(package_list, config, &mut report);
+    fetch_from_registered_backends(package_list, config, &mut report);
+    report
+}
+
+/// Fetches the license of a single package of the project at [Config::manifest_dir], without fetching the others.
+///
+/// The package is looked up in the resolved dependencies, so its license text is read from the folder cargo uses,
+/// whether it comes from a registry, git or a vendored or local path. Packages the project does not depend on are
+/// looked up by name and version in the registries of the cargo home and only get a license text.
+/// Afterwards the [registered backends](super::ConfigBuilder::license_source) run. No cache is used.
+///
+/// Returns `None` if the package is neither a dependency nor found in a registry. Panics like
+/// [generate_package_list_with_config](super::generate_package_list_with_config) if cargo fails.
+///
+/// # Example
+/// ```no_run
+/// use license_fetcher::build_script::{fetch, ConfigBuilder};
+///
+/// let config = ConfigBuilder::new(".", "my-tool").build();
+/// if let Some((package, _report)) = fetch::license_for("log", "0.4.22", &config) {
+///     println!("{}", package.license_text.as_deref().unwrap_or_default());
+/// }
+/// ```
+pub fn license_for(name: &str, version: &str, config: &Config) -> Option<(Package, FetchReport)> {
+    let mut report = FetchReport::default();
+    let dependency = resolve_packages(config, &mut report.cargo_attempts)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .0
+        .into_iter()
+        .find(|package| package.name == name && package.version == version);
+    let is_dependency = dependency.is_some();
+
+    let mut package_list =
+        PackageList(vec![dependency.unwrap_or_else(|| {
+            Package::builder().name(name).version(version).build()
+        })]);
+    let folder = package_list[0]
+        .manifest_path
+        .as_deref()
+        .and_then(|manifest_path| Path::new(manifest_path).parent())
+        .map(Path::to_path_buf);
+    match folder {
+        Some(folder) => {
+            fetch_license_for_package(&mut package_list[0], &folder, config, &mut report)
+        }
+        None => RegistryBackend.fetch(&mut package_list, config, &mut report),
+    }
+    fetch_from_registered_backends(&mut package_list, config, &mut report);
+
+    let package = package_list.0.pop()?;
+    (is_dependency || package.license_text.is_some()).then_some((package, report))
+}
@@ -0,0 +1,132 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Wrappers of [std::fs] functions whose errors name the offending path.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Prepends the path to the message of an IO error. The error kind is kept.
+pub(super) fn with_path(path: &Path, error: io::Error) -> io::Error {
+    io::Error::new(error.kind(), format!("{:?}: {}", path, error))
+}
+
+pub(super) fn create_dir_all(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    fs::create_dir_all(path).map_err(|e| with_path(path, e))
+}
+
+pub(super) fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    fs::write(path, contents).map_err(|e| with_path(path, e))
+}
+
+/// Writes to a temporary file next to `path` and renames it, so readers never see a partially written file.
+pub(super) fn write_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = PathBuf::from(temp_path);
+
+    write(&temp_path, contents)?;
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        with_path(path, e)
+    })
+}
+
+pub(super) fn read(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    let path = path.as_ref();
+    fs::read(path).map_err(|e| with_path(path, e))
+}
+
+pub(super) fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+    let path = path.as_ref();
+    fs::read_to_string(path).map_err(|e| with_path(path, e))
+}
+
+/// Creates an empty directory for a test, unique per process and call.
+#[cfg(test)]
+pub(super) fn test_dir(name: &str) -> PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let dir = std::env::temp_dir().join(format!(
+        "license-fetcher-test-{}-{}-{}",
+        name,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Strips the verbatim prefix `\\?\` of Windows paths where it is safe, see [dunce::simplified].
+///
+/// Cargo reports paths without the prefix, while `CARGO_HOME` or canonicalized paths may have it, so paths
+/// need to be simplified before comparing them. Verbatim UNC paths, paths with reserved names like `NUL` and
+/// paths longer than 260 characters are kept. Other platforms are not affected.
+pub(super) fn simplified(path: &Path) -> PathBuf {
+    dunce::simplified(path).to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_contains_path() {
+        let path = test_dir("missing-file").join("LICENSE");
+        let error = read(&path).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().contains("missing-file"));
+    }
+
+    /// Length from which Windows paths need the verbatim prefix `\\?\`.
+    #[cfg(windows)]
+    const MAX_PATH: usize = 260;
+
+    #[cfg(windows)]
+    #[test]
+    fn test_long_path() {
+        let mut path = test_dir("long-path");
+        while path.as_os_str().len() <= MAX_PATH {
+            path.push("a".repeat(50));
+        }
+        create_dir_all(&path).unwrap();
+        write(path.join("LICENSE"), "MIT").unwrap();
+        assert_eq!(read_to_string(path.join("LICENSE")).unwrap(), "MIT");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_simplified() {
+        let simplifies = |path: &str| simplified(Path::new(path)) != Path::new(path);
+        assert_eq!(
+            simplified(Path::new(r"\\?\C:\Users\me\.cargo")),
+            PathBuf::from(r"C:\Users\me\.cargo")
+        );
+        assert!(!simplifies(r"\\?\UNC\server\share\.cargo"));
+        assert!(!simplifies(r"\\?\C:\Users\NUL\.cargo"));
+        assert!(!simplifies(r"\\?\C:\Users\con.txt"));
+        assert!(!simplifies(r"\\?\C:\Users\me.\.cargo"));
+        assert!(!simplifies(&format!(r"\\?\C:\{}", "a".repeat(MAX_PATH))));
+        assert_eq!(
+            simplified(Path::new(r"C:\Users")),
+            PathBuf::from(r"C:\Users")
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_simplified() {
+        let path = Path::new(r"\\?\C:\Users\me\.cargo");
+        assert_eq!(simplified(path), path);
+    }
+}
@@ -0,0 +1,260 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Import of license clarifications and allow lists of `cargo-deny` and `cargo-about`, and of asset lists.
+
+use std::io;
+use std::path::Path;
+
+use log::warn;
+use serde::Deserialize;
+use toml::Table;
+
+use super::config::{ConfigBuilder, LicenseOverride};
+use super::fs::read_to_string;
+use crate::{AssetKind, Package};
+
+#[derive(Deserialize, Default)]
+struct DenyConfig {
+    #[serde(default)]
+    licenses: DenyLicenses,
+}
+
+#[derive(Deserialize, Default)]
+struct DenyLicenses {
+    #[serde(default)]
+    allow: Vec<String>,
+    #[serde(default)]
+    clarify: Vec<DenyClarification>,
+}
+
+#[derive(Deserialize)]
+struct DenyClarification {
+    #[serde(alias = "name")]
+    #[serde(rename = "crate")]
+    krate: String,
+    version: Option<String>,
+    expression: String,
+}
+
+#[derive(Deserialize, Default)]
+struct AssetList {
+    #[serde(default)]
+    asset: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+    kind: AssetKind,
+    version: Option<String>,
+    license: String,
+    license_file: Option<String>,
+    #[serde(default)]
+    authors: Vec<String>,
+    homepage: Option<String>,
+    repository: Option<String>,
+}
+
+fn parse_toml<T: serde::de::DeserializeOwned>(path: &Path) -> io::Result<T> {
+    toml::from_str(&read_to_string(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Converts a version requirement into an exact version. Other requirements match all versions.
+fn exact_version(name: &str, requirement: Option<&str>) -> Option<String> {
+    let requirement = requirement?.trim();
+    let version = requirement.strip_prefix('=').unwrap_or(requirement).trim();
+    if version == "*" {
+        return None;
+    }
+    if version.contains(['<', '>', '^', '~', '*', ',']) {
+        warn!(
+            "Version requirement {:?} of {} is not supported. Matching all versions.",
+            requirement, name
+        );
+        return None;
+    }
+    Some(version.to_owned())
+}
+
+impl ConfigBuilder {
+    /// Imports the license clarifications and allowed licenses of a `cargo-deny` config (`deny.toml`).
+    ///
+    /// `[[licenses.clarify]]` entries are added as [LicenseOverride]s and `licenses.allow` as allowed licenses.
+    /// Version requirements other than exact versions match all versions of a crate.
+    pub fn import_cargo_deny(mut self, path: impl AsRef<Path>) -> io::Result<Self> {
+        let deny: DenyConfig = parse_toml(path.as_ref())?;
+
+        for clarification in deny.licenses.clarify {
+            let (name, spec_version) = match clarification.krate.split_once('@') {
+                Some((name, version)) => (name.to_owned(), Some(version)),
+                None => (clarification.krate.clone(), None),
+            };
+            let version = exact_version(&name, spec_version.or(clarification.version.as_deref()));
+            self = self.license_override(LicenseOverride::new(
+                name,
+                version,
+                clarification.expression,
+            ));
+        }
+
+        Ok(self.allowed_licenses(deny.licenses.allow))
+    }
+
+    /// Imports the license clarifications and accepted licenses of a `cargo-about` config (`about.toml`).
+    ///
+    /// `[<crate>.clarify]` tables are added as [LicenseOverride]s and `accepted` as allowed licenses.
+    pub fn import_cargo_about(mut self, path: impl AsRef<Path>) -> io::Result<Self> {
+        let about: Table = parse_toml(path.as_ref())?;
+
+        for (name, value) in &about {
+            let Some(expression) = value
+                .get("clarify")
+                .and_then(|clarify| clarify.get("license"))
+                .and_then(|license| license.as_str())
+            else {
+                continue;
+            };
+            self = self.license_override(LicenseOverride::new(name.clone(), None, expression));
+        }
+
+        let accepted = about
+            .get("accepted")
+            .and_then(|accepted| accepted.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|license| license.as_str());
+
+        Ok(self.allowed_licenses(accepted))
+    }
+
+    /// Imports assets like fonts, icons and data sets from a TOML file as extra packages.
+    ///
+    /// Every `[[asset]]` entry needs a `name`, a `kind` (`font`, `icon`, `data`, `model` or `other`) and a
+    /// `license` expression. `version`, `license_file`, `authors`, `homepage` and `repository` are optional.
+    /// License files are read relative to the folder of the TOML file. Fails if a license file can not be read.
+    ///
+    /// ```toml
+    /// [[asset]]
+    /// name = "Fira Sans"
+    /// kind = "font"
+    /// version = "4.301"
+    /// license = "OFL-1.1"
+    /// license_file = "fonts/OFL.txt"
+    /// ```
+    ///
+    /// See [ConfigBuilder::extra_package].
+    pub fn import_assets(mut self, path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let assets: AssetList = parse_toml(path)?;
+        let folder = path.parent().unwrap_or(Path::new(""));
+
+        for asset in assets.asset {
+            let mut builder = Package::builder()
+                .name(asset.name)
+                .version(asset.version.unwrap_or_default())
+                .license_identifier(asset.license)
+                .asset(asset.kind);
+            for author in asset.authors {
+                builder = builder.author(author);
+            }
+            if let Some(homepage) = asset.homepage {
+                builder = builder.homepage(homepage);
+            }
+            if let Some(repository) = asset.repository {
+                builder = builder.repository(repository);
+            }
+            if let Some(license_file) = asset.license_file {
+                builder = builder.license_text(read_to_string(folder.join(license_file))?);
+            }
+            self = self.extra_package(builder.build());
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::build_script::fs::test_dir;
+
+    #[test]
+    fn test_import_cargo_deny() {
+        let path = test_dir("deny").join("deny.toml");
+        std::fs::write(
+            &path,
+            r#"
+            [licenses]
+            allow = ["MIT", "Apache-2.0"]
+
+            [[licenses.clarify]]
+            crate = "ring"
+            expression = "MIT AND ISC AND OpenSSL"
+
+            [[licenses.clarify]]
+            name = "webpki"
+            version = "=0.22.0"
+            expression = "ISC"
+            "#,
+        )
+        .unwrap();
+
+        let config = ConfigBuilder::new(".", "test")
+            .import_cargo_deny(&path)
+            .unwrap()
+            .build();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.allowed_licenses, ["MIT", "Apache-2.0"]);
+        assert_eq!(
+            config.license_overrides,
+            [
+                LicenseOverride::new("ring", None, "MIT AND ISC AND OpenSSL"),
+                LicenseOverride::new("webpki", Some("0.22.0".to_owned()), "ISC"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_assets() {
+        let dir = test_dir("assets");
+        std::fs::create_dir_all(dir.join("fonts")).unwrap();
+        std::fs::write(dir.join("fonts/OFL.txt"), "SIL OPEN FONT LICENSE").unwrap();
+        std::fs::write(
+            dir.join("assets.toml"),
+            r#"
+            [[asset]]
+            name = "Fira Sans"
+            kind = "font"
+            version = "4.301"
+            license = "OFL-1.1"
+            license_file = "fonts/OFL.txt"
+
+            [[asset]]
+            name = "Tabler Icons"
+            kind = "icon"
+            license = "MIT"
+            "#,
+        )
+        .unwrap();
+
+        let config = ConfigBuilder::new(".", "test")
+            .import_assets(dir.join("assets.toml"))
+            .unwrap()
+            .build();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let [font, icons] = &config.extra_packages[..] else {
+            panic!("expected two assets");
+        };
+        assert_eq!(font.asset, Some(AssetKind::Font));
+        assert_eq!(font.license_text.as_deref(), Some("SIL OPEN FONT LICENSE"));
+        assert_eq!(icons.asset, Some(AssetKind::Icon));
+        assert_eq!(icons.license_text, None);
+    }
+}
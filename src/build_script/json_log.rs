@@ -0,0 +1,112 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Log output as JSON lines on stderr for [LogFormat::Json].
+
+use std::io::{stderr, Write};
+
+use log::{LevelFilter, Log, Metadata, Record};
+use serde_json::{json, Value};
+
+use super::config::{Config, LogFormat};
+use super::report::FetchReport;
+
+/// Logger writing one JSON object per record to stderr.
+struct JsonLogger;
+
+impl Log for JsonLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        emit(json!({
+            "type": "log",
+            "level": record.level().as_str().to_ascii_lowercase(),
+            "target": record.target(),
+            "message": record.args().to_string(),
+        }));
+    }
+
+    fn flush(&self) {
+        let _ = stderr().flush();
+    }
+}
+
+fn emit(line: Value) {
+    let _ = writeln!(stderr().lock(), "{}", line);
+}
+
+/// Installs the JSON logger for [LogFormat::Json], unless another logger is already installed.
+pub(super) fn init(log_format: LogFormat) {
+    if log_format != LogFormat::Json {
+        return;
+    }
+    if log::set_boxed_logger(Box::new(JsonLogger)).is_ok() {
+        log::set_max_level(LevelFilter::Info);
+    }
+}
+
+fn to_strings<T: ToString>(items: &[T]) -> Vec<String> {
+    items.iter().map(ToString::to_string).collect()
+}
+
+/// The final summary line of [LogFormat::Json].
+fn summary(package_count: usize, report: &FetchReport) -> Value {
+    json!({
+        "type": "summary",
+        "packages": package_count,
+        "clean": report.is_clean(),
+        "failures": to_strings(&report.failures),
+        "policy_violations": to_strings(&report.policy_violations),
+        "source_warnings": to_strings(&report.source_warnings),
+        "copyleft_warnings": to_strings(&report.copyleft_warnings),
+        "license_changes": to_strings(&report.license_changes),
+        "cache": report.cache.map(|cache| json!({
+            "restored": cache.restored,
+            "missed": cache.missed,
+            "uncacheable": cache.uncacheable,
+        })),
+        "timings": report
+            .timings
+            .iter()
+            .map(|timing| json!({
+                "stage": format!("{:?}", timing.stage),
+                "millis": timing.duration.as_millis(),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Writes the summary of a run to stderr for [LogFormat::Json].
+pub(super) fn finish(config: &Config, package_count: usize, report: &FetchReport) {
+    if config.log_format == LogFormat::Json {
+        emit(summary(package_count, report));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::build_script::PolicyViolation;
+
+    #[test]
+    fn test_summary() {
+        let report = FetchReport {
+            policy_violations: vec![PolicyViolation {
+                package_name: "a".to_owned(),
+                package_version: "1.0.0".to_owned(),
+                license_identifier: None,
+            }],
+            ..Default::default()
+        };
+        let summary = summary(2, &report);
+        assert_eq!(summary["type"], "summary");
+        assert_eq!(summary["packages"], 2);
+        assert_eq!(summary["clean"], false);
+        assert_eq!(summary["policy_violations"].as_array().unwrap().len(), 1);
+    }
+}
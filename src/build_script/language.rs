@@ -0,0 +1,98 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Heuristic detection of translated license files.
+
+/// Language codes recognized in file names like `LICENSE.ja.md` or `LICENSE-zh_CN`.
+const LANGUAGE_CODES: &[&str] = &[
+    "ar", "cs", "da", "de", "el", "es", "fa", "fi", "fr", "he", "hi", "hu", "it", "ja", "jp", "ko",
+    "kr", "nl", "pl", "pt", "ro", "ru", "sv", "th", "tr", "uk", "vi", "zh",
+];
+
+/// Share of letters from non-Latin scripts above which a text is considered a translation.
+const NON_LATIN_THRESHOLD: f32 = 0.3;
+
+fn language_from_file_name(file_name: &str) -> Option<String> {
+    file_name
+        .split(['.', '-', '_'])
+        .skip(1)
+        .map(|part| part.to_ascii_lowercase())
+        .find(|part| LANGUAGE_CODES.contains(&part.as_str()))
+        .map(|code| match code.as_str() {
+            "jp" => "ja".to_owned(),
+            "kr" => "ko".to_owned(),
+            _ => code,
+        })
+}
+
+fn language_from_text(text: &str) -> Option<String> {
+    let (mut letters, mut non_latin) = (0usize, 0usize);
+    let (mut kana, mut hangul, mut han, mut cyrillic) = (0usize, 0usize, 0usize, 0usize);
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        if c <= '\u{024F}' {
+            continue;
+        }
+        non_latin += 1;
+        match c {
+            '\u{3040}'..='\u{30FF}' => kana += 1,
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => hangul += 1,
+            '\u{4E00}'..='\u{9FFF}' => han += 1,
+            '\u{0400}'..='\u{04FF}' => cyrillic += 1,
+            _ => {}
+        }
+    }
+    if letters == 0 || (non_latin as f32) < letters as f32 * NON_LATIN_THRESHOLD {
+        return None;
+    }
+
+    let language = if kana > 0 {
+        "ja"
+    } else if hangul > 0 {
+        "ko"
+    } else if han > 0 {
+        "zh"
+    } else if cyrillic > 0 {
+        "ru"
+    } else {
+        "und"
+    };
+    Some(language.to_owned())
+}
+
+/// Detects the language of a license file that is not in English.
+///
+/// Returns `None` for English or undetected texts. Otherwise returns an ISO 639-1 code taken from the
+/// file name, or guessed from the script of the text. `und` is returned for unknown non-Latin scripts.
+pub(super) fn translation_language(file_name: &str, text: &str) -> Option<String> {
+    language_from_file_name(file_name).or_else(|| language_from_text(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translation_language() {
+        assert_eq!(translation_language("LICENSE-MIT", "MIT License"), None);
+        assert_eq!(translation_language("LICENSE-APACHE", "Apache"), None);
+        assert_eq!(
+            translation_language("LICENSE.ja.md", "MIT License"),
+            Some("ja".to_owned())
+        );
+        assert_eq!(
+            translation_language("LICENSE_zh-CN", "MIT"),
+            Some("zh".to_owned())
+        );
+        assert_eq!(
+            translation_language("LICENSE-JP", "MIT"),
+            Some("ja".to_owned())
+        );
+        assert_eq!(
+            translation_language("COPYING", "以下に定める条件に従い、本ソフトウェア"),
+            Some("ja".to_owned())
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Resolving packages from `Cargo.lock` for builds without cargo.
+//!
+//! `Cargo.lock` does not distinguish normal, build and dev dependencies or target platforms, so all of them end up
+//! in the package list.
+//! License identifiers and authors are read from the manifests of the packages in the registry.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use serde::Deserialize;
+
+use super::build_info::find_lockfile;
+use super::cargo_source::registry_src_folders;
+use super::config::Config;
+use super::crates_io_url;
+use super::fs::read_to_string;
+use super::report::BuildError;
+use crate::{Package, PackageList};
+
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockfilePackage>,
+}
+
+#[derive(Deserialize)]
+struct LockfilePackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+    /// Entries like `log`, `log 0.4.22` or `log 0.4.22 (registry+https://...)`.
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+/// Finds the package a dependency entry of the lockfile refers to.
+fn resolve_dependency(packages: &[LockfilePackage], dependency: &str) -> Option<usize> {
+    let mut parts = dependency.split_whitespace();
+    let name = parts.next()?;
+    let version = parts.next();
+    packages.iter().position(|package| {
+        package.name == name && version.is_none_or(|version| package.version == version)
+    })
+}
+
+/// Fields of the `[package]` table of a manifest. Fields inherited from a workspace are skipped.
+fn read_manifest(path: &Path, package: &mut Package) {
+    let manifest = match read_to_string(path).map(|text| toml::from_str::<toml::Table>(&text)) {
+        Ok(Ok(manifest)) => manifest,
+        Ok(Err(e)) => {
+            warn!("Failed parsing {:?}: {}", path, e);
+            return;
+        }
+        Err(e) => {
+            warn!("{}", e);
+            return;
+        }
+    };
+    let Some(table) = manifest.get("package").and_then(toml::Value::as_table) else {
+        return;
+    };
+    let string = |key: &str| {
+        table
+            .get(key)
+            .and_then(toml::Value::as_str)
+            .map(str::to_owned)
+    };
+
+    package.license_identifier = string("license");
+    package.description = string("description");
+    package.homepage = string("homepage");
+    package.repository = string("repository");
+    package.authors = table
+        .get("authors")
+        .and_then(toml::Value::as_array)
+        .map(|authors| {
+            authors
+                .iter()
+                .filter_map(toml::Value::as_str)
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default();
+    package.manifest_path = Some(path.to_string_lossy().into_owned());
+}
+
+/// Manifest of a package in one of the registry source folders.
+fn registry_manifest(src_folders: &[PathBuf], package: &Package) -> Option<PathBuf> {
+    src_folders
+        .iter()
+        .map(|folder| {
+            folder
+                .join(format!("{}-{}", package.name, package.version))
+                .join("Cargo.toml")
+        })
+        .find(|path| path.is_file())
+}
+
+/// Packages reachable from the package itself according to the `Cargo.lock` within [Config::dependency_depth],
+/// including the package itself.
+///
+/// Fails if the `Cargo.lock` can not be read or does not contain the package.
+pub(super) fn package_list_from_lockfile(config: &Config) -> Result<PackageList, BuildError> {
+    let lockfile_error = |message: String, cause: Option<Box<dyn Error + Send + Sync>>| {
+        BuildError::Lockfile { message, cause }
+    };
+    let path = find_lockfile(&config.manifest_dir).ok_or_else(|| {
+        lockfile_error(
+            format!(
+                "Cargo.lock not found in {:?} or its parents.",
+                config.manifest_dir
+            ),
+            None,
+        )
+    })?;
+    let lockfile: Lockfile = read_to_string(&path)
+        .map_err(Into::into)
+        .and_then(|text| toml::from_str(&text).map_err(Into::into))
+        .map_err(|e| lockfile_error(format!("Failed reading {:?}", path), Some(e)))?;
+
+    let root = lockfile
+        .package
+        .iter()
+        .position(|package| package.name == config.package_name && package.source.is_none())
+        .ok_or_else(|| {
+            lockfile_error(
+                format!("Package {} not found in {:?}.", config.package_name, path),
+                None,
+            )
+        })?;
+
+    // Breadth first, so every package is reached on its shortest path.
+    let max_depth = config.dependency_depth.limit();
+    let mut used = BTreeSet::from([root]);
+    let mut queue = VecDeque::from([(root, 0)]);
+    while let Some((index, depth)) = queue.pop_front() {
+        if max_depth.is_some_and(|max_depth| depth >= max_depth) {
+            continue;
+        }
+        for dependency in lockfile.package[index].dependencies.iter() {
+            match resolve_dependency(&lockfile.package, dependency) {
+                Some(dependency) if used.insert(dependency) => {
+                    queue.push_back((dependency, depth + 1))
+                }
+                Some(_) => {}
+                None => warn!("Dependency {} not found in Cargo.lock.", dependency),
+            }
+        }
+    }
+
+    let src_folders = if used
+        .iter()
+        .any(|&index| lockfile.package[index].source.is_some())
+    {
+        registry_src_folders()
+    } else {
+        vec![]
+    };
+
+    let package_list: Vec<Package> = used
+        .into_iter()
+        .map(|index| {
+            let locked = &lockfile.package[index];
+            let mut package = Package {
+                name: locked.name.clone(),
+                version: locked.version.clone(),
+                registry_url: crates_io_url(
+                    &locked.name,
+                    &locked.version,
+                    locked.source.as_deref(),
+                ),
+                ..Default::default()
+            };
+            let manifest = if index == root {
+                Some(config.manifest_dir.join("Cargo.toml"))
+            } else {
+                registry_manifest(&src_folders, &package)
+            };
+            match manifest {
+                Some(manifest) => read_manifest(&manifest, &mut package),
+                None => warn!(
+                    "Manifest of {} {} not found. Its license is unknown.",
+                    package.name, package.version
+                ),
+            }
+            package
+        })
+        .collect();
+    info!("Read {} packages from {:?}.", package_list.len(), path);
+
+    Ok(PackageList(package_list))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_dependency() {
+        let package = |name: &str, version: &str| LockfilePackage {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            source: None,
+            dependencies: vec![],
+        };
+        let packages = [
+            package("log", "0.3.9"),
+            package("log", "0.4.22"),
+            package("cfg-if", "1.0.0"),
+        ];
+
+        assert_eq!(resolve_dependency(&packages, "cfg-if"), Some(2));
+        assert_eq!(resolve_dependency(&packages, "log 0.4.22"), Some(1));
+        assert_eq!(
+            resolve_dependency(
+                &packages,
+                "log 0.3.9 (registry+https://github.com/rust-lang/crates.io-index)"
+            ),
+            Some(0)
+        );
+        assert_eq!(resolve_dependency(&packages, "serde"), None);
+    }
+}
@@ -3,10 +3,18 @@
 //         (See accompanying file LICENSE or copy at
 //          https://www.boost.org/LICENSE_1_0.txt)
 
-
 use std::cmp;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::io::Read;
 
 use serde::Deserialize;
+use serde_json::from_reader;
+
+use super::config::DependencyDepth;
+use super::crates_io_url;
+use super::features::{activating_features, enabled_by_features, FeatureEdge};
+
+use crate::{ExtendedMetadata, Package, PackageKey, PackageList};
 
 // Compatible json decode of `cargo metadata --format-version 1`
 // https://doc.rust-lang.org/cargo/commands/cargo-metadata.html
@@ -22,6 +30,35 @@ pub(super) struct MetadataPackage {
     pub authors: Vec<String>,
     pub repository: Option<String>,
     pub homepage: Option<String>,
+    pub manifest_path: Option<String>,
+    pub source: Option<String>,
+    pub dependencies: Vec<MetadataDependency>,
+    pub targets: Vec<MetadataTarget>,
+    pub documentation: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    pub edition: Option<String>,
+    pub rust_version: Option<String>,
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(super) struct MetadataTarget {
+    pub name: String,
+    pub kind: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(super) struct MetadataDependency {
+    pub name: String,
+    pub source: Option<String>,
+    pub kind: Option<String>,
+    pub rename: Option<String>,
+    #[serde(default)]
+    pub optional: bool,
 }
 
 #[derive(Deserialize, Debug, cmp::PartialEq, cmp::Eq, cmp::PartialOrd, cmp::Ord)]
@@ -39,29 +76,243 @@ pub(super) struct MetadataResolveNodeDeps {
 pub(super) struct MetadataResolveNode {
     pub id: String,
     pub deps: Vec<MetadataResolveNodeDeps>,
+    #[serde(default)]
+    pub features: Vec<String>,
 }
 
 #[derive(Deserialize, Debug)]
 pub(super) struct MetadataResolve {
     pub nodes: Vec<MetadataResolveNode>,
-    pub root: Option<String>
+    pub root: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
 pub(super) struct Metadata {
     pub packages: Vec<MetadataPackage>,
-    pub resolve: MetadataResolve
+    pub workspace_members: Vec<String>,
+    pub resolve: MetadataResolve,
+}
+
+/// Returns `true` for sources like `registry+https://github.com/rust-lang/crates.io-index`.
+fn is_registry_source(source: &str) -> bool {
+    source.starts_with("registry+") || source.starts_with("sparse+")
+}
+
+/// Ids of the packages reachable from `root` over normal dependencies within `max_depth` edges.
+fn walk_dependencies<'a>(
+    dependencies: &'a [MetadataResolveNode],
+    root: &String,
+    max_depth: Option<usize>,
+) -> BTreeSet<&'a String> {
+    let find = |id: &String| dependencies.iter().find(|&dep| dep.id == *id);
+    let mut used_dependencies = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    if let Some(package) = find(root) {
+        used_dependencies.insert(&package.id);
+        queue.push_back((package, 0));
+    }
+    // Breadth first, so every package is reached on its shortest path.
+    while let Some((package, depth)) = queue.pop_front() {
+        if max_depth.is_some_and(|max_depth| depth >= max_depth) {
+            continue;
+        }
+        for dep in package.deps.iter() {
+            if !dep.dep_kinds.iter().map(|d| &d.kind).any(|o| o.is_none()) {
+                continue;
+            }
+            if let Some(dependency) = find(&dep.pkg) {
+                if used_dependencies.insert(&dependency.id) {
+                    queue.push_back((dependency, depth + 1));
+                }
+            }
+        }
+    }
+    used_dependencies
+}
+
+/// Parses the output of `cargo metadata` while it is read, without holding the whole JSON in memory.
+pub(super) fn parse_metadata(reader: &mut dyn Read) -> serde_json::Result<Metadata> {
+    from_reader(reader)
+}
+
+/// Packages reachable from `root` over normal dependencies within the depth, including `root` itself.
+fn packages_reachable_from(
+    metadata: &Metadata,
+    root: &String,
+    depth: DependencyDepth,
+) -> PackageList {
+    let used_packages = walk_dependencies(&metadata.resolve.nodes, root, depth.limit());
+
+    // Names of packages requested from a registry. Packages with these names, but another source are replacements.
+    let registry_dependencies: BTreeSet<&String> = metadata
+        .packages
+        .iter()
+        .flat_map(|package| package.dependencies.iter())
+        .filter(|dep| dep.source.as_deref().is_some_and(is_registry_source))
+        .map(|dep| &dep.name)
+        .collect();
+
+    // Names under which packages are imported by the packages in use, like `foo` for `foo = { package = "bar" }`.
+    let mut renames: BTreeMap<&String, BTreeSet<String>> = BTreeMap::new();
+    for dep in metadata
+        .packages
+        .iter()
+        .filter(|package| used_packages.contains(&package.id))
+        .flat_map(|package| package.dependencies.iter())
+        .filter(|dep| dep.kind.is_none())
+    {
+        if let Some(rename) = &dep.rename {
+            renames.entry(&dep.name).or_default().insert(rename.clone());
+        }
+    }
+
+    // Features that pulled in optional dependencies.
+    let packages_by_id: BTreeMap<&String, &MetadataPackage> = metadata
+        .packages
+        .iter()
+        .map(|package| (&package.id, package))
+        .collect();
+    let mut edges = vec![];
+    for node in metadata
+        .resolve
+        .nodes
+        .iter()
+        .filter(|node| used_packages.contains(&node.id))
+    {
+        let Some(parent) = packages_by_id.get(&node.id) else {
+            continue;
+        };
+        for dep in node.deps.iter().filter(|dep| {
+            used_packages.contains(&dep.pkg) && dep.dep_kinds.iter().any(|d| d.kind.is_none())
+        }) {
+            let Some(child) = packages_by_id.get(&dep.pkg) else {
+                continue;
+            };
+            let declarations: Vec<&MetadataDependency> = parent
+                .dependencies
+                .iter()
+                .filter(|d| d.name == child.name && d.kind.is_none())
+                .collect();
+            let activated_by =
+                (!declarations.is_empty() && declarations.iter().all(|d| d.optional)).then(|| {
+                    declarations
+                        .iter()
+                        .flat_map(|d| {
+                            activating_features(
+                                &parent.name,
+                                &parent.features,
+                                &node.features,
+                                d.rename.as_deref().unwrap_or(&d.name),
+                            )
+                        })
+                        .collect()
+                });
+            edges.push(FeatureEdge {
+                parent: &node.id,
+                child: &dep.pkg,
+                activated_by,
+            });
+        }
+    }
+    let feature_conditions = enabled_by_features(root, &edges);
+
+    // Add dependencies:
+
+    let mut package_list = vec![];
+
+    for package in metadata.packages.iter() {
+        if used_packages.contains(&package.id) {
+            let patched = !package.source.as_deref().is_some_and(is_registry_source)
+                && registry_dependencies.contains(&package.name);
+            package_list.push(Package {
+                authors: package.authors.clone(),
+                license_identifier: package.license.clone(),
+                name: package.name.clone(),
+                version: package.version.clone(),
+                description: package.description.clone(),
+                homepage: package.homepage.clone(),
+                repository: package.repository.clone(),
+                manifest_path: package.manifest_path.clone(),
+                patched,
+                replacement_source: package.source.clone().filter(|_| patched),
+                registry_url: crates_io_url(
+                    &package.name,
+                    &package.version,
+                    package.source.as_deref(),
+                ),
+                renamed_as: renames
+                    .get(&package.name)
+                    .map(|renames| renames.iter().cloned().collect())
+                    .unwrap_or_default(),
+                enabled_by_features: feature_conditions
+                    .get(&package.id)
+                    .cloned()
+                    .unwrap_or_default(),
+                extended_metadata: Some(ExtendedMetadata {
+                    documentation: package.documentation.clone(),
+                    keywords: package.keywords.clone(),
+                    categories: package.categories.clone(),
+                    edition: package.edition.clone(),
+                    rust_version: package.rust_version.clone(),
+                }),
+                ..Default::default()
+            });
+        }
+    }
+
+    PackageList(package_list)
+}
+
+/// Packages reachable from the root over normal dependencies.
+pub(super) fn package_list_from_metadata(
+    metadata: &Metadata,
+    depth: DependencyDepth,
+) -> PackageList {
+    let root = metadata
+        .resolve
+        .root
+        .as_ref()
+        .expect("cargo metadata is missing the root.");
+    packages_reachable_from(metadata, root, depth)
 }
 
+/// Packages of each binary target of the workspace members.
+///
+/// Every list is accompanied by the key of the package the binary belongs to.
+pub(super) fn binary_package_lists_from_metadata(
+    metadata: &Metadata,
+    depth: DependencyDepth,
+) -> BTreeMap<String, (PackageKey, PackageList)> {
+    let mut package_lists = BTreeMap::new();
+    for package in metadata
+        .packages
+        .iter()
+        .filter(|package| metadata.workspace_members.contains(&package.id))
+    {
+        for target in package
+            .targets
+            .iter()
+            .filter(|target| target.kind.iter().any(|kind| kind == "bin"))
+        {
+            package_lists.insert(
+                target.name.clone(),
+                (
+                    PackageKey::new(package.name.clone(), package.version.clone()),
+                    packages_reachable_from(metadata, &package.id, depth),
+                ),
+            );
+        }
+    }
+    package_lists
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use serde_json::from_slice;
-    use std::fs::read;
-    use std::ffi::OsString;
     use std::env;
+    use std::ffi::OsString;
+    use std::fs::read;
 
     fn get_path() -> OsString {
         env::var_os("CARGO_MANIFEST_DIR").unwrap()
@@ -72,6 +323,105 @@ mod tests {
         let mut root = get_path();
         root.push("/tests/metadata_test.json");
         let json_bytes = read(root).unwrap();
-        let _parsed_metadata: Metadata = from_slice(&json_bytes).unwrap();
+        let parsed_metadata = parse_metadata(&mut json_bytes.as_slice()).unwrap();
+
+        let package_list = package_list_from_metadata(&parsed_metadata, DependencyDepth::All);
+        let metadata = package_list[0].extended_metadata.as_ref().unwrap();
+        assert_eq!(metadata.edition.as_deref(), Some("2021"));
+        assert!(metadata.keywords.contains(&"license".to_owned()));
+    }
+
+    fn package(name: &str, bins: &[&str]) -> serde_json::Value {
+        let mut targets = vec![serde_json::json!({ "name": name, "kind": ["lib"] })];
+        targets.extend(
+            bins.iter()
+                .map(|bin| serde_json::json!({ "name": bin, "kind": ["bin"] })),
+        );
+        serde_json::json!({
+            "name": name, "version": "1.0.0", "id": name, "license": "MIT", "description": null,
+            "authors": [], "repository": null, "homepage": null, "manifest_path": null, "source": null,
+            "dependencies": [], "targets": targets, "documentation": null, "edition": "2021", "rust_version": null,
+        })
+    }
+
+    fn node(id: &str, deps: &[(&str, Option<&str>)]) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "deps": deps.iter().map(|(pkg, kind)| serde_json::json!({
+                "pkg": pkg, "dep_kinds": [{ "kind": kind }],
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    #[test]
+    fn test_dependency_depth() {
+        // Chain a -> b -> c, with a dev dependency a -> d.
+        let packages = ["a", "b", "c", "d"].map(|name| package(name, &[]));
+        let json = serde_json::json!({
+            "packages": packages,
+            "workspace_members": ["a"],
+            "resolve": {
+                "root": "a",
+                "nodes": [
+                    node("a", &[("b", None), ("d", Some("dev"))]),
+                    node("b", &[("c", None)]),
+                    node("c", &[]),
+                    node("d", &[]),
+                ],
+            },
+        })
+        .to_string();
+        let metadata = parse_metadata(&mut json.as_bytes()).unwrap();
+
+        let names = |depth| {
+            package_list_from_metadata(&metadata, depth)
+                .iter()
+                .map(|p| p.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(DependencyDepth::All), ["a", "b", "c"]);
+        assert_eq!(names(DependencyDepth::Direct), ["a", "b"]);
+        assert_eq!(names(DependencyDepth::max_depth(0)), ["a"]);
+    }
+
+    #[test]
+    fn test_binary_package_lists() {
+        // Virtual workspace of a with the binaries a-cli and a-gui and b with the binary b, sharing c.
+        let json = serde_json::json!({
+            "packages": [
+                package("a", &["a-cli", "a-gui"]),
+                package("b", &["b"]),
+                package("c", &[]),
+                package("d", &[]),
+                package("e", &[]),
+            ],
+            "workspace_members": ["a", "b"],
+            "resolve": {
+                "root": null,
+                "nodes": [
+                    node("a", &[("c", None), ("d", None)]),
+                    node("b", &[("c", None), ("e", Some("build"))]),
+                    node("c", &[]),
+                    node("d", &[]),
+                    node("e", &[]),
+                ],
+            },
+        })
+        .to_string();
+        let metadata = parse_metadata(&mut json.as_bytes()).unwrap();
+
+        let binaries = binary_package_lists_from_metadata(&metadata, DependencyDepth::All);
+        let names = |binary: &str| {
+            let (root, package_list) = &binaries[binary];
+            let names: Vec<&str> = package_list.iter().map(|p| p.name.as_str()).collect();
+            (root.name.as_str(), names)
+        };
+        assert_eq!(binaries.keys().collect::<Vec<_>>(), ["a-cli", "a-gui", "b"]);
+        assert_eq!(names("a-cli"), ("a", vec!["a", "c", "d"]));
+        assert_eq!(names("a-gui"), names("a-cli"));
+        assert_eq!(names("b"), ("b", vec!["b", "c"]));
+
+        let binaries = binary_package_lists_from_metadata(&metadata, DependencyDepth::max_depth(0));
+        assert_eq!(binaries["b"].1.len(), 1);
     }
 }
@@ -3,167 +3,467 @@
 //         (See accompanying file LICENSE or copy at
 //          https://www.boost.org/LICENSE_1_0.txt)
 
+use log::info;
+use simplelog::{ColorChoice, Config as LogConfig, LevelFilter, TermLogger, TerminalMode};
+use std::cmp::Ordering;
 use std::collections::BTreeSet;
-use std::env::{var, var_os};
+use std::env::var_os;
 use std::ffi::OsString;
-use std::fs::write;
+use std::fs::{read_to_string, File};
+use std::io::{self, BufReader, BufWriter, Read};
 use std::path::PathBuf;
-use std::process::Command;
-use std::time::Instant;
+use std::process::{self, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod backend;
+mod badge;
+mod binaries;
+mod build_info;
+pub mod cache;
+mod cargo_source;
+#[cfg(feature = "clearlydefined")]
+mod clearlydefined;
+mod config;
+mod encode;
+mod export;
+mod features;
+pub mod fetch;
+mod fs;
+mod import;
+mod json_log;
+mod language;
+mod lockfile;
+#[cfg(not(feature = "cargo_metadata"))]
+mod metadata;
+mod notice;
+mod pipeline;
+mod plan;
+mod report;
+mod reuse;
+pub mod scan;
+mod sidecar;
+mod snapshot;
+mod stale;
+#[cfg(feature = "cargo_metadata")]
+mod structured_metadata;
+mod targets;
+#[cfg(feature = "cargo_metadata")]
+use structured_metadata as metadata;
+
+use crate::error::ErrorCode;
+use crate::*;
+pub use backend::{LicenseSourceBackend, RegistryBackend};
+pub use binaries::{
+    generate_package_lists_per_binary, merge_package_list_files, write_package_lists_per_binary,
+};
+use cargo_source::{fetch_license_for_package, packages_missing_locally};
+pub use config::{
+    AuthorsPolicy, CachePolicy, Config, ConfigBuilder, DependencyDepth, FetchBackend,
+    LicenseOverride, LicenseTextOverride, LogFormat, NetworkPolicy, RootLicense, SortOrder,
+};
+pub use encode::{EncodeOptions, FieldMask};
+pub use notice::NoticeOptions;
+pub use pipeline::{Pipeline, Stage};
+pub use plan::{plan, FetchPlan, PlannedPackage, PlannedSource};
+use report::is_lock_contention;
+pub use report::{
+    BuildError, CacheStats, CargoAttempt, CargoNotFound, CopyleftWarning, FetchFailure,
+    FetchReport, LicenseChange, PackageSize, PolicyViolation, SourceWarning, SourceWarningKind,
+    StageTiming,
+};
+pub use sidecar::Output;
+pub use stale::warn_if_stale;
+pub use targets::generate_package_list_for_targets;
+
+/// Maximum length of the error output recorded in a [CargoAttempt].
+const STDERR_EXCERPT_LEN: usize = 4096;
+
+fn stderr_excerpt(stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let stderr = stderr.trim();
+    let mut end = stderr.len().min(STDERR_EXCERPT_LEN);
+    while !stderr.is_char_boundary(end) {
+        end -= 1;
+    }
+    stderr[..end].to_owned()
+}
 
-#[cfg(feature = "compress")]
-use miniz_oxide::deflate::compress_to_vec;
+fn cargo_path(config: &Config) -> OsString {
+    config
+        .cargo_path
+        .clone()
+        .unwrap_or_else(|| OsString::from("cargo"))
+}
 
-use log::info;
-use serde_json::from_slice;
-use simplelog::{ColorChoice, Config, LevelFilter, TermLogger, TerminalMode};
+/// Checks that cargo can be run by calling `cargo --version`.
+fn detect_cargo(config: &Config) -> Result<(), CargoNotFound> {
+    let path = cargo_path(config);
+    match Command::new(&path).arg("--version").output() {
+        Ok(_) => Ok(()),
+        Err(cause) => Err(CargoNotFound { path, cause }),
+    }
+}
 
-mod cargo_source;
-mod metadata;
+/// Directives cargo is invoked with in turn until an invocation succeeds.
+///
+/// First `--frozen` and, unless the feature `frozen` is set, again without on failure.
+/// With [Config::offline] the second attempt is made with `--offline`. [Config::cargo_directives] replace both.
+/// With [NetworkPolicy::Forbidden] there is no second attempt.
+fn cargo_directives(config: &Config) -> Vec<Option<&str>> {
+    if cfg!(feature = "frozen") || config.network == NetworkPolicy::Forbidden {
+        vec![Some("--frozen")]
+    } else if let Some(directives) = &config.cargo_directives {
+        directives.iter().map(Option::as_deref).collect()
+    } else if config.offline {
+        vec![Some("--frozen"), Some("--offline")]
+    } else {
+        vec![Some("--frozen"), None]
+    }
+}
 
-use crate::*;
-use build_script::metadata::*;
-use cargo_source::{license_text_from_folder, licenses_text_from_cargo_src_folder};
+fn cargo_command(config: &Config, args: &[&str], directive: Option<&str>) -> Command {
+    let mut command = Command::new(cargo_path(config));
+    command
+        .current_dir(&config.manifest_dir)
+        .args(args)
+        .args(directive);
+    command
+}
 
-fn walk_dependencies<'a>(
-    used_dependencies: &mut BTreeSet<&'a String>,
-    dependencies: &'a Vec<MetadataResolveNode>,
-    root: &String,
-) {
-    let package = match dependencies.iter().find(|&dep| dep.id == *root) {
-        Some(pack) => pack,
-        None => return,
-    };
-    used_dependencies.insert(&package.id);
-    for dep in package.deps.iter() {
-        if dep.dep_kinds.iter().map(|d| &d.kind).any(|o| o.is_none()) {
-            walk_dependencies(used_dependencies, dependencies, &dep.pkg);
+fn cargo_not_found(config: &Config, cause: io::Error) -> BuildError {
+    BuildError::CargoNotFound(CargoNotFound {
+        path: cargo_path(config),
+        cause,
+    })
+}
+
+/// Number of times an invocation failing on a lock held by another cargo process is retried.
+const LOCK_RETRIES: u32 = 4;
+/// Delay before the first retry after lock contention. Doubles with every retry.
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// Single invocation of cargo.
+struct Invocation<O> {
+    output: O,
+    status: ExitStatus,
+    stderr: Vec<u8>,
+}
+
+/// Invokes a cargo subcommand with the [cargo_directives] until it succeeds.
+///
+/// Invocations failing because another cargo process holds a lock, like the one of the package cache, are retried
+/// with the same directive up to [LOCK_RETRIES] times with exponential backoff.
+///
+/// Every invocation is logged and recorded in `attempts`. Returns the last invocation.
+fn invoke_cargo<O>(
+    config: &Config,
+    args: &[&str],
+    attempts: &mut Vec<CargoAttempt>,
+    mut invoke: impl FnMut(Command) -> Result<Invocation<O>, BuildError>,
+) -> Result<Invocation<O>, BuildError> {
+    let mut invocation = None;
+    'directives: for directive in cargo_directives(config) {
+        let mut delay = LOCK_RETRY_DELAY;
+        for retry in 0..=LOCK_RETRIES {
+            let start = Instant::now();
+            let current = invoke(cargo_command(config, args, directive))?;
+            let attempt = CargoAttempt {
+                subcommand: args[0].to_owned(),
+                directive: directive.map(str::to_owned),
+                duration: start.elapsed(),
+                exit_code: current.status.code(),
+                stderr: stderr_excerpt(&current.stderr),
+            };
+            if attempt.succeeded() {
+                info!("{}", attempt);
+            } else {
+                log::warn!("{}", attempt);
+            }
+            let success = attempt.succeeded();
+            let contended = attempt.is_lock_contention();
+            attempts.push(attempt);
+            invocation = Some(current);
+
+            if success {
+                break 'directives;
+            }
+            if !contended || retry == LOCK_RETRIES {
+                break;
+            }
+            log::warn!(
+                "Another cargo process holds a lock. Retrying cargo {} in {:?}.",
+                args[0],
+                delay
+            );
+            thread::sleep(delay);
+            delay *= 2;
         }
     }
-}
 
-fn generate_package_list(cargo_path: Option<OsString>, manifest_dir_path: OsString) -> PackageList {
-    let cargo_path = cargo_path.unwrap_or_else(|| OsString::from("cargo"));
+    Ok(invocation.expect("Cargo is invoked at least once."))
+}
 
-    let mut metadata_output = Command::new(&cargo_path)
-        .current_dir(&manifest_dir_path)
-        .args([
-            "metadata",
-            "--format-version",
-            "1",
-            "--frozen",
-            "--color",
-            "never",
-        ])
-        .output()
-        .unwrap();
+/// Code of a failed cargo invocation, [ErrorCode::CargoLockContention] if it failed on a lock.
+fn cargo_failure_code(stderr: &[u8]) -> ErrorCode {
+    if is_lock_contention(&String::from_utf8_lossy(stderr)) {
+        ErrorCode::CargoLockContention
+    } else {
+        ErrorCode::CargoFailed
+    }
+}
 
-    #[cfg(not(feature = "frozen"))]
-    if !metadata_output.status.success() {
-        metadata_output = Command::new(&cargo_path)
-            .current_dir(&manifest_dir_path)
-            .args(["metadata", "--format-version", "1", "--color", "never"])
+/// Runs a cargo subcommand, see [invoke_cargo]. Returns the output of the last invocation.
+fn run_cargo(
+    config: &Config,
+    args: &[&str],
+    attempts: &mut Vec<CargoAttempt>,
+) -> Result<process::Output, BuildError> {
+    invoke_cargo(config, args, attempts, |mut command| {
+        let output = command
             .output()
-            .unwrap();
-    }
+            .map_err(|cause| cargo_not_found(config, cause))?;
+        Ok(Invocation {
+            status: output.status,
+            stderr: output.stderr.clone(),
+            output,
+        })
+    })
+    .map(|invocation| invocation.output)
+}
 
-    if !metadata_output.status.success() {
-        panic!(
-            "Failed executing cargo metadata with:\n{}",
-            String::from_utf8_lossy(&metadata_output.stderr)
-        );
+/// Counts the bytes read through it.
+struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
     }
+}
 
-    let metadata_parsed: Metadata = from_slice(&metadata_output.stdout).unwrap();
+/// Runs a cargo subcommand like [run_cargo], but parses its output while it is written instead of buffering it.
+///
+/// Output of failed invocations is discarded. Returns the last invocation with its parsed output and size in bytes.
+fn run_cargo_streaming<T>(
+    config: &Config,
+    args: &[&str],
+    attempts: &mut Vec<CargoAttempt>,
+    mut parse: impl FnMut(&mut dyn Read) -> serde_json::Result<T>,
+) -> Result<Invocation<(serde_json::Result<T>, u64)>, BuildError> {
+    invoke_cargo(config, args, attempts, |mut command| {
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|cause| cargo_not_found(config, cause))?;
+
+        // Read concurrently, so cargo never blocks on a full pipe.
+        let mut child_stderr = child.stderr.take().expect("stderr is piped.");
+        let stderr_reader = thread::spawn(move || {
+            let mut stderr = vec![];
+            let _ = child_stderr.read_to_end(&mut stderr);
+            stderr
+        });
+        let mut stdout = BufReader::new(CountingReader {
+            inner: child.stdout.take().expect("stdout is piped."),
+            count: 0,
+        });
+        let parsed = parse(&mut stdout);
+        let _ = io::copy(&mut stdout, &mut io::sink());
+        let size = stdout.get_ref().count;
+
+        let status = child
+            .wait()
+            .map_err(|cause| cargo_not_found(config, cause))?;
+        Ok(Invocation {
+            output: (parsed, size),
+            status,
+            stderr: stderr_reader.join().expect("stderr reader panicked."),
+        })
+    })
+}
 
-    let packages = metadata_parsed.packages;
-    let package_id = metadata_parsed.resolve.root.unwrap();
-    let dependencies = metadata_parsed.resolve.nodes;
+/// Sources of crates.io, via git and sparse index.
+const CRATES_IO_SOURCES: [&str; 2] = [
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
 
-    let mut used_packages = BTreeSet::new();
+/// Page of a package on crates.io, like `https://crates.io/crates/log/0.4.22`.
+///
+/// `None` for packages from other sources, as other registries have no standard web page.
+fn crates_io_url(name: &str, version: &str, source: Option<&str>) -> Option<String> {
+    source
+        .filter(|source| CRATES_IO_SOURCES.contains(source))
+        .map(|_| format!("https://crates.io/crates/{}/{}", name, version))
+}
 
-    walk_dependencies(&mut used_packages, &dependencies, &package_id);
+/// Runs `cargo metadata` and parses its output while it is read. Fails if cargo fails or the output is invalid.
+///
+/// Warns if the output exceeds [Config::metadata_size_warning].
+fn cargo_metadata<T>(
+    config: &Config,
+    attempts: &mut Vec<CargoAttempt>,
+    parse: impl FnMut(&mut dyn Read) -> serde_json::Result<T>,
+) -> Result<T, BuildError> {
+    let mut args = vec!["metadata", "--format-version", "1", "--color", "never"];
+    if let Some(target) = &config.target {
+        args.extend(["--filter-platform", target]);
+    }
 
-    // Add dependencies:
+    let invocation = run_cargo_streaming(config, &args, attempts, parse)?;
+    if !invocation.status.success() {
+        return Err(cargo_metadata_failed(config, &invocation.stderr));
+    }
+    let (parsed, size) = invocation.output;
+    if config
+        .metadata_size_warning
+        .is_some_and(|threshold| size > threshold)
+    {
+        log::warn!(
+            "Output of cargo metadata is {} MiB large. Consider limiting it with a target or dependency depth.",
+            size / (1024 * 1024)
+        );
+    }
+    parsed.map_err(BuildError::MetadataParse)
+}
 
-    let mut package_list = vec![];
+/// Reason cargo metadata failed.
+fn cargo_metadata_failed(config: &Config, stderr: &[u8]) -> BuildError {
+    let stderr = String::from_utf8_lossy(stderr).into_owned();
+    if config.network == NetworkPolicy::Forbidden {
+        return BuildError::NetworkForbidden {
+            missing: packages_missing_locally(&config.manifest_dir),
+            stderr,
+        };
+    }
+    BuildError::CargoFailed {
+        subcommand: "metadata".to_owned(),
+        code: cargo_failure_code(stderr.as_bytes()),
+        stderr,
+    }
+}
 
-    for package in packages {
-        if used_packages.contains(&package.id) {
-            package_list.push(Package {
-                license_text: None,
-                authors: package.authors,
-                license_identifier: package.license,
-                name: package.name,
-                version: package.version,
-                description: package.description,
-                homepage: package.homepage,
-                repository: package.repository,
+fn generate_package_list(
+    config: &Config,
+    attempts: &mut Vec<CargoAttempt>,
+) -> Result<PackageList, BuildError> {
+    let metadata = cargo_metadata(config, attempts, metadata::parse_metadata)?;
+    Ok(metadata::package_list_from_metadata(
+        &metadata,
+        config.dependency_depth,
+    ))
+}
+
+/// Replaces the license text of a package with the content of a single file.
+///
+/// On failure the package is left untouched and the failure is recorded in the report.
+fn license_text_from_file(package: &mut Package, path: PathBuf, report: &mut FetchReport) {
+    match read_to_string(&path) {
+        Ok(text) => {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            package.license_files = vec![LicenseFile::new(file_name, 0..text.len())];
+            package.license_translations = vec![];
+            package.license_text = Some(text.into());
+            package.license_text_source = Some(LicenseTextSource::LicenseFiles);
+        }
+        Err(cause) => {
+            log::warn!("Failed reading license file {:?}: {}", &path, cause);
+            report.failures.push(FetchFailure {
+                package_name: package.name.clone(),
+                package_version: package.version.clone(),
+                path,
+                cause,
             });
         }
     }
+}
 
-    PackageList(package_list)
+/// Applies [Config::license_text_overrides].
+fn override_license_texts(
+    package_list: &mut PackageList,
+    config: &Config,
+    report: &mut FetchReport,
+) {
+    for package in package_list.iter_mut() {
+        let Some((_, text_override)) = config
+            .license_text_overrides
+            .iter()
+            .find(|(name, _)| *name == package.name)
+        else {
+            continue;
+        };
+        info!(
+            "Overriding license text of {} {}",
+            package.name, package.version
+        );
+        match text_override {
+            LicenseTextOverride::File(path) => {
+                license_text_from_file(package, config.manifest_dir.join(path), report);
+            }
+            LicenseTextOverride::Text(text) => {
+                package.license_files = vec![];
+                package.license_translations = vec![];
+                package.license_text = Some(text.as_str().into());
+                package.license_text_source = None;
+            }
+        }
+    }
 }
 
-/// Filters [PackageList] with output of `cargo tree`.
-///
-/// Workaround for `cargo metadata`'s inability to differentiate between dependencies
-/// of packages that are used in build scripts and normally.
-fn filter_package_list_with_cargo_tree(
-    package_list: PackageList,
-    cargo_path: Option<OsString>,
-    manifest_dir_path: OsString,
-) -> PackageList {
-    let cargo_path = cargo_path.unwrap_or_else(|| OsString::from("cargo"));
-
-    let mut output = Command::new(&cargo_path)
-        .current_dir(&manifest_dir_path)
-        .args([
-            "tree",
-            "-e",
-            "normal",
-            "-f",
-            "{p}",
-            "--prefix",
-            "none",
-            "--frozen",
-            "--color",
-            "never",
-            "--no-dedupe",
-        ])
-        .output()
-        .unwrap();
+/// Fills the license text of the package itself according to [Config::root_license].
+fn fetch_root_license(package: &mut Package, config: &Config, report: &mut FetchReport) {
+    match &config.root_license {
+        RootLicense::Auto => {
+            fetch_license_for_package(package, &config.manifest_dir, config, report);
+        }
+        RootLicense::File(path) => {
+            license_text_from_file(package, config.manifest_dir.join(path), report);
+        }
+        RootLicense::Text(text) => {
+            package.license_text = Some(text.as_str().into());
+        }
+        RootLicense::Skip => {}
+    }
+}
 
-    #[cfg(not(feature = "frozen"))]
-    if !output.status.success() {
-        output = Command::new(&cargo_path)
-            .current_dir(&manifest_dir_path)
-            .args([
-                "tree",
-                "-e",
-                "normal",
-                "-f",
-                "{p}",
-                "--prefix",
-                "none",
-                "--color",
-                "never",
-                "--no-dedupe",
-            ])
-            .output()
-            .unwrap();
+/// Names of the packages in the output of `cargo tree`. Returns `None` if `cargo tree` fails.
+fn cargo_tree_package_names(
+    config: &Config,
+    attempts: &mut Vec<CargoAttempt>,
+) -> Result<Option<BTreeSet<String>>, BuildError> {
+    let mut args = vec![
+        "tree",
+        "-e",
+        "normal",
+        "-f",
+        "{p}",
+        "--prefix",
+        "none",
+        "--color",
+        "never",
+        "--no-dedupe",
+    ];
+    if let Some(target) = &config.target {
+        args.extend(["--target", target]);
     }
+    let output = run_cargo(config, &args, attempts)?;
 
     if !output.status.success() {
         log::error!(
-            "Failed executing cargo tree with:\n{}",
+            "{}: Failed executing cargo tree with:\n{}",
+            cargo_failure_code(&output.stderr),
             String::from_utf8_lossy(&output.stderr)
         );
-        return package_list;
+        return Ok(None);
     }
 
-    let tree_string = String::from_utf8(output.stdout).unwrap();
+    let tree_string = String::from_utf8_lossy(&output.stdout);
     let mut used_package_set = BTreeSet::new();
 
     for package in tree_string.lines() {
@@ -173,6 +473,17 @@ fn filter_package_list_with_cargo_tree(
         }
     }
 
+    Ok(Some(used_package_set))
+}
+
+/// Filters [PackageList] with output of `cargo tree`.
+///
+/// Workaround for `cargo metadata`'s inability to differentiate between dependencies
+/// of packages that are used in build scripts and normally.
+fn filter_package_list_with_cargo_tree(
+    package_list: PackageList,
+    used_package_set: &BTreeSet<String>,
+) -> PackageList {
     let mut filtered_package_list = PackageList(vec![]);
 
     for pkg in package_list.iter() {
@@ -201,25 +512,160 @@ pub fn generate_package_list_with_licenses_without_env_calls(
     manifest_dir_path: OsString,
     this_package_name: String,
 ) -> PackageList {
-    let mut package_list = generate_package_list(cargo_path.clone(), manifest_dir_path.clone());
-    package_list =
-        filter_package_list_with_cargo_tree(package_list, cargo_path, manifest_dir_path.clone());
-
-    licenses_text_from_cargo_src_folder(&mut package_list);
-
-    info!("Fetching license for: {}", &this_package_name);
-    let this_package_index = package_list
-        .iter()
-        .enumerate()
-        .filter(|(_, p)| p.name == this_package_name)
-        .map(|(i, _)| i)
-        .next()
-        .unwrap();
-    package_list[this_package_index].license_text =
-        license_text_from_folder(&PathBuf::from(manifest_dir_path));
-    package_list.swap(this_package_index, 0);
+    let config = ConfigBuilder::new(manifest_dir_path, this_package_name)
+        .cargo_path(cargo_path)
+        .build();
+
+    generate_package_list_with_config(config)
+}
+
+/// Generates a package list with package name, authors and license text according to a [Config].
+///
+/// Does not initialize a logger. See [ConfigBuilder] for an example.
+pub fn generate_package_list_with_config(config: Config) -> PackageList {
+    generate_package_list_with_report(config).0
+}
+
+/// Runs `cargo metadata` and `cargo tree` and returns the used packages without license texts.
+///
+/// Without cargo the packages are read from `Cargo.lock` if [Config::lockfile_fallback] is set. Otherwise it fails.
+fn resolve_packages(
+    config: &Config,
+    attempts: &mut Vec<CargoAttempt>,
+) -> Result<PackageList, BuildError> {
+    if let Err(e) = detect_cargo(config) {
+        if !config.lockfile_fallback {
+            return Err(e.into());
+        }
+        log::warn!("{}. Reading packages from Cargo.lock instead.", e);
+        return lockfile::package_list_from_lockfile(config);
+    }
+
+    let (package_list, used_package_set) = if config.parallelism() > 1 {
+        thread::scope(|scope| {
+            let tree = scope.spawn(|| {
+                let mut tree_attempts = vec![];
+                let names = cargo_tree_package_names(config, &mut tree_attempts);
+                (names, tree_attempts)
+            });
+            let package_list = generate_package_list(config, attempts);
+            let (names, tree_attempts) = tree.join().expect("cargo tree thread panicked.");
+            attempts.extend(tree_attempts);
+            (package_list, names)
+        })
+    } else {
+        (
+            generate_package_list(config, attempts),
+            cargo_tree_package_names(config, attempts),
+        )
+    };
+    let (mut package_list, used_package_set) = (package_list?, used_package_set?);
+    if let Some(used_package_set) = used_package_set {
+        package_list = filter_package_list_with_cargo_tree(package_list, &used_package_set);
+    }
+
+    Ok(package_list)
+}
+
+/// Same as [generate_package_list_with_config], but additionally returns a [FetchReport].
+///
+/// The package itself is always the first entry of the list, unless excluded with
+/// [ConfigBuilder::include_root].
+///
+/// Failing to read a license file or folder of a package does not abort fetching.
+/// Such failures are collected in the report instead, so callers can decide whether to fail the build.
+///
+/// Use a [Pipeline] to run custom steps between the stages of fetching.
+pub fn generate_package_list_with_report(config: Config) -> (PackageList, FetchReport) {
+    Pipeline::new(config).run()
+}
 
-    package_list
+/// Same as [generate_package_list_with_report], but returns a [BuildError] instead of panicking.
+///
+/// # Example
+/// In `build.rs`:
+/// ```no_run
+/// use std::error::Error;
+///
+/// use license_fetcher::build_script::{try_generate_package_list_with_report, ConfigBuilder, EncodeOptions};
+///
+/// fn main() -> Result<(), Box<dyn Error>> {
+///     let (package_list, _report) = try_generate_package_list_with_report(ConfigBuilder::from_build_env().build())?;
+///     package_list.try_write_with_options(&EncodeOptions::from_build_env())?;
+///     Ok(())
+/// }
+/// ```
+pub fn try_generate_package_list_with_report(
+    config: Config,
+) -> Result<(PackageList, FetchReport), BuildError> {
+    Pipeline::new(config).try_run()
+}
+
+/// Removes email addresses from an author. Returns `None` if nothing else is left.
+fn strip_email(author: &str) -> Option<String> {
+    let mut stripped = String::new();
+    let mut rest = author;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        stripped.push_str(&rest[..start]);
+        let inner = &rest[start + 1..start + end];
+        if !inner.contains('@') {
+            stripped.push_str(&rest[start..=start + end]);
+        }
+        rest = &rest[start + end + 1..];
+    }
+    stripped.push_str(rest);
+
+    let stripped = stripped
+        .split_whitespace()
+        .filter(|word| !word.contains('@'))
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!stripped.is_empty()).then_some(stripped)
+}
+
+/// Compares versions by their numeric components, so that `1.10.0` comes after `1.9.0`.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let mut a_parts = a.split(['.', '-', '+']);
+    let mut b_parts = b.split(['.', '-', '+']);
+    loop {
+        let ordering = match (a_parts.next(), b_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                _ => a.cmp(b),
+            },
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+fn sort_packages(packages: &mut [Package], sort: SortOrder) {
+    let by_name_version = |a: &Package, b: &Package| {
+        a.name
+            .cmp(&b.name)
+            .then_with(|| compare_versions(&a.version, &b.version))
+    };
+
+    match sort {
+        SortOrder::NameVersion => packages.sort_by(by_name_version),
+        SortOrder::License => packages.sort_by(|a, b| {
+            match (&a.license_identifier, &b.license_identifier) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+            .then_with(|| by_name_version(a, b))
+        }),
+        SortOrder::None => {}
+    }
 }
 
 /// Generates a package list with package name, authors and license text. Uses env variables supplied by cargo during build.
@@ -244,23 +690,22 @@ pub fn generate_package_list_with_licenses_without_env_calls(
 /// }
 /// ```
 pub fn generate_package_list_with_licenses() -> PackageList {
-    TermLogger::init(
-        LevelFilter::Trace,
-        Config::default(),
-        TerminalMode::Stderr,
-        ColorChoice::Auto,
-    )
-    .unwrap();
-
-    let cargo_path = var_os("CARGO").unwrap();
-    let manifest_dir_path = var_os("CARGO_MANIFEST_DIR").unwrap();
-    let this_package_name = var("CARGO_PKG_NAME").unwrap();
+    // The logger is installed first, so warnings about invalid env values are not lost.
+    let log_format = config::log_format_from_env();
+    if log_format == LogFormat::Text {
+        TermLogger::init(
+            LevelFilter::Trace,
+            LogConfig::default(),
+            TerminalMode::Stderr,
+            ColorChoice::Auto,
+        )
+        .unwrap();
+    } else {
+        json_log::init(log_format);
+    }
+    let config = ConfigBuilder::from_build_env().build();
 
-    generate_package_list_with_licenses_without_env_calls(
-        Some(cargo_path),
-        manifest_dir_path,
-        this_package_name,
-    )
+    generate_package_list_with_config(config)
 }
 
 impl PackageList {
@@ -268,27 +713,223 @@ impl PackageList {
     ///
     /// Copmresses and writes the PackageList into the `OUT_DIR` with file name `LICENSE-3RD-PARTY.bincode`.
     pub fn write(self) {
-        let mut path = var_os("OUT_DIR").unwrap();
-        path.push("/LICENSE-3RD-PARTY.bincode");
+        self.write_with_build_info(None);
+    }
 
-        let data = bincode::encode_to_vec(self, config::standard()).unwrap();
+    /// Same as [PackageList::write], but additionally embeds a [BuildInfo].
+    ///
+    /// Both apply the env variables documented at [EncodeOptions::from_build_env].
+    ///
+    /// The build info is not written by default, as its timestamp breaks reproducible builds,
+    /// unless `SOURCE_DATE_EPOCH` is set.
+    ///
+    /// # Example
+    /// In `build.rs`:
+    /// ```no_run
+    /// use license_fetcher::BuildInfo;
+    /// use license_fetcher::build_script::generate_package_list_with_licenses;
+    ///
+    /// fn main() {
+    ///     generate_package_list_with_licenses().write_with_build_info(Some(BuildInfo::from_build_env()));
+    /// }
+    /// ```
+    pub fn write_with_build_info(self, build_info: Option<BuildInfo>) {
+        let mut options = EncodeOptions::from_build_env();
+        if let Some(build_info) = build_info {
+            options = options.build_info(build_info);
+        }
+        self.write_with_options(&options);
+    }
 
-        info!("License data size: {} Bytes", data.len());
-        let instant_before_compression = Instant::now();
+    /// Encodes the [PackageList] with the given [EncodeOptions] and writes it into the `OUT_DIR`.
+    pub fn write_with_options(self, options: &EncodeOptions) {
+        self.try_write_with_options(options)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
 
-        #[cfg(feature = "compress")]
-        let compressed_data = compress_to_vec(&data, 10);
+    /// Same as [PackageList::write_with_options], but returns a [BuildError] instead of panicking.
+    pub fn try_write_with_options(self, options: &EncodeOptions) -> Result<(), BuildError> {
+        let mut path = PathBuf::from(var_os("OUT_DIR").ok_or(BuildError::OutDirMissing)?);
+        path.push("LICENSE-3RD-PARTY.bincode");
+
+        info!("Writing to file: {:?}", &path);
+        File::create(&path)
+            .and_then(|file| self.encode_to_writer(options, BufWriter::new(file)))
+            .map_err(|cause| BuildError::Write { path, cause })
+    }
+}
 
-        #[cfg(not(feature = "compress"))]
-        let compressed_data = data;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use fs::test_dir;
+
+    #[test]
+    fn test_sort_packages() {
+        let package = |name: &str, version: &str, license: Option<&str>| {
+            let builder = Package::builder().name(name).version(version);
+            match license {
+                Some(license) => builder.license_identifier(license),
+                None => builder,
+            }
+            .build()
+        };
+        let mut packages = vec![
+            package("b", "1.10.0", Some("MIT")),
+            package("b", "1.9.0", None),
+            package("a", "0.1.0", Some("Zlib")),
+        ];
+
+        sort_packages(&mut packages, SortOrder::NameVersion);
+        let order: Vec<_> = packages.iter().map(|p| p.version.as_str()).collect();
+        assert_eq!(order, ["0.1.0", "1.9.0", "1.10.0"]);
+
+        sort_packages(&mut packages, SortOrder::License);
+        let order: Vec<_> = packages.iter().map(|p| p.version.as_str()).collect();
+        assert_eq!(order, ["1.10.0", "0.1.0", "1.9.0"]);
+    }
 
-        info!(
-            "Compressed data size: {} Bytes in {}ms",
-            compressed_data.len(),
-            instant_before_compression.elapsed().as_millis()
+    #[test]
+    fn test_crates_io_url() {
+        assert_eq!(
+            crates_io_url("log", "0.4.22", Some("sparse+https://index.crates.io/")).as_deref(),
+            Some("https://crates.io/crates/log/0.4.22")
+        );
+        assert_eq!(
+            crates_io_url("log", "0.4.22", Some("registry+https://example.com/index")),
+            None
         );
+        assert_eq!(crates_io_url("log", "0.4.22", None), None);
+    }
 
-        info!("Writing to file: {:?}", &path);
-        write(path, compressed_data).unwrap();
+    #[test]
+    fn test_build_errors() {
+        use std::error::Error;
+
+        let config = ConfigBuilder::new(std::env::temp_dir(), "root")
+            .cargo_path(Some("license-fetcher-test-missing-cargo".into()))
+            .build();
+        let e = try_generate_package_list_with_report(config).unwrap_err();
+        assert_eq!(e.code(), ErrorCode::CargoNotFound);
+        let cause = e.source().unwrap().source().unwrap();
+        assert_eq!(
+            cause.downcast_ref::<io::Error>().unwrap().kind(),
+            io::ErrorKind::NotFound
+        );
+
+        let config = ConfigBuilder::new(test_dir("missing-lockfile"), "root")
+            .cargo_path(Some("license-fetcher-test-missing-cargo".into()))
+            .lockfile_fallback(true)
+            .build();
+        let e = try_generate_package_list_with_report(config).unwrap_err();
+        assert!(matches!(e, BuildError::Lockfile { .. }));
+        assert!(e.to_string().starts_with("LF1006: Cargo.lock not found"));
+
+        assert_eq!(BuildError::OutDirMissing.code(), ErrorCode::OutDirMissing);
+        assert!(BuildError::OutDirMissing
+            .to_string()
+            .starts_with("LF1012: OUT_DIR is not set."));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cargo_tree_invalid_utf8() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Fake cargo printing a package name that is not valid UTF-8.
+        let dir = test_dir("tree");
+        let script = dir.join("cargo");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\nprintf 'log v0.4.22\\n\\377 v1.0.0\\n'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let config = ConfigBuilder::new(&dir, "root")
+            .cargo_path(Some(script.into_os_string()))
+            .build();
+        let names = cargo_tree_package_names(&config, &mut vec![]).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            names.unwrap(),
+            BTreeSet::from(["log".to_owned(), "\u{FFFD}".to_owned()])
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lock_contention_retry() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Fake cargo failing on a lock twice before succeeding.
+        let dir = test_dir("lock");
+        let script = dir.join("cargo");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n\
+            echo x >> count\n\
+            if [ $(wc -l < count) -le 2 ]; then\n\
+            echo 'error: failed to acquire cache lock' >&2; exit 101\n\
+            fi\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let config = ConfigBuilder::new(&dir, "root")
+            .cargo_path(Some(script.into_os_string()))
+            .build();
+        let mut attempts = vec![];
+        let output = run_cargo(&config, &["tree"], &mut attempts).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(attempts.len(), 3);
+        assert!(attempts[0].is_lock_contention());
+        assert!(attempts
+            .iter()
+            .all(|a| a.directive.as_deref() == Some("--frozen")));
+        assert_eq!(
+            cargo_failure_code(attempts[0].stderr.as_bytes()),
+            ErrorCode::CargoLockContention
+        );
+    }
+
+    #[test]
+    fn test_strip_email() {
+        assert_eq!(
+            strip_email("Jane Doe <jane@example.com>").as_deref(),
+            Some("Jane Doe")
+        );
+        assert_eq!(
+            strip_email("The Team <https://example.com>").as_deref(),
+            Some("The Team <https://example.com>")
+        );
+        assert_eq!(strip_email("jane@example.com"), None);
+        assert_eq!(strip_email("<jane@example.com>"), None);
+    }
+
+    #[test]
+    fn test_override_license_texts() {
+        let config = ConfigBuilder::new(std::env::temp_dir(), "root")
+            .override_license("a", LicenseTextOverride::Text("A License".to_owned()))
+            .override_license(
+                "b",
+                LicenseTextOverride::File("license-fetcher-test-missing-file".into()),
+            )
+            .build();
+        let mut package_list = PackageList(vec![
+            Package::builder().name("a").version("1.0.0").build(),
+            Package::builder().name("b").version("1.0.0").build(),
+        ]);
+        let mut report = FetchReport::default();
+
+        override_license_texts(&mut package_list, &config, &mut report);
+
+        assert_eq!(package_list[0].license_text.as_deref(), Some("A License"));
+        assert_eq!(package_list[1].license_text, None);
+        assert_eq!(report.failures.len(), 1);
     }
 }
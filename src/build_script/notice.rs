@@ -0,0 +1,181 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use log::info;
+
+use super::fs::write;
+use crate::{Labels, Package, PackageList};
+
+/// Options for [PackageList::write_notice_txt].
+///
+/// # Example
+/// In `build.rs`:
+/// ```no_run
+/// use license_fetcher::build_script::{generate_package_list_with_licenses, NoticeOptions};
+///
+/// fn main() {
+///     let package_list = generate_package_list_with_licenses();
+///     let options = NoticeOptions::default()
+///         .header("This program uses the following third party software.")
+///         .notice_files_only(true);
+///     package_list.write_notice_txt("target/NOTICE", &options).unwrap();
+///     package_list.write();
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NoticeOptions {
+    header: Option<String>,
+    footer: Option<String>,
+    notice_files_only: bool,
+    labels: Labels,
+}
+
+impl NoticeOptions {
+    /// Text written before the packages.
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.header = Some(header.into());
+        self
+    }
+
+    /// Text written after the packages.
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    /// Only includes the content of `NOTICE` files instead of the whole license text.
+    ///
+    /// Packages without a `NOTICE` file are omitted.
+    pub fn notice_files_only(mut self, notice_files_only: bool) -> Self {
+        self.notice_files_only = notice_files_only;
+        self
+    }
+
+    /// Labels of the package fields, e.g. in the language of the users. Defaults to English.
+    pub fn labels(mut self, labels: Labels) -> Self {
+        self.labels = labels;
+        self
+    }
+}
+
+struct Notice<'a> {
+    package_list: &'a PackageList,
+    options: &'a NoticeOptions,
+}
+
+impl Notice<'_> {
+    fn notice_text(package: &Package) -> Option<String> {
+        let notices: Vec<&str> = package
+            .license_file_texts()
+            .filter(|(file, _)| file.is_notice())
+            .map(|(_, text)| text)
+            .collect();
+        if notices.is_empty() {
+            None
+        } else {
+            Some(notices.join("\n\n"))
+        }
+    }
+}
+
+impl fmt::Display for Notice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const SEPERATOR_WIDTH: usize = 80;
+        let separator: String = "=".repeat(SEPERATOR_WIDTH);
+
+        if let Some(header) = &self.options.header {
+            writeln!(f, "{}\n", header)?;
+        }
+
+        writeln!(f, "{}\n", separator)?;
+
+        let labels = &self.options.labels;
+        for (kind, packages) in self.package_list.asset_groups() {
+            if let Some(kind) = kind {
+                writeln!(
+                    f,
+                    "{}: {}\n\n{}\n",
+                    labels.assets,
+                    labels.asset_heading(kind),
+                    separator
+                )?;
+            }
+            for package in packages {
+                if self.options.notice_files_only {
+                    if let Some(notice_text) = Self::notice_text(package) {
+                        package.fmt_package_with_text(f, Some(&notice_text), labels)?;
+                    }
+                } else {
+                    package.fmt_package_with_text(f, package.license_text.as_deref(), labels)?;
+                }
+            }
+        }
+
+        if let Some(footer) = &self.options.footer {
+            writeln!(f, "{}", footer)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PackageList {
+    /// Writes the same content as [Display](std::fmt::Display) into a plain text file, configured by [NoticeOptions].
+    ///
+    /// Meant for build scripts to place a `NOTICE` file next to the binary or into a packaging directory.
+    pub fn write_notice_txt(
+        &self,
+        path: impl AsRef<Path>,
+        options: &NoticeOptions,
+    ) -> io::Result<()> {
+        let notice = Notice {
+            package_list: self,
+            options,
+        };
+
+        info!("Writing notice to file: {:?}", path.as_ref());
+        write(path, notice.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notice_files_only() {
+        let package_list = PackageList(vec![
+            Package::builder()
+                .name("a")
+                .version("1.0.0")
+                .license_file("LICENSE", "Apache License")
+                .license_file("NOTICE", "Copyright A")
+                .build(),
+            Package::builder()
+                .name("b")
+                .version("1.0.0")
+                .license_file("LICENSE", "MIT License")
+                .build(),
+        ]);
+        let options = NoticeOptions::default()
+            .header("Header")
+            .notice_files_only(true);
+
+        let notice = Notice {
+            package_list: &package_list,
+            options: &options,
+        }
+        .to_string();
+
+        assert!(notice.starts_with("Header\n"));
+        assert!(notice.contains("Copyright A"));
+        assert!(!notice.contains("Apache License"));
+        assert!(!notice.contains("Package:     b"));
+    }
+}
@@ -0,0 +1,741 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Fetching of licenses split into stages with hooks in between.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use log::info;
+
+use super::backend::{fetch_from_registered_backends, LicenseSourceBackend, RegistryBackend};
+use super::cargo_source::{licenses_text_from_patched_sources, source_warnings};
+#[cfg(feature = "clearlydefined")]
+use super::config::NetworkPolicy;
+use super::config::{AuthorsPolicy, CachePolicy, Config};
+use super::report::{
+    BuildError, CacheStats, CargoAttempt, CopyleftWarning, FetchReport, PolicyViolation,
+};
+use super::sidecar::write_sidecars;
+use super::stale::record_fingerprint;
+use super::{
+    cache, fetch_root_license, json_log, override_license_texts, resolve_packages, sort_packages,
+    strip_email,
+};
+use crate::spdx::{self, Copyleft, LicenseInfo};
+use crate::{Package, PackageList};
+
+/// Stages of a [Pipeline] in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Stage {
+    /// Runs `cargo metadata` and `cargo tree` and records [SourceWarning](super::SourceWarning)s.
+    /// Packages have no license texts yet.
+    Resolve,
+    /// Restores license texts from the cache and its seed, if configured.
+    Cache,
+    /// Reads license texts from the cargo registry, or the replacement of patched packages, and updates the cache.
+    Registry,
+    /// Fetches the license of the package itself and moves it to the front, or removes it.
+    Root,
+    /// Applies license text overrides, ClearlyDefined, license overrides, the license policy, license exclusions,
+    /// the authors policy and sorting.
+    PostProcess,
+}
+
+type Hook = Box<dyn FnOnce(&mut PackageList, &Config, &mut FetchReport)>;
+type Resolver = Box<dyn FnOnce(&Config, &mut Vec<CargoAttempt>) -> Result<PackageList, BuildError>>;
+
+/// Fetching of licenses according to a [Config], with custom steps between the stages.
+///
+/// [generate_package_list_with_report](super::generate_package_list_with_report) is a pipeline without hooks.
+/// Encoding is not part of the pipeline. It happens when writing the result, e.g. with
+/// [PackageList::write](crate::PackageList::write).
+///
+/// # Example
+/// In `build.rs`:
+/// ```no_run
+/// use license_fetcher::Package;
+/// use license_fetcher::build_script::{ConfigBuilder, Pipeline, Stage};
+///
+/// fn main() {
+///     let (package_list, _report) = Pipeline::new(ConfigBuilder::from_build_env().build())
+///         .after(Stage::Root, |package_list, _config, _report| {
+///             package_list.push(
+///                 Package::builder()
+///                     .name("other dependency")
+///                     .version("0.1.0")
+///                     .license_identifier("MIT")
+///                     .build(),
+///             );
+///         })
+///         .run();
+///     package_list.write();
+/// }
+/// ```
+pub struct Pipeline {
+    config: Config,
+    hooks: Vec<(Stage, Hook)>,
+    resolver: Option<Resolver>,
+}
+
+impl Pipeline {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            hooks: vec![],
+            resolver: None,
+        }
+    }
+
+    /// Replaces the cargo calls of [Stage::Resolve].
+    pub(super) fn resolver(
+        mut self,
+        resolver: impl FnOnce(&Config, &mut Vec<CargoAttempt>) -> Result<PackageList, BuildError>
+            + 'static,
+    ) -> Self {
+        self.resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// Runs `hook` after `stage`. Hooks of the same stage run in the order they were added.
+    ///
+    /// Hooks after [Stage::Root] see the final set of packages before overrides and the license policy are applied.
+    /// They must keep the package itself in front, if it is included.
+    pub fn after(
+        mut self,
+        stage: Stage,
+        hook: impl FnOnce(&mut PackageList, &Config, &mut FetchReport) + 'static,
+    ) -> Self {
+        self.hooks.push((stage, Box::new(hook)));
+        self
+    }
+
+    /// Runs all stages and hooks.
+    ///
+    /// No stage or hook runs if [Config::skip] is set.
+    ///
+    /// # Panics
+    /// Panics with the message of the [BuildError] if [Self::try_run] fails.
+    pub fn run(self) -> (PackageList, FetchReport) {
+        self.try_run().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [Self::run], but returns a [BuildError] instead of panicking.
+    ///
+    /// Fails if cargo can't be run or fails, or if the package itself is missing at [Stage::Root], e.g. because a
+    /// hook removed it.
+    pub fn try_run(mut self) -> Result<(PackageList, FetchReport), BuildError> {
+        json_log::init(self.config.log_format);
+        let mut report = FetchReport::default();
+        if self.config.skip {
+            info!("Skipping license fetching.");
+            json_log::finish(&self.config, 0, &report);
+            return Ok((PackageList(vec![]), report));
+        }
+        let config = &self.config;
+
+        let start = Instant::now();
+        let mut package_list = match self.resolver.take() {
+            Some(resolver) => resolver(config, &mut report.cargo_attempts)?,
+            None => {
+                let package_list = resolve_packages(config, &mut report.cargo_attempts)?;
+                record_fingerprint(&package_list);
+                package_list
+            }
+        };
+        if config.source_warnings {
+            report.source_warnings = source_warnings(&package_list, config);
+            for warning in report.source_warnings.iter() {
+                log::warn!("{}", warning);
+            }
+        }
+        report.record_timing(Stage::Resolve, start);
+        run_hooks(
+            &mut self.hooks,
+            Stage::Resolve,
+            &mut package_list,
+            config,
+            &mut report,
+        );
+
+        let start = Instant::now();
+        let cache = restore_from_cache(&mut package_list, config, &mut report);
+        report.record_timing(Stage::Cache, start);
+        run_hooks(
+            &mut self.hooks,
+            Stage::Cache,
+            &mut package_list,
+            config,
+            &mut report,
+        );
+
+        let start = Instant::now();
+        fetch_from_sources(&mut package_list, config, &mut report, cache);
+        report.record_timing(Stage::Registry, start);
+        run_hooks(
+            &mut self.hooks,
+            Stage::Registry,
+            &mut package_list,
+            config,
+            &mut report,
+        );
+
+        let start = Instant::now();
+        let this_package_index = package_list
+            .iter()
+            .position(|p| p.name == config.package_name)
+            .ok_or_else(|| BuildError::RootMissing {
+                package_name: config.package_name.clone(),
+            })?;
+        let mut root_license_identifier =
+            package_list[this_package_index].license_identifier.clone();
+        if config.include_root {
+            info!("Fetching license for: {}", &config.package_name);
+            fetch_root_license(&mut package_list[this_package_index], config, &mut report);
+            let root = package_list.remove(this_package_index);
+            package_list.insert(0, root);
+        } else {
+            package_list.remove(this_package_index);
+        }
+        report.record_timing(Stage::Root, start);
+        run_hooks(
+            &mut self.hooks,
+            Stage::Root,
+            &mut package_list,
+            config,
+            &mut report,
+        );
+        // Post-processing keeps the package itself in front, so hooks must not move it.
+        if config.include_root
+            && package_list
+                .first()
+                .is_none_or(|package| package.name != config.package_name)
+        {
+            return Err(BuildError::RootMissing {
+                package_name: config.package_name.clone(),
+            });
+        }
+
+        let start = Instant::now();
+        post_process(&mut package_list, config, &mut report);
+        let dependencies = if config.include_root {
+            root_license_identifier = package_list[0].license_identifier.clone();
+            &package_list[1..]
+        } else {
+            &package_list[..]
+        };
+        report.copyleft_warnings =
+            copyleft_warnings(dependencies, root_license_identifier.as_deref());
+        for warning in report.copyleft_warnings.iter() {
+            log::warn!("{}", warning);
+        }
+        report.record_timing(Stage::PostProcess, start);
+        run_hooks(
+            &mut self.hooks,
+            Stage::PostProcess,
+            &mut package_list,
+            config,
+            &mut report,
+        );
+        write_sidecars(&package_list, config);
+
+        json_log::finish(config, package_list.len(), &report);
+        Ok((package_list, report))
+    }
+}
+
+/// License cache with its location and the checksums of the `Cargo.lock`.
+///
+/// The location is `None` if only a read-only seed is used.
+pub(super) type LoadedCache = (
+    Option<PathBuf>,
+    BTreeMap<String, String>,
+    cache::LicenseCache,
+);
+
+/// Restores license texts from the cache and its seed, if configured, and records the [CacheStats].
+pub(super) fn restore_from_cache(
+    package_list: &mut PackageList,
+    config: &Config,
+    report: &mut FetchReport,
+) -> Option<LoadedCache> {
+    cache::LicenseCache::load_with_seed(config).map(|(path, cache)| {
+        let checksums = cache::lockfile_checksums(&config.manifest_dir);
+        let mut stats = CacheStats::default();
+        for package in package_list.iter_mut() {
+            if cache.restore(package, &checksums) {
+                stats.restored += 1;
+            } else if checksums.contains_key(&cache::cache_key(package)) {
+                stats.missed += 1;
+            } else {
+                stats.uncacheable += 1;
+            }
+        }
+        info!("{}", stats);
+        report.cache = Some(stats);
+        (path, checksums, cache)
+    })
+}
+
+/// Reads missing license texts from patched sources and the cargo registry and updates the cache.
+///
+/// Before updating the cache, license identifiers are compared with older versions in it.
+pub(super) fn fetch_from_sources(
+    package_list: &mut PackageList,
+    config: &Config,
+    report: &mut FetchReport,
+    cache: Option<LoadedCache>,
+) {
+    // Only packages with checksum are located in the registry.
+    let registry_needed = cache.as_ref().is_none_or(|(_, checksums, _)| {
+        package_list.iter().any(|package| {
+            package.license_text.is_none() && checksums.contains_key(&cache::cache_key(package))
+        })
+    });
+    licenses_text_from_patched_sources(package_list, config, report);
+    if registry_needed {
+        RegistryBackend.fetch(package_list, config, report);
+    } else {
+        info!("All licenses restored from cache. Skipping registry.");
+    }
+    fetch_from_registered_backends(package_list, config, report);
+
+    if let Some((path, checksums, mut cache)) = cache {
+        report.license_changes = cache.license_changes(package_list);
+        for change in report.license_changes.iter() {
+            log::warn!("{}", change);
+        }
+        let mut changed = false;
+        for package in package_list.iter() {
+            changed |= cache.insert(package, &checksums);
+        }
+        match path {
+            Some(_) if !changed && config.cache == CachePolicy::RefreshChanged => {
+                info!("License cache is up to date.")
+            }
+            Some(path) => cache.save(&path),
+            None => {}
+        }
+    }
+}
+
+/// Finds strong copyleft dependencies of a root package that is not strong copyleft itself.
+///
+/// Every package of the list is linked into the program, as only normal dependencies are fetched.
+fn copyleft_warnings(
+    dependencies: &[Package],
+    root_license_identifier: Option<&str>,
+) -> Vec<CopyleftWarning> {
+    let root_copyleft = root_license_identifier
+        .and_then(LicenseInfo::from_expression)
+        .map(|info| info.copyleft);
+    if root_copyleft == Some(Copyleft::Strong) {
+        return vec![];
+    }
+
+    dependencies
+        .iter()
+        .filter(|package| {
+            package
+                .license_info
+                .is_some_and(|info| info.copyleft == Copyleft::Strong)
+        })
+        .map(|package| CopyleftWarning {
+            package_name: package.name.clone(),
+            package_version: package.version.clone(),
+            license_identifier: package.license_identifier.clone().unwrap_or_default(),
+            root_license_identifier: root_license_identifier.map(str::to_owned),
+        })
+        .collect()
+}
+
+/// Returns `true` if the package can be used under the excluded licenses only.
+fn is_excluded(package: &Package, excluded_licenses: &[String]) -> bool {
+    package
+        .effective_license
+        .as_deref()
+        .or(package.license_identifier.as_deref())
+        .and_then(spdx::Expression::parse)
+        .is_some_and(|e| e.is_allowed(excluded_licenses))
+}
+
+/// Drops dependencies with excluded licenses. The package itself is kept.
+fn exclude_packages(package_list: &mut PackageList, config: &Config) {
+    let mut dependencies = package_list.split_off(usize::from(config.include_root));
+    dependencies.retain(|package| {
+        let excluded = is_excluded(package, &config.excluded_licenses);
+        if excluded {
+            info!(
+                "Excluding {} {} with license: {}",
+                package.name,
+                package.version,
+                package.license_identifier.as_deref().unwrap_or_default()
+            );
+        }
+        !excluded
+    });
+    package_list.extend(dependencies);
+}
+
+fn run_hooks(
+    hooks: &mut Vec<(Stage, Hook)>,
+    stage: Stage,
+    package_list: &mut PackageList,
+    config: &Config,
+    report: &mut FetchReport,
+) {
+    let (current, rest) = hooks.drain(..).partition(|(s, _)| *s == stage);
+    *hooks = rest;
+    for (_, hook) in current {
+        hook(package_list, config, report);
+    }
+}
+
+pub(super) fn post_process(
+    package_list: &mut PackageList,
+    config: &Config,
+    report: &mut FetchReport,
+) {
+    package_list.extend(config.extra_packages.iter().cloned());
+    override_license_texts(package_list, config, report);
+
+    if !config.extended_metadata {
+        for package in package_list.iter_mut() {
+            package.extended_metadata = None;
+        }
+    }
+
+    #[cfg(feature = "clearlydefined")]
+    if config.clearlydefined && config.network == NetworkPolicy::Forbidden {
+        info!("Not querying ClearlyDefined, as network access is forbidden.");
+    } else if config.clearlydefined {
+        super::clearlydefined::enrich_package_list(package_list);
+    }
+
+    for package in package_list.iter_mut() {
+        if let Some(license_override) = config
+            .license_overrides
+            .iter()
+            .find(|o| o.matches(&package.name, &package.version))
+        {
+            info!(
+                "Overriding license of {} {} with: {}",
+                package.name, package.version, license_override.expression
+            );
+            package.license_identifier = Some(license_override.expression.clone());
+        }
+
+        for (_, annotation) in config
+            .annotations
+            .iter()
+            .filter(|(name, _)| *name == package.name)
+        {
+            if !package.annotations.contains(annotation) {
+                package.annotations.push(annotation.clone());
+            }
+        }
+
+        if !config.allowed_licenses.is_empty() {
+            let allowed = package
+                .license_identifier
+                .as_deref()
+                .and_then(spdx::Expression::parse)
+                .is_some_and(|e| e.is_allowed(&config.allowed_licenses));
+            if !allowed {
+                let violation = PolicyViolation {
+                    package_name: package.name.clone(),
+                    package_version: package.version.clone(),
+                    license_identifier: package.license_identifier.clone(),
+                };
+                log::warn!("{}", violation);
+                report.policy_violations.push(violation);
+            }
+        }
+
+        package.license_info = package
+            .license_identifier
+            .as_deref()
+            .and_then(LicenseInfo::from_expression);
+        if !config.license_preference.is_empty() {
+            package.effective_license = package
+                .license_identifier
+                .as_deref()
+                .and_then(|id| spdx::effective_license(id, &config.license_preference));
+        }
+    }
+
+    if !config.excluded_licenses.is_empty() {
+        exclude_packages(package_list, config);
+    }
+
+    match config.authors {
+        AuthorsPolicy::Keep => {}
+        AuthorsPolicy::StripEmails => {
+            for package in package_list.iter_mut() {
+                package.authors = package
+                    .authors
+                    .iter()
+                    .filter_map(|author| strip_email(author))
+                    .collect();
+            }
+        }
+        AuthorsPolicy::Drop => {
+            for package in package_list.iter_mut() {
+                package.authors.clear();
+            }
+        }
+    }
+
+    let dependencies = if config.include_root {
+        &mut package_list[1..]
+    } else {
+        &mut package_list[..]
+    };
+    sort_packages(dependencies, config.sort);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::build_script::fs::test_dir;
+    use crate::build_script::{ConfigBuilder, RootLicense};
+
+    fn pipeline() -> Pipeline {
+        pipeline_with_root(true)
+    }
+
+    fn pipeline_with_root(include_root: bool) -> Pipeline {
+        let config = ConfigBuilder::new(std::env::temp_dir(), "root")
+            .root_license(RootLicense::Text("Root License".to_owned()))
+            .include_root(include_root)
+            .build();
+        Pipeline::new(config).resolver(|_, _| {
+            Ok(PackageList(vec![
+                Package::builder().name("a").version("1.0.0").build(),
+                Package::builder().name("root").version("0.1.0").build(),
+            ]))
+        })
+    }
+
+    #[test]
+    fn test_hooks_run_after_their_stage() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let hook = |stage: Stage, label: &'static str| {
+            let calls = calls.clone();
+            move |package_list: &mut PackageList, _: &Config, report: &mut FetchReport| {
+                assert_eq!(report.timings.last().unwrap().stage, stage);
+                let names: Vec<_> = package_list.iter().map(|p| p.name.clone()).collect();
+                calls.borrow_mut().push((label, names));
+            }
+        };
+
+        let (package_list, report) = pipeline()
+            .after(Stage::PostProcess, hook(Stage::PostProcess, "post process"))
+            .after(Stage::Resolve, hook(Stage::Resolve, "resolve"))
+            .after(Stage::Root, hook(Stage::Root, "root"))
+            .after(Stage::Root, |package_list, _, _| {
+                package_list.push(Package::builder().name("b").version("1.0.0").build());
+            })
+            .after(Stage::Root, hook(Stage::Root, "root again"))
+            .run();
+
+        assert_eq!(
+            *calls.borrow(),
+            [
+                ("resolve", vec!["a".to_owned(), "root".to_owned()]),
+                ("root", vec!["root".to_owned(), "a".to_owned()]),
+                (
+                    "root again",
+                    vec!["root".to_owned(), "a".to_owned(), "b".to_owned()]
+                ),
+                (
+                    "post process",
+                    vec!["root".to_owned(), "a".to_owned(), "b".to_owned()]
+                ),
+            ]
+        );
+        assert_eq!(package_list.len(), 3);
+        assert_eq!(
+            package_list[0].license_text.as_deref(),
+            Some("Root License")
+        );
+        let stages: Vec<_> = report.timings.iter().map(|t| t.stage).collect();
+        assert_eq!(
+            stages,
+            [
+                Stage::Resolve,
+                Stage::Cache,
+                Stage::Registry,
+                Stage::Root,
+                Stage::PostProcess
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skip_runs_no_hooks() {
+        let config = ConfigBuilder::new(std::env::temp_dir(), "root")
+            .skip(true)
+            .build();
+        let (package_list, report) = Pipeline::new(config)
+            .resolver(|_, _| unreachable!())
+            .after(Stage::Resolve, |_, _, _| unreachable!())
+            .run();
+        assert!(package_list.is_empty());
+        assert!(report.timings.is_empty());
+    }
+
+    #[test]
+    fn test_hook_removing_root_fails() {
+        let result = pipeline()
+            .after(Stage::Registry, |package_list, _, _| {
+                package_list.retain(|p| p.name != "root");
+            })
+            .try_run();
+        assert!(matches!(
+            result,
+            Err(BuildError::RootMissing { package_name }) if package_name == "root"
+        ));
+    }
+
+    #[test]
+    fn test_hook_moving_root_fails() {
+        let result = pipeline()
+            .after(Stage::Root, |package_list, _, _| {
+                package_list.retain(|p| p.name != "root");
+            })
+            .try_run();
+        assert!(matches!(
+            result,
+            Err(BuildError::RootMissing { package_name }) if package_name == "root"
+        ));
+
+        let result = pipeline()
+            .after(Stage::Root, |package_list, _, _| package_list.clear())
+            .try_run();
+        assert!(matches!(result, Err(BuildError::RootMissing { .. })));
+
+        let result = pipeline()
+            .after(Stage::Root, |package_list, _, _| package_list.reverse())
+            .try_run();
+        assert!(matches!(result, Err(BuildError::RootMissing { .. })));
+
+        // Without the package itself, hooks may remove any package.
+        let (package_list, _) = pipeline_with_root(false)
+            .after(Stage::Root, |package_list, _, _| package_list.clear())
+            .try_run()
+            .unwrap();
+        assert!(package_list.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "LF1011: Package root is missing")]
+    fn test_hook_removing_root_panics() {
+        pipeline()
+            .after(Stage::Registry, |package_list, _, _| {
+                package_list.retain(|p| p.name != "root");
+            })
+            .run();
+    }
+
+    #[test]
+    fn test_copyleft_warnings() {
+        let dependencies = [
+            Package::builder()
+                .name("a")
+                .license_identifier("GPL-3.0-only")
+                .build(),
+            Package::builder()
+                .name("b")
+                .license_identifier("MIT OR GPL-3.0-only")
+                .build(),
+        ];
+
+        let warnings = copyleft_warnings(&dependencies, Some("MIT"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].package_name, "a");
+
+        assert!(copyleft_warnings(&dependencies, Some("GPL-3.0-or-later")).is_empty());
+    }
+
+    #[test]
+    fn test_cache_stats() {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let checksums = cache::lockfile_checksums(manifest_dir.as_ref());
+        let mut keys = checksums.keys().map(|key| key.split_once(' ').unwrap());
+        let package =
+            |(name, version): (&str, &str)| Package::builder().name(name).version(version).build();
+        let (cached, missing) = (keys.next().unwrap(), keys.next().unwrap());
+
+        let mut seed = cache::LicenseCache::default();
+        seed.insert(
+            &Package::builder()
+                .name(cached.0)
+                .version(cached.1)
+                .license_text("MIT")
+                .build(),
+            &checksums,
+        );
+        let seed_path = test_dir("cache-stats").join("seed.bincode");
+        seed.save(&seed_path);
+
+        let config = ConfigBuilder::new(manifest_dir, "root")
+            .cache_seed_path(&seed_path)
+            .build();
+        let mut package_list = PackageList(vec![
+            package(cached),
+            package(missing),
+            package(("root", "0.1.0")),
+        ]);
+        let mut report = FetchReport::default();
+        restore_from_cache(&mut package_list, &config, &mut report);
+
+        assert_eq!(
+            report.cache,
+            Some(CacheStats {
+                restored: 1,
+                missed: 1,
+                uncacheable: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_exclude_packages() {
+        let config = ConfigBuilder::new(std::env::temp_dir(), "root")
+            .exclude_licenses(["CC0-1.0", "Unlicense"])
+            .build();
+        let mut package_list = PackageList(vec![
+            Package::builder()
+                .name("root")
+                .license_identifier("CC0-1.0")
+                .build(),
+            Package::builder()
+                .name("a")
+                .license_identifier("CC0-1.0 OR MIT")
+                .build(),
+            Package::builder()
+                .name("b")
+                .license_identifier("CC0-1.0 OR MIT")
+                .effective_license("MIT")
+                .build(),
+            Package::builder()
+                .name("c")
+                .license_identifier("Unlicense AND MIT")
+                .build(),
+            Package::builder().name("d").build(),
+        ]);
+        exclude_packages(&mut package_list, &config);
+
+        let names: Vec<&str> = package_list.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["root", "b", "c", "d"]);
+    }
+}
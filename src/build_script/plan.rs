@@ -0,0 +1,208 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Dry run of the license fetching.
+
+use std::env::var_os;
+use std::fmt;
+use std::path::PathBuf;
+
+use super::cargo_source::registry_src_folders;
+use super::config::{Config, LicenseTextOverride, RootLicense};
+use super::report::CargoAttempt;
+use super::{cache, resolve_packages};
+
+/// Where the license text of a package is expected to come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PlannedSource {
+    /// Restored from the license cache.
+    Cache,
+    /// License files in a folder of the cargo registry, with the configured fallbacks if there are none.
+    Registry(PathBuf),
+    /// License files in the manifest dir of the package itself or of a patched package.
+    ManifestDir(PathBuf),
+    /// A single file configured with [RootLicense::File] or [LicenseTextOverride::File].
+    File(PathBuf),
+    /// A fixed text configured with [RootLicense::Text] or [LicenseTextOverride::Text].
+    Text,
+    /// No license text, as configured with [RootLicense::Skip].
+    Skip,
+    /// No source was found, e.g. for path or git dependencies.
+    Missing,
+}
+
+impl fmt::Display for PlannedSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cache => write!(f, "cache"),
+            Self::Registry(path) => write!(f, "registry {:?}", path),
+            Self::ManifestDir(path) => write!(f, "manifest dir {:?}", path),
+            Self::File(path) => write!(f, "file {:?}", path),
+            Self::Text => write!(f, "configured text"),
+            Self::Skip => write!(f, "skipped"),
+            Self::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+/// A package that will be included in the package list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PlannedPackage {
+    pub package_name: String,
+    pub package_version: String,
+    pub source: PlannedSource,
+}
+
+/// Result of [plan].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct FetchPlan {
+    /// Packages in the order they are fetched. The package itself comes first, if included.
+    pub packages: Vec<PlannedPackage>,
+    /// File the encoded license data is written to by [PackageList::write](crate::PackageList::write).
+    /// `None` outside of build scripts.
+    pub output: Option<PathBuf>,
+    pub cargo_attempts: Vec<CargoAttempt>,
+}
+
+impl FetchPlan {
+    /// Packages without any expected license source.
+    pub fn missing(&self) -> impl Iterator<Item = &PlannedPackage> {
+        self.packages
+            .iter()
+            .filter(|package| package.source == PlannedSource::Missing)
+    }
+}
+
+impl fmt::Display for FetchPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for package in &self.packages {
+            writeln!(
+                f,
+                "{} {}: {}",
+                package.package_name, package.package_version, package.source
+            )?;
+        }
+        if let Some(output) = &self.output {
+            writeln!(f, "Output: {:?}", output)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves which packages are included and where their licenses are expected to come from,
+/// without reading any license file.
+///
+/// Useful for debugging a [Config]. Runs `cargo metadata` and `cargo tree` like the real fetching.
+pub fn plan(config: Config) -> FetchPlan {
+    let output = var_os("OUT_DIR").map(|dir| PathBuf::from(dir).join("LICENSE-3RD-PARTY.bincode"));
+    let mut cargo_attempts = vec![];
+    if config.skip {
+        return FetchPlan {
+            packages: vec![],
+            output,
+            cargo_attempts,
+        };
+    }
+
+    let mut package_list =
+        resolve_packages(&config, &mut cargo_attempts).unwrap_or_else(|e| panic!("{}", e));
+
+    let cache = cache::LicenseCache::load_with_seed(&config)
+        .map(|(_, cache)| (cache, cache::lockfile_checksums(&config.manifest_dir)));
+    let src_folders = registry_src_folders();
+
+    let root_index = package_list
+        .iter()
+        .position(|package| package.name == config.package_name);
+    if let Some(index) = root_index {
+        let root = package_list.remove(index);
+        if config.include_root {
+            package_list.insert(0, root);
+        }
+    }
+
+    let packages = package_list
+        .iter()
+        .enumerate()
+        .map(|(index, package)| {
+            let text_override = config
+                .license_text_overrides
+                .iter()
+                .find(|(name, _)| *name == package.name);
+            let source = if let Some((_, text_override)) = text_override {
+                match text_override {
+                    LicenseTextOverride::File(path) => {
+                        PlannedSource::File(config.manifest_dir.join(path))
+                    }
+                    LicenseTextOverride::Text(_) => PlannedSource::Text,
+                }
+            } else if index == 0 && root_index.is_some() && config.include_root {
+                match &config.root_license {
+                    RootLicense::Auto => PlannedSource::ManifestDir(config.manifest_dir.clone()),
+                    RootLicense::File(path) => PlannedSource::File(config.manifest_dir.join(path)),
+                    RootLicense::Text(_) => PlannedSource::Text,
+                    RootLicense::Skip => PlannedSource::Skip,
+                }
+            } else if package.patched {
+                package
+                    .manifest_path
+                    .as_deref()
+                    .and_then(|path| PathBuf::from(path).parent().map(PathBuf::from))
+                    .map_or(PlannedSource::Missing, PlannedSource::ManifestDir)
+            } else if cache
+                .as_ref()
+                .is_some_and(|(cache, checksums)| cache.restore(&mut package.clone(), checksums))
+            {
+                PlannedSource::Cache
+            } else {
+                src_folders
+                    .iter()
+                    .map(|folder| folder.join(format!("{}-{}", package.name, package.version)))
+                    .find(|folder| folder.is_dir())
+                    .map_or(PlannedSource::Missing, PlannedSource::Registry)
+            };
+
+            PlannedPackage {
+                package_name: package.name.clone(),
+                package_version: package.version.clone(),
+                source,
+            }
+        })
+        .collect();
+
+    FetchPlan {
+        packages,
+        output,
+        cargo_attempts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::build_script::{generate_package_list_with_report, ConfigBuilder};
+
+    #[test]
+    fn test_skip_runs_no_cargo() {
+        let config = || {
+            ConfigBuilder::new(std::env::temp_dir(), "root")
+                .cargo_path(Some("license-fetcher-test-missing-cargo".into()))
+                .skip(true)
+                .build()
+        };
+
+        let plan = plan(config());
+        assert!(plan.packages.is_empty());
+        assert!(plan.cargo_attempts.is_empty());
+
+        let (package_list, report) = generate_package_list_with_report(config());
+        assert!(package_list.is_empty());
+        assert!(report.cargo_attempts.is_empty());
+    }
+}
@@ -0,0 +1,561 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use super::pipeline::Stage;
+use crate::error::ErrorCode;
+
+/// Failure to read license information of a single package.
+///
+/// Fetching continues after such a failure, so the package may still hold partial information.
+#[derive(Debug)]
+pub struct FetchFailure {
+    pub package_name: String,
+    pub package_version: String,
+    /// File or folder that could not be read.
+    pub path: PathBuf,
+    pub cause: io::Error,
+}
+
+impl FetchFailure {
+    /// Returns the stable [ErrorCode] of this failure.
+    pub fn code(&self) -> ErrorCode {
+        ErrorCode::Unreadable
+    }
+}
+
+impl fmt::Display for FetchFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: Failed reading {:?} of package {} {}: {}",
+            self.code(),
+            self.path,
+            self.package_name,
+            self.package_version,
+            self.cause
+        )
+    }
+}
+
+impl Error for FetchFailure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.cause)
+    }
+}
+
+/// The cargo executable could not be run, e.g. because it is not in `PATH`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct CargoNotFound {
+    /// Path or name cargo was run with.
+    pub path: OsString,
+    pub cause: io::Error,
+}
+
+impl CargoNotFound {
+    /// Returns the stable [ErrorCode] of this error.
+    pub fn code(&self) -> ErrorCode {
+        ErrorCode::CargoNotFound
+    }
+}
+
+impl fmt::Display for CargoNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: Failed running cargo as {:?}: {}",
+            self.code(),
+            self.path,
+            self.cause
+        )
+    }
+}
+
+impl Error for CargoNotFound {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.cause)
+    }
+}
+
+/// Error of the build step, which can't produce a package list.
+///
+/// Returned by the `try_` variants of the build functions, like
+/// [try_generate_package_list_with_report](super::try_generate_package_list_with_report). The other variants panic
+/// with its message. Failures of single packages do not abort the build step and are collected in the
+/// [FetchReport] instead.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BuildError {
+    CargoNotFound(CargoNotFound),
+    /// A cargo subcommand failed.
+    CargoFailed {
+        subcommand: String,
+        /// [ErrorCode::CargoFailed] or [ErrorCode::CargoLockContention].
+        code: ErrorCode,
+        stderr: String,
+    },
+    /// `cargo metadata` needs network access, which is forbidden by the
+    /// [NetworkPolicy](super::NetworkPolicy).
+    NetworkForbidden {
+        /// Packages of the `Cargo.lock` missing in the local registry. `None` if there is no `Cargo.lock`.
+        missing: Option<Vec<String>>,
+        stderr: String,
+    },
+    /// The output of `cargo metadata` could not be parsed.
+    MetadataParse(serde_json::Error),
+    /// The `Cargo.lock` read instead of running cargo is missing, invalid or lacks the package itself.
+    Lockfile {
+        message: String,
+        cause: Option<Box<dyn Error + Send + Sync>>,
+    },
+    /// The package itself was removed by a hook, or moved from the front of the list by a hook of [Stage::Root].
+    RootMissing {
+        package_name: String,
+    },
+    /// The env variable `OUT_DIR` is not set, e.g. as the license data is written outside of a build script.
+    OutDirMissing,
+    /// The license data could not be written.
+    Write {
+        path: PathBuf,
+        cause: io::Error,
+    },
+}
+
+impl BuildError {
+    /// Returns the stable [ErrorCode] of this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::CargoNotFound(e) => e.code(),
+            Self::CargoFailed { code, .. } => *code,
+            Self::NetworkForbidden { .. } => ErrorCode::NetworkForbidden,
+            Self::MetadataParse(_) | Self::Lockfile { .. } => ErrorCode::MetadataParse,
+            Self::RootMissing { .. } => ErrorCode::RootMissing,
+            Self::OutDirMissing => ErrorCode::OutDirMissing,
+            Self::Write { .. } => ErrorCode::WriteFailed,
+        }
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CargoNotFound(e) => e.fmt(f),
+            Self::CargoFailed {
+                subcommand, stderr, ..
+            } => write!(
+                f,
+                "{}: Failed executing cargo {} with:\n{}",
+                self.code(),
+                subcommand,
+                stderr
+            ),
+            Self::NetworkForbidden { missing, stderr } => {
+                let missing = match missing {
+                    Some(missing) if missing.is_empty() => {
+                        "None. Cargo.lock may need to be updated.".to_owned()
+                    }
+                    Some(missing) => missing.join("\n"),
+                    None => "Cargo.lock not found.".to_owned(),
+                };
+                write!(
+                    f,
+                    "{}: cargo metadata needs network access, which is forbidden by the network policy.\n\
+                    Packages missing in the local registry:\n{}\n\
+                    cargo metadata failed with:\n{}",
+                    self.code(),
+                    missing,
+                    stderr
+                )
+            }
+            Self::MetadataParse(e) => {
+                write!(f, "{}: Failed parsing cargo metadata: {}", self.code(), e)
+            }
+            Self::Lockfile { message, cause } => {
+                write!(f, "{}: {}", self.code(), message)?;
+                if let Some(cause) = cause {
+                    write!(f, ": {}", cause)?;
+                }
+                Ok(())
+            }
+            Self::RootMissing { package_name } => write!(
+                f,
+                "{}: Package {} is missing in the package list or was moved from its front by a hook of {:?}.",
+                self.code(),
+                package_name,
+                Stage::Root
+            ),
+            Self::OutDirMissing => write!(
+                f,
+                "{}: OUT_DIR is not set. License data can only be written from a build script.",
+                self.code()
+            ),
+            Self::Write { path, cause } => write!(
+                f,
+                "{}: Failed writing license data to {:?}: {}",
+                self.code(),
+                path,
+                cause
+            ),
+        }
+    }
+}
+
+impl Error for BuildError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::CargoNotFound(e) => Some(e),
+            Self::MetadataParse(e) => Some(e),
+            Self::Lockfile {
+                cause: Some(cause), ..
+            } => Some(cause.as_ref()),
+            Self::Write { cause, .. } => Some(cause),
+            _ => None,
+        }
+    }
+}
+
+impl From<CargoNotFound> for BuildError {
+    fn from(e: CargoNotFound) -> Self {
+        Self::CargoNotFound(e)
+    }
+}
+
+/// Package whose license is not covered by the allowed licenses of the [Config](super::Config).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyViolation {
+    pub package_name: String,
+    pub package_version: String,
+    pub license_identifier: Option<String>,
+}
+
+impl PolicyViolation {
+    /// Returns the stable [ErrorCode] of this violation.
+    pub fn code(&self) -> ErrorCode {
+        ErrorCode::PolicyViolation
+    }
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: License of package {} {} is not allowed: {}",
+            self.code(),
+            self.package_name,
+            self.package_version,
+            self.license_identifier.as_deref().unwrap_or("unknown")
+        )
+    }
+}
+
+/// Contribution of the license text of a package to the size of the embedded data.
+///
+/// See [PackageList::size_report](crate::PackageList::size_report).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PackageSize {
+    pub package_name: String,
+    pub package_version: String,
+    /// Length of the license text.
+    pub license_text_bytes: usize,
+    /// Length of the license text compressed on its own.
+    pub compressed_bytes: usize,
+}
+
+impl fmt::Display for PackageSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} Bytes ({} Bytes compressed)",
+            self.package_name, self.package_version, self.license_text_bytes, self.compressed_bytes
+        )
+    }
+}
+
+/// A single invocation of cargo.
+///
+/// Cargo is first called with `--frozen` and, unless the feature `frozen` is set, again without
+/// on failure. Each invocation is recorded, so failures of the preferred directive are not lost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CargoAttempt {
+    /// Cargo subcommand, like `metadata` or `tree`.
+    pub subcommand: String,
+    /// Directive like `--frozen`. `None` if cargo was called without.
+    pub directive: Option<String>,
+    pub duration: Duration,
+    /// Exit code of cargo. `None` if cargo was terminated by a signal.
+    pub exit_code: Option<i32>,
+    /// Beginning of the error output of cargo.
+    pub stderr: String,
+}
+
+/// Returns `true` if the error output of cargo reports a lock held by another cargo process.
+pub(super) fn is_lock_contention(stderr: &str) -> bool {
+    [
+        "waiting for file lock on",
+        "failed to acquire cache lock",
+        "failed to lock file",
+    ]
+    .iter()
+    .any(|message| stderr.contains(message))
+}
+
+impl CargoAttempt {
+    /// Returns `true` if cargo exited successfully.
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// Returns `true` if cargo failed on a lock held by another cargo process, like the one of the package cache.
+    pub fn is_lock_contention(&self) -> bool {
+        !self.succeeded() && is_lock_contention(&self.stderr)
+    }
+}
+
+impl fmt::Display for CargoAttempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_lock_contention() {
+            write!(f, "{}: ", ErrorCode::CargoLockContention)?;
+        } else if !self.succeeded() {
+            write!(f, "{}: ", ErrorCode::CargoFailed)?;
+        }
+        write!(f, "cargo {}", self.subcommand)?;
+        if let Some(directive) = &self.directive {
+            write!(f, " {}", directive)?;
+        }
+        match self.exit_code {
+            Some(code) => write!(f, " exited with {} after {:?}", code, self.duration)?,
+            None => write!(f, " was terminated after {:?}", self.duration)?,
+        }
+        if !self.stderr.is_empty() {
+            write!(f, ":\n{}", self.stderr)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reason a package may not correspond to its published sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SourceWarningKind {
+    /// The version is a pre-release like `1.0.0-beta.1`.
+    PreRelease,
+    /// The package is not located in the cargo registry, e.g. path and git dependencies or patched crates.
+    NotInRegistry,
+}
+
+/// Package that may not correspond to published sources.
+///
+/// Only recorded with [ConfigBuilder::source_warnings](super::ConfigBuilder::source_warnings).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SourceWarning {
+    pub package_name: String,
+    pub package_version: String,
+    pub kind: SourceWarningKind,
+}
+
+impl fmt::Display for SourceWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Package {} {} ", self.package_name, self.package_version)?;
+        match self.kind {
+            SourceWarningKind::PreRelease => write!(f, "is a pre-release."),
+            SourceWarningKind::NotInRegistry => write!(f, "is not located in the cargo registry."),
+        }
+    }
+}
+
+/// Dependency under a strong copyleft license, like `GPL-3.0-only`, linked into a package that is not
+/// licensed under a strong copyleft license itself.
+///
+/// The obligations of the dependency may extend to the whole program. This is a hint for a review,
+/// not a legal assessment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CopyleftWarning {
+    pub package_name: String,
+    pub package_version: String,
+    pub license_identifier: String,
+    /// License identifier of the package itself.
+    pub root_license_identifier: Option<String>,
+}
+
+impl fmt::Display for CopyleftWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Package {} {} is licensed under the strong copyleft license {}, but the package itself is licensed under {}.",
+            self.package_name,
+            self.package_version,
+            self.license_identifier,
+            self.root_license_identifier.as_deref().unwrap_or("an unknown license")
+        )
+    }
+}
+
+/// Package whose license identifier differs from the one of an older version in the license cache.
+///
+/// Only detected if the [CachePolicy](super::CachePolicy) reads the cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LicenseChange {
+    pub package_name: String,
+    /// Closest older version found in the cache.
+    pub previous_version: String,
+    pub previous_license_identifier: String,
+    pub package_version: String,
+    pub license_identifier: String,
+}
+
+impl fmt::Display for LicenseChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "License of package {} changed from {} in {} to {} in {}.",
+            self.package_name,
+            self.previous_license_identifier,
+            self.previous_version,
+            self.license_identifier,
+            self.package_version
+        )
+    }
+}
+
+/// Duration of a [Stage] of the license fetching, excluding [Pipeline](super::Pipeline) hooks.
+///
+/// Durations of the individual cargo calls are recorded in [CargoAttempt]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StageTiming {
+    pub stage: Stage,
+    pub duration: Duration,
+}
+
+impl fmt::Display for StageTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Stage {:?} took {:?}.", self.stage, self.duration)
+    }
+}
+
+/// Effectiveness of the license cache in a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct CacheStats {
+    /// Packages whose license text was restored from the cache or its seed.
+    pub restored: usize,
+    /// Packages with checksum in `Cargo.lock` that had no valid entry and were fetched again.
+    pub missed: usize,
+    /// Packages without checksum, like path or git dependencies, which are never cached.
+    pub uncacheable: usize,
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "License cache: {} restored, {} missed, {} uncacheable.",
+            self.restored, self.missed, self.uncacheable
+        )
+    }
+}
+
+/// Report of non fatal problems that occurred while fetching licenses.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct FetchReport {
+    pub failures: Vec<FetchFailure>,
+    pub policy_violations: Vec<PolicyViolation>,
+    /// All invocations of cargo in order, including failed ones that were retried successfully.
+    pub cargo_attempts: Vec<CargoAttempt>,
+    pub source_warnings: Vec<SourceWarning>,
+    pub copyleft_warnings: Vec<CopyleftWarning>,
+    pub license_changes: Vec<LicenseChange>,
+    /// Durations of the stages in the order they ran.
+    pub timings: Vec<StageTiming>,
+    /// Hits and misses of the license cache. `None` if neither cache nor seed is configured.
+    pub cache: Option<CacheStats>,
+}
+
+impl FetchReport {
+    /// Returns `true` if no problems occurred.
+    ///
+    /// Failed [CargoAttempt]s that were retried successfully, [SourceWarning]s, [CopyleftWarning]s and
+    /// [LicenseChange]s are not considered a problem.
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty() && self.policy_violations.is_empty()
+    }
+
+    /// Total duration of all calls of a cargo subcommand like `metadata` or `tree`, including failed attempts.
+    ///
+    /// `cargo metadata` and `cargo tree` may run in parallel, so their durations can overlap.
+    pub fn cargo_duration(&self, subcommand: &str) -> Duration {
+        self.cargo_attempts
+            .iter()
+            .filter(|attempt| attempt.subcommand == subcommand)
+            .map(|attempt| attempt.duration)
+            .sum()
+    }
+
+    /// Records the duration of a stage that started at `start` and logs it.
+    pub(super) fn record_timing(&mut self, stage: Stage, start: Instant) {
+        let timing = StageTiming {
+            stage,
+            duration: start.elapsed(),
+        };
+        log::info!("{}", timing);
+        self.timings.push(timing);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetch_report() {
+        let mut report = FetchReport::default();
+        assert!(report.is_clean());
+
+        let attempt = |subcommand: &str, millis: u64| CargoAttempt {
+            subcommand: subcommand.to_owned(),
+            directive: Some("--frozen".to_owned()),
+            duration: Duration::from_millis(millis),
+            exit_code: Some(0),
+            stderr: String::new(),
+        };
+        report.cargo_attempts = vec![
+            attempt("metadata", 20),
+            attempt("tree", 5),
+            attempt("metadata", 30),
+        ];
+        assert_eq!(report.cargo_duration("metadata"), Duration::from_millis(50));
+        assert_eq!(report.cargo_duration("build"), Duration::ZERO);
+        assert!(report.is_clean());
+
+        report.failures.push(FetchFailure {
+            package_name: "a".to_owned(),
+            package_version: "1.0.0".to_owned(),
+            path: PathBuf::from("LICENSE"),
+            cause: io::Error::new(io::ErrorKind::PermissionDenied, "denied"),
+        });
+        assert!(!report.is_clean());
+
+        let failure = &report.failures[0];
+        assert_eq!(failure.code(), ErrorCode::Unreadable);
+        assert_eq!(
+            failure.to_string(),
+            "LF1001: Failed reading \"LICENSE\" of package a 1.0.0: denied"
+        );
+        assert_eq!(failure.source().unwrap().to_string(), "denied");
+    }
+}
@@ -0,0 +1,145 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use log::info;
+use serde::Serialize;
+
+use super::fs::{create_dir_all, write};
+use crate::spdx::Expression;
+use crate::PackageList;
+
+/// Entry of the manifest written by [PackageList::write_reuse_licenses].
+#[derive(Serialize)]
+struct ManifestEntry<'a> {
+    name: &'a str,
+    version: &'a str,
+    license_identifier: Option<&'a str>,
+    license_file: Option<String>,
+}
+
+/// Returns the REUSE identifier a license text is stored under.
+///
+/// Texts of packages with a single SPDX identifier are stored under that identifier.
+/// All other texts are stored under a `LicenseRef-` identifier unique to the package.
+fn reuse_identifier(name: &str, version: &str, license_identifier: Option<&str>) -> String {
+    match license_identifier.and_then(Expression::parse) {
+        Some(Expression::License {
+            id,
+            exception: None,
+        }) => id,
+        _ => {
+            let sanitized: String = format!("{}-{}", name, version)
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                        c
+                    } else {
+                        '-'
+                    }
+                })
+                .collect();
+            format!("LicenseRef-{}", sanitized)
+        }
+    }
+}
+
+impl PackageList {
+    /// Writes the license texts into a [REUSE](https://reuse.software/spec/) compliant `LICENSES` folder.
+    ///
+    /// Creates `<dir>/LICENSES/<SPDX-ID>.txt` for every license, deduplicated by identifier, where the first text
+    /// found wins. Texts of packages without a single SPDX identifier are written as `LicenseRef-<name>-<version>.txt`.
+    /// Additionally `<dir>/LICENSES.json` is written, which maps every package to its identifier and license file.
+    pub fn write_reuse_licenses(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let licenses_dir = dir.as_ref().join("LICENSES");
+        create_dir_all(&licenses_dir)?;
+
+        let mut texts: BTreeMap<String, &str> = BTreeMap::new();
+        let mut manifest = vec![];
+
+        for package in self.iter() {
+            let license_file = package.license_text.as_deref().map(|text| {
+                let identifier = reuse_identifier(
+                    &package.name,
+                    &package.version,
+                    package.license_identifier.as_deref(),
+                );
+                let file_name = format!("{}.txt", identifier);
+                texts.entry(identifier).or_insert(text);
+                format!("LICENSES/{}", file_name)
+            });
+
+            manifest.push(ManifestEntry {
+                name: &package.name,
+                version: &package.version,
+                license_identifier: package.license_identifier.as_deref(),
+                license_file,
+            });
+        }
+
+        for (identifier, text) in &texts {
+            write(licenses_dir.join(format!("{}.txt", identifier)), text)?;
+        }
+        info!(
+            "Wrote {} license texts to: {:?}",
+            texts.len(),
+            &licenses_dir
+        );
+
+        let manifest = serde_json::to_string_pretty(&manifest).map_err(io::Error::from)?;
+        write(dir.as_ref().join("LICENSES.json"), manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::build_script::fs::test_dir;
+    use crate::Package;
+
+    #[test]
+    fn test_write_reuse_licenses() {
+        let package_list = PackageList(vec![
+            Package::builder()
+                .name("a")
+                .version("1.0.0")
+                .license_identifier("MIT")
+                .license_text("MIT a")
+                .build(),
+            Package::builder()
+                .name("b")
+                .version("1.0.0")
+                .license_identifier("MIT")
+                .license_text("MIT b")
+                .build(),
+            Package::builder()
+                .name("c")
+                .version("0.1.0+x")
+                .license_identifier("MIT OR Apache-2.0")
+                .license_text("Dual")
+                .build(),
+        ]);
+
+        let dir = test_dir("reuse");
+        package_list.write_reuse_licenses(&dir).unwrap();
+
+        let licenses = dir.join("LICENSES");
+        assert_eq!(
+            std::fs::read_to_string(licenses.join("MIT.txt")).unwrap(),
+            "MIT a"
+        );
+        assert_eq!(
+            std::fs::read_to_string(licenses.join("LicenseRef-c-0.1.0-x.txt")).unwrap(),
+            "Dual"
+        );
+        assert!(dir.join("LICENSES.json").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
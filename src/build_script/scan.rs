@@ -0,0 +1,273 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! License detection of `license-fetcher` for arbitrary folders, like `third_party/` checkouts.
+//!
+//! Files are detected the same way as in the source folders of crates: every file whose name contains
+//! `license`, `copying`, `authors`, `notice` or `eula` counts, and translations are kept apart.
+//!
+//! # Example
+//! In `build.rs`:
+//! ```no_run
+//! use license_fetcher::build_script::generate_package_list_with_licenses;
+//! use license_fetcher::build_script::scan::{folder_licenses, ScanOptions};
+//! use license_fetcher::Package;
+//!
+//! fn main() {
+//!     let mut package_list = generate_package_list_with_licenses();
+//!
+//!     let licenses = folder_licenses(
+//!         concat!(env!("CARGO_MANIFEST_DIR"), "/third_party/zlib"),
+//!         &ScanOptions::default(),
+//!     )
+//!     .unwrap();
+//!     let mut zlib = Package::builder().name("zlib").version("1.3.1").build();
+//!     licenses.apply_to(&mut zlib);
+//!     package_list.push(zlib);
+//!
+//!     package_list.write();
+//! }
+//! ```
+
+use std::fs::read_to_string;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use log::{debug, trace};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::cargo_source::list_folder;
+use super::config::FetchBackend;
+use super::language::translation_language;
+use crate::{LicenseFile, LicenseTextSource, LicenseTranslation, Package};
+
+static LICENSE_FILE_NAME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i).*(license|copying|authors|notice|eula).*").unwrap());
+
+/// Options of [folder_licenses].
+///
+/// # Example
+/// ```
+/// use license_fetcher::build_script::scan::ScanOptions;
+///
+/// let options = ScanOptions::default().max_depth(2).follow_symlinks(false);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    max_depth: usize,
+    follow_symlinks: bool,
+    fetch_backend: FetchBackend,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 0,
+            follow_symlinks: true,
+            fetch_backend: FetchBackend::Std,
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Number of subfolder levels scanned below the folder. Defaults to `0`, which only scans the folder itself.
+    ///
+    /// Hidden folders like `.git` are skipped.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Follows symlinked files and folders. Enabled by default.
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Sets the implementation used for listing folders.
+    pub fn fetch_backend(mut self, fetch_backend: FetchBackend) -> Self {
+        self.fetch_backend = fetch_backend;
+        self
+    }
+}
+
+/// License files found in a folder, in the form of [Package::license_text] and [Package::license_files].
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct FolderLicenses {
+    /// Content of all license files, separated by empty lines.
+    pub license_text: String,
+    /// License files with their part of [Self::license_text]. Names are relative to the scanned folder, like
+    /// `LICENSE` or `vendor/zlib/LICENSE`.
+    pub license_files: Vec<LicenseFile>,
+    /// Translated license files. They make up the license text instead, if there is nothing else.
+    pub license_translations: Vec<LicenseTranslation>,
+    /// Files and subfolders that could not be read. They are skipped.
+    pub unreadable: Vec<(PathBuf, io::Error)>,
+}
+
+impl FolderLicenses {
+    /// Returns `true` if no license file was found.
+    pub fn is_empty(&self) -> bool {
+        self.license_files.is_empty()
+    }
+
+    /// Sets license text, files and translations of a package, unless no license file was found.
+    pub fn apply_to(&self, package: &mut Package) {
+        if self.is_empty() {
+            return;
+        }
+        package.license_text = Some(self.license_text.clone().into());
+        package.license_files = self.license_files.clone();
+        package.license_translations = self.license_translations.clone();
+        package.license_text_source = Some(LicenseTextSource::LicenseFiles);
+    }
+}
+
+/// Name of a file relative to the scanned folder, with `/` as separator.
+fn relative_name(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Potential license files of a folder and its subfolders up to `depth` levels.
+///
+/// Only fails if the folder itself can not be read. Unreadable subfolders are recorded in `unreadable`.
+fn potential_license_files(
+    path: &Path,
+    options: &ScanOptions,
+    depth: usize,
+    unreadable: &mut Vec<(PathBuf, io::Error)>,
+) -> io::Result<Vec<PathBuf>> {
+    let mut files = list_folder(
+        path,
+        options.fetch_backend,
+        false,
+        options.follow_symlinks,
+        |name| LICENSE_FILE_NAME_REGEX.is_match(name),
+    )?;
+
+    if depth > 0 {
+        let subfolders = list_folder(
+            path,
+            options.fetch_backend,
+            true,
+            options.follow_symlinks,
+            |name| !name.starts_with('.'),
+        )?;
+        for subfolder in subfolders {
+            match potential_license_files(&subfolder, options, depth - 1, unreadable) {
+                Ok(subfolder_files) => files.extend(subfolder_files),
+                Err(e) => {
+                    debug!("Skipping folder {:?}: {}", subfolder, e);
+                    unreadable.push((subfolder, e));
+                }
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Scans a folder for license files.
+///
+/// Fails if the folder can not be read. Unreadable files and subfolders are recorded in
+/// [FolderLicenses::unreadable] instead. To fill a [Package] with README and SPDX header fallbacks as for crates,
+/// see [fetch::scan_folder](super::fetch::scan_folder).
+pub fn folder_licenses(
+    path: impl AsRef<Path>,
+    options: &ScanOptions,
+) -> io::Result<FolderLicenses> {
+    let path = path.as_ref();
+    trace!("Fetching license in folder: {:?}", path);
+
+    let mut licenses = FolderLicenses::default();
+    let files =
+        potential_license_files(path, options, options.max_depth, &mut licenses.unreadable)?;
+
+    let mut originals = vec![];
+    for file in files {
+        match read_to_string(&file) {
+            Ok(text) => {
+                let file_name = file.file_name().unwrap_or_default().to_string_lossy();
+                let name = relative_name(path, &file);
+                match translation_language(&file_name, &text) {
+                    Some(language) => licenses
+                        .license_translations
+                        .push(LicenseTranslation::new(name, language, text)),
+                    None => originals.push((name, text)),
+                }
+            }
+            Err(e) => licenses.unreadable.push((file, e)),
+        }
+    }
+
+    // Translations only make up the license text, if there is nothing else.
+    if originals.is_empty() {
+        originals = licenses
+            .license_translations
+            .drain(..)
+            .map(|translation| (translation.file_name, translation.text))
+            .collect();
+    }
+
+    for (file_name, text) in originals {
+        if !licenses.license_text.is_empty() {
+            licenses.license_text.push_str("\n\n");
+        }
+        let start = licenses.license_text.len();
+        licenses.license_text.push_str(&text);
+        licenses.license_files.push(LicenseFile::new(
+            file_name,
+            start..licenses.license_text.len(),
+        ));
+    }
+
+    Ok(licenses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs::{create_dir_all, remove_dir_all, write};
+
+    use crate::build_script::fs::test_dir;
+
+    #[test]
+    fn test_folder_licenses() {
+        let dir = test_dir("scan");
+        create_dir_all(dir.join("zlib")).unwrap();
+        create_dir_all(dir.join(".git")).unwrap();
+        write(dir.join("LICENSE"), "MIT License").unwrap();
+        write(dir.join("main.c"), "int main() {}").unwrap();
+        write(dir.join("zlib/COPYING"), "zlib License").unwrap();
+        write(dir.join(".git/LICENSE"), "Not a license").unwrap();
+
+        let top = folder_licenses(&dir, &ScanOptions::default()).unwrap();
+        let nested = folder_licenses(&dir, &ScanOptions::default().max_depth(1)).unwrap();
+        remove_dir_all(&dir).unwrap();
+
+        assert_eq!(top.license_text, "MIT License");
+        assert_eq!(top.license_files[0].file_name, "LICENSE");
+
+        let mut names: Vec<_> = nested
+            .license_files
+            .iter()
+            .map(|file| file.file_name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["LICENSE", "zlib/COPYING"]);
+
+        let mut package = Package::builder().name("vendored").build();
+        nested.apply_to(&mut package);
+        assert_eq!(package.license_file_texts().count(), 2);
+    }
+}
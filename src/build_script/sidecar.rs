@@ -0,0 +1,105 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Human readable files written next to the embedded package list.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use log::info;
+
+use super::config::Config;
+use super::fs::{create_dir_all, write};
+use crate::PackageList;
+
+/// Human readable file written along with the package list, see [ConfigBuilder::sidecar_outputs](super::ConfigBuilder::sidecar_outputs).
+///
+/// Relative paths are resolved against the manifest dir, so `target/licenses.json` ends up in the `target` folder
+/// of the package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Output {
+    /// [PackageList::canonical_json].
+    Json(PathBuf),
+    /// [PackageList::to_markdown].
+    Markdown(PathBuf),
+    /// [PackageList::to_csv].
+    Csv(PathBuf),
+    /// Plain text like [Display](std::fmt::Display) of [PackageList].
+    Text(PathBuf),
+}
+
+impl Output {
+    fn path(&self) -> &Path {
+        match self {
+            Output::Json(path)
+            | Output::Markdown(path)
+            | Output::Csv(path)
+            | Output::Text(path) => path,
+        }
+    }
+
+    fn render(&self, package_list: &PackageList) -> String {
+        match self {
+            Output::Json(_) => package_list.canonical_json(),
+            Output::Markdown(_) => package_list.to_markdown(),
+            Output::Csv(_) => package_list.to_csv(),
+            Output::Text(_) => package_list.to_string(),
+        }
+    }
+
+    fn write(&self, package_list: &PackageList, manifest_dir: &Path) -> io::Result<()> {
+        let path = manifest_dir.join(self.path());
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        info!("Writing sidecar file: {:?}", path);
+        write(path, self.render(package_list))
+    }
+}
+
+/// Writes all configured sidecar files.
+///
+/// Panics if a file can not be written, like [PackageList::write].
+pub(super) fn write_sidecars(package_list: &PackageList, config: &Config) {
+    for output in config.sidecar_outputs.iter() {
+        output
+            .write(package_list, &config.manifest_dir)
+            .unwrap_or_else(|e| panic!("Failed writing sidecar file: {}", e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::build_script::fs::test_dir;
+    use crate::build_script::ConfigBuilder;
+    use crate::Package;
+
+    #[test]
+    fn test_write_sidecars() {
+        let dir = test_dir("sidecar");
+        let config = ConfigBuilder::new(dir.clone(), "test")
+            .sidecar_outputs([
+                Output::Json("target/licenses.json".into()),
+                Output::Markdown("target/LICENSES.md".into()),
+            ])
+            .build();
+        let package_list = PackageList(vec![Package::builder()
+            .name("a")
+            .version("1.0.0")
+            .license_identifier("MIT")
+            .build()]);
+
+        write_sidecars(&package_list, &config);
+        let json = std::fs::read_to_string(dir.join("target/licenses.json")).unwrap();
+        let markdown = std::fs::read_to_string(dir.join("target/LICENSES.md")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(json, package_list.canonical_json());
+        assert!(markdown.starts_with("# Third Party Licenses"));
+    }
+}
@@ -0,0 +1,79 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Stable JSON of a package list for snapshot tests.
+
+use std::io;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use super::fs::write;
+use crate::PackageList;
+
+/// Rebuilds all objects with sorted keys.
+///
+/// Independent of whether the `preserve_order` feature of `serde_json` is enabled by another crate.
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<(String, Value)> = object.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(array) => Value::Array(array.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+impl PackageList {
+    /// Serializes the package list as JSON meant for snapshot tests, e.g. to review attribution changes
+    /// after updating `Cargo.lock`.
+    ///
+    /// Keys are sorted, the output is indented with two spaces and ends with a newline.
+    /// The order of packages is kept, so sort them with [SortOrder](super::SortOrder) for stable snapshots.
+    /// The format only changes along with the fields of [Package](crate::Package).
+    pub fn canonical_json(&self) -> String {
+        let value = serde_json::to_value(self).expect("Package list is always serializable.");
+        let mut json = serde_json::to_string_pretty(&sort_keys(value))
+            .expect("JSON value is always serializable.");
+        json.push('\n');
+        json
+    }
+
+    /// Writes [PackageList::canonical_json] to a file.
+    pub fn write_canonical_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write(path, self.canonical_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Package;
+
+    #[test]
+    fn test_canonical_json() {
+        let package_list = PackageList(vec![Package::builder()
+            .name("a")
+            .version("1.0.0")
+            .license_identifier("MIT")
+            .license_text("MIT License")
+            .build()]);
+        let json = package_list.canonical_json();
+
+        assert!(json.ends_with("]\n"));
+        assert!(json.find("\"authors\"").unwrap() < json.find("\"name\"").unwrap());
+        assert!(json.find("\"copyleft\"").unwrap() < json.find("\"osi_approved\"").unwrap());
+        assert!(json.contains("\n    \"name\": \"a\""));
+        assert_eq!(json, package_list.canonical_json());
+    }
+}
@@ -0,0 +1,130 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Detection of package lists in `OUT_DIR` that were not regenerated after the dependencies changed.
+
+use std::collections::BTreeSet;
+use std::env::var_os;
+use std::path::PathBuf;
+
+use log::{debug, info, warn};
+use sha2::{Digest, Sha256};
+
+use super::config::Config;
+use super::fs::{read_to_string, write};
+use super::resolve_packages;
+use crate::PackageList;
+
+/// File in `OUT_DIR` holding the fingerprint of the dependencies the package list was generated from.
+const FINGERPRINT_FILE_NAME: &str = "LICENSE-3RD-PARTY.deps";
+
+fn fingerprint_path() -> Option<PathBuf> {
+    var_os("OUT_DIR").map(|dir| PathBuf::from(dir).join(FINGERPRINT_FILE_NAME))
+}
+
+/// SHA-256 of the sorted `name@version` set of the packages, as hex.
+fn fingerprint(package_list: &PackageList) -> String {
+    let packages: BTreeSet<String> = package_list
+        .iter()
+        .map(|package| format!("{}@{}\n", package.name, package.version))
+        .collect();
+    let mut hasher = Sha256::new();
+    for package in packages {
+        hasher.update(package);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Records the fingerprint of freshly resolved packages in `OUT_DIR`, for [warn_if_stale].
+///
+/// Does nothing outside of build scripts.
+pub(super) fn record_fingerprint(package_list: &PackageList) {
+    let Some(path) = fingerprint_path() else {
+        return;
+    };
+    if let Err(e) = write(&path, fingerprint(package_list)) {
+        warn!("Failed writing dependency fingerprint: {}", e);
+    }
+}
+
+/// Warns if the dependencies changed since the package list in `OUT_DIR` was generated.
+///
+/// Meant for build scripts that do not regenerate the package list on every run, e.g. as they only print
+/// `cargo::rerun-if-changed=build.rs` or keep a previously generated list to save time. Dependencies are resolved
+/// without fetching any license and compared to the `name@version` set recorded when the list was last generated.
+/// If they differ, a `cargo::warning` is printed, as the embedded attribution is stale.
+///
+/// Returns `true` if the list is stale. Returns `false` if no list was generated yet or outside of build scripts.
+///
+/// # Example
+/// In `build.rs`:
+/// ```no_run
+/// use std::path::Path;
+///
+/// use license_fetcher::build_script::{generate_package_list_with_licenses, warn_if_stale, ConfigBuilder};
+///
+/// fn main() {
+///     let out = Path::new(&std::env::var("OUT_DIR").unwrap()).join("LICENSE-3RD-PARTY.bincode");
+///     if out.exists() {
+///         warn_if_stale(&ConfigBuilder::from_build_env().build());
+///         return;
+///     }
+///     generate_package_list_with_licenses().write();
+/// }
+/// ```
+pub fn warn_if_stale(config: &Config) -> bool {
+    let Some(path) = fingerprint_path() else {
+        debug!("Not checking for stale package list, as OUT_DIR is not set.");
+        return false;
+    };
+    let recorded = match read_to_string(&path) {
+        Ok(recorded) => recorded,
+        Err(e) => {
+            info!("No dependency fingerprint to check against: {}", e);
+            return false;
+        }
+    };
+
+    let current = match resolve_packages(config, &mut vec![]) {
+        Ok(package_list) => fingerprint(&package_list),
+        Err(e) => {
+            warn!("Not checking for stale package list: {}", e);
+            return false;
+        }
+    };
+    if current == recorded.trim() {
+        return false;
+    }
+
+    let message = "Dependencies changed since the license data was generated, so the embedded attribution is \
+                   stale. Regenerate it, e.g. by printing `cargo::rerun-if-changed=Cargo.lock` in the build script.";
+    warn!("{}", message);
+    println!("cargo::warning={}", message);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Package;
+
+    #[test]
+    fn test_fingerprint() {
+        let package =
+            |name: &str, version: &str| Package::builder().name(name).version(version).build();
+        let a = PackageList(vec![package("log", "0.4.22"), package("cfg-if", "1.0.0")]);
+        let b = PackageList(vec![package("cfg-if", "1.0.0"), package("log", "0.4.22")]);
+        let c = PackageList(vec![package("cfg-if", "1.0.0"), package("log", "0.4.21")]);
+
+        assert_eq!(fingerprint(&a), fingerprint(&b));
+        assert_ne!(fingerprint(&a), fingerprint(&c));
+        assert_eq!(fingerprint(&a).len(), 64);
+    }
+}
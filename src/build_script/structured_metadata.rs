@@ -0,0 +1,253 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Parsing of `cargo metadata` with the [cargo_metadata] crate.
+//!
+//! Replaces the lightweight parser of the `metadata` module if the feature `cargo_metadata` is set.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::io::Read;
+
+use cargo_metadata::{DependencyKind, Metadata, Node, PackageId};
+use serde_json::from_reader;
+
+use super::config::DependencyDepth;
+use super::crates_io_url;
+use super::features::{activating_features, enabled_by_features, FeatureEdge};
+
+use crate::{ExtendedMetadata, Package, PackageKey, PackageList};
+
+/// Returns `true` for sources like `registry+https://github.com/rust-lang/crates.io-index`.
+fn is_registry_source(source: &str) -> bool {
+    source.starts_with("registry+") || source.starts_with("sparse+")
+}
+
+/// Ids of the packages reachable from `root` over normal dependencies within `max_depth` edges.
+fn walk_dependencies<'a>(
+    nodes: &HashMap<&'a PackageId, &'a Node>,
+    root: &PackageId,
+    max_depth: Option<usize>,
+) -> BTreeSet<&'a PackageId> {
+    let mut used_dependencies = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    if let Some(node) = nodes.get(root) {
+        used_dependencies.insert(&node.id);
+        queue.push_back((*node, 0));
+    }
+    // Breadth first, so every package is reached on its shortest path.
+    while let Some((node, depth)) = queue.pop_front() {
+        if max_depth.is_some_and(|max_depth| depth >= max_depth) {
+            continue;
+        }
+        for dep in node.deps.iter().filter(|dep| {
+            dep.dep_kinds
+                .iter()
+                .any(|info| info.kind == DependencyKind::Normal)
+        }) {
+            if let Some(dependency) = nodes.get(&dep.pkg) {
+                if used_dependencies.insert(&dependency.id) {
+                    queue.push_back((*dependency, depth + 1));
+                }
+            }
+        }
+    }
+    used_dependencies
+}
+
+/// Parses the output of `cargo metadata` while it is read, without holding the whole JSON in memory.
+pub(super) fn parse_metadata(reader: &mut dyn Read) -> serde_json::Result<Metadata> {
+    from_reader(reader)
+}
+
+/// Packages reachable from `root` over normal dependencies within the depth, including `root` itself.
+fn packages_reachable_from(
+    metadata: &Metadata,
+    root: &PackageId,
+    depth: DependencyDepth,
+) -> PackageList {
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .expect("cargo metadata is missing the dependency graph.");
+    let nodes: HashMap<&PackageId, &Node> = resolve.nodes.iter().map(|n| (&n.id, n)).collect();
+
+    let used_packages = walk_dependencies(&nodes, root, depth.limit());
+
+    // Names of packages requested from a registry. Packages with these names, but another source are replacements.
+    let registry_dependencies: BTreeSet<&String> = metadata
+        .packages
+        .iter()
+        .flat_map(|package| package.dependencies.iter())
+        .filter(|dep| dep.source.as_deref().is_some_and(is_registry_source))
+        .map(|dep| &dep.name)
+        .collect();
+
+    // Names under which packages are imported by the packages in use, like `foo` for `foo = { package = "bar" }`.
+    let mut renames: HashMap<&String, BTreeSet<String>> = HashMap::new();
+    for dep in metadata
+        .packages
+        .iter()
+        .filter(|package| used_packages.contains(&package.id))
+        .flat_map(|package| package.dependencies.iter())
+        .filter(|dep| dep.kind == DependencyKind::Normal)
+    {
+        if let Some(rename) = &dep.rename {
+            renames.entry(&dep.name).or_default().insert(rename.clone());
+        }
+    }
+
+    // Features that pulled in optional dependencies.
+    let packages_by_id: HashMap<&PackageId, &cargo_metadata::Package> = metadata
+        .packages
+        .iter()
+        .map(|package| (&package.id, package))
+        .collect();
+    let mut edges = vec![];
+    for node in resolve
+        .nodes
+        .iter()
+        .filter(|node| used_packages.contains(&node.id))
+    {
+        let Some(parent) = packages_by_id.get(&node.id) else {
+            continue;
+        };
+        for dep in node.deps.iter().filter(|dep| {
+            used_packages.contains(&dep.pkg)
+                && dep
+                    .dep_kinds
+                    .iter()
+                    .any(|info| info.kind == DependencyKind::Normal)
+        }) {
+            let Some(child) = packages_by_id.get(&dep.pkg) else {
+                continue;
+            };
+            let declarations: Vec<&cargo_metadata::Dependency> = parent
+                .dependencies
+                .iter()
+                .filter(|d| d.name == child.name.as_str() && d.kind == DependencyKind::Normal)
+                .collect();
+            let activated_by =
+                (!declarations.is_empty() && declarations.iter().all(|d| d.optional)).then(|| {
+                    declarations
+                        .iter()
+                        .flat_map(|d| {
+                            activating_features(
+                                &parent.name,
+                                &parent.features,
+                                &node.features,
+                                d.rename.as_deref().unwrap_or(&d.name),
+                            )
+                        })
+                        .collect()
+                });
+            edges.push(FeatureEdge {
+                parent: &node.id.repr,
+                child: &dep.pkg.repr,
+                activated_by,
+            });
+        }
+    }
+    let feature_conditions = enabled_by_features(&root.repr, &edges);
+
+    let package_list = metadata
+        .packages
+        .iter()
+        .filter(|package| used_packages.contains(&package.id))
+        .map(|package| {
+            let source = package.source.as_ref().map(|source| source.repr.clone());
+            let patched = !source.as_deref().is_some_and(is_registry_source)
+                && registry_dependencies.contains(&package.name);
+            let registry_url = crates_io_url(
+                &package.name,
+                &package.version.to_string(),
+                source.as_deref(),
+            );
+            Package {
+                authors: package.authors.clone(),
+                license_identifier: package.license.clone(),
+                name: package.name.clone(),
+                version: package.version.to_string(),
+                description: package.description.clone(),
+                homepage: package.homepage.clone(),
+                repository: package.repository.clone(),
+                manifest_path: Some(package.manifest_path.to_string()),
+                patched,
+                replacement_source: source.filter(|_| patched),
+                registry_url,
+                renamed_as: renames
+                    .get(&package.name)
+                    .map(|renames| renames.iter().cloned().collect())
+                    .unwrap_or_default(),
+                enabled_by_features: feature_conditions
+                    .get(&package.id.repr)
+                    .cloned()
+                    .unwrap_or_default(),
+                extended_metadata: Some(ExtendedMetadata {
+                    documentation: package.documentation.clone(),
+                    keywords: package.keywords.clone(),
+                    categories: package.categories.clone(),
+                    edition: Some(package.edition.to_string()),
+                    rust_version: package.rust_version.as_ref().map(ToString::to_string),
+                }),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    PackageList(package_list)
+}
+
+/// Packages reachable from the root over normal dependencies.
+pub(super) fn package_list_from_metadata(
+    metadata: &Metadata,
+    depth: DependencyDepth,
+) -> PackageList {
+    let root = metadata
+        .resolve
+        .as_ref()
+        .and_then(|resolve| resolve.root.as_ref())
+        .expect("cargo metadata is missing the root.");
+    packages_reachable_from(metadata, root, depth)
+}
+
+/// Packages of each binary target of the workspace members.
+///
+/// Every list is accompanied by the key of the package the binary belongs to.
+pub(super) fn binary_package_lists_from_metadata(
+    metadata: &Metadata,
+    depth: DependencyDepth,
+) -> BTreeMap<String, (PackageKey, PackageList)> {
+    let mut package_lists = BTreeMap::new();
+    for package in metadata.workspace_packages() {
+        for target in package.targets.iter().filter(|target| target.is_bin()) {
+            package_lists.insert(
+                target.name.clone(),
+                (
+                    PackageKey::new(package.name.clone(), package.version.to_string()),
+                    packages_reachable_from(metadata, &package.id, depth),
+                ),
+            );
+        }
+    }
+    package_lists
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::fs::read;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_metadata_json() {
+        let path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap())
+            .join("tests/metadata_test.json");
+        let metadata = parse_metadata(&mut read(path).unwrap().as_slice()).unwrap();
+        let package_list = package_list_from_metadata(&metadata, DependencyDepth::All);
+        assert!(!package_list.is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! One package list covering several target platforms.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::config::Config;
+use super::pipeline::Pipeline;
+use super::report::{BuildError, CargoAttempt, FetchReport};
+use super::resolve_packages;
+use crate::{Package, PackageKey, PackageList};
+
+/// Resolves the packages of every target and records in [Package::targets] where they are used.
+fn resolve_for_targets(
+    config: &Config,
+    targets: &[String],
+    attempts: &mut Vec<CargoAttempt>,
+) -> Result<PackageList, BuildError> {
+    if targets.is_empty() {
+        return resolve_packages(config, attempts);
+    }
+
+    let mut package_lists = vec![];
+    for target in targets {
+        let target_config = Config {
+            target: Some(target.clone()),
+            ..config.clone()
+        };
+        package_lists.push((target.as_str(), resolve_packages(&target_config, attempts)?));
+    }
+    Ok(merge_target_lists(package_lists))
+}
+
+/// Merges the package lists of several targets in the order packages first occur.
+///
+/// Packages used on all targets get no targets, all others the targets they are used on.
+fn merge_target_lists<'a>(
+    package_lists: impl IntoIterator<Item = (&'a str, PackageList)>,
+) -> PackageList {
+    let mut packages: Vec<(Package, BTreeSet<&str>)> = vec![];
+    let mut indices: BTreeMap<PackageKey, usize> = BTreeMap::new();
+    let mut all_targets = BTreeSet::new();
+    for (target, package_list) in package_lists {
+        all_targets.insert(target);
+        for package in package_list.0 {
+            let index = *indices.entry(package.key()).or_insert_with(|| {
+                packages.push((package, BTreeSet::new()));
+                packages.len() - 1
+            });
+            packages[index].1.insert(target);
+        }
+    }
+
+    PackageList(
+        packages
+            .into_iter()
+            .map(|(mut package, used_on)| {
+                if used_on.len() < all_targets.len() {
+                    package.targets = used_on.into_iter().map(str::to_owned).collect();
+                }
+                package
+            })
+            .collect(),
+    )
+}
+
+/// Generates a single package list for several target triples like `x86_64-pc-windows-msvc`.
+///
+/// Dependencies are resolved once per target with `--filter-platform`, but every license is fetched only once.
+/// Platform specific packages record their targets in [Package::targets]. At runtime
+/// [PackageList::for_current_target] selects the packages of the running program, so one encoded list can be
+/// shared by the builds of a CI matrix. Without targets it is the same as
+/// [generate_package_list_with_report](super::generate_package_list_with_report).
+///
+/// # Example
+/// In `build.rs`:
+/// ```no_run
+/// use license_fetcher::build_script::{generate_package_list_for_targets, ConfigBuilder};
+///
+/// fn main() {
+///     let (package_list, _report) = generate_package_list_for_targets(
+///         ConfigBuilder::from_build_env().build(),
+///         ["x86_64-pc-windows-msvc", "x86_64-unknown-linux-gnu", "aarch64-apple-darwin"],
+///     );
+///     package_list.write();
+/// }
+/// ```
+/// In `main.rs`:
+/// ```ignore
+/// use license_fetcher::get_package_list_macro;
+///
+/// fn main() {
+///     let package_list = get_package_list_macro!().unwrap().for_current_target();
+/// }
+/// ```
+pub fn generate_package_list_for_targets<I, S>(
+    config: Config,
+    targets: I,
+) -> (PackageList, FetchReport)
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let targets: Vec<String> = targets.into_iter().map(Into::into).collect();
+    Pipeline::new(config)
+        .resolver(move |config, attempts| resolve_for_targets(config, &targets, attempts))
+        .run()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_target_lists() {
+        let package = |name: &str| Package::builder().name(name).version("1.0.0").build();
+        let merged = merge_target_lists([
+            (
+                "x86_64-pc-windows-msvc",
+                PackageList(vec![package("app"), package("windows-sys"), package("log")]),
+            ),
+            (
+                "x86_64-unknown-linux-gnu",
+                PackageList(vec![package("app"), package("libc"), package("log")]),
+            ),
+            (
+                "aarch64-apple-darwin",
+                PackageList(vec![package("app"), package("libc"), package("log")]),
+            ),
+        ]);
+
+        let targets: Vec<(&str, Vec<&str>)> = merged
+            .iter()
+            .map(|p| {
+                (
+                    p.name.as_str(),
+                    p.targets.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                ("app", vec![]),
+                ("windows-sys", vec!["x86_64-pc-windows-msvc"]),
+                ("log", vec![]),
+                (
+                    "libc",
+                    vec!["aarch64-apple-darwin", "x86_64-unknown-linux-gnu"]
+                ),
+            ]
+        );
+
+        // A single target is all targets.
+        let merged =
+            merge_target_lists([("wasm32-unknown-unknown", PackageList(vec![package("app")]))]);
+        assert!(merged[0].targets.is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use core::fmt;
+
+use crate::PackageList;
+
+/// How many packages of a [PackageList] have complete attribution information.
+///
+/// See [PackageList::completeness].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct Completeness {
+    pub total: usize,
+    /// Packages with license identifier.
+    pub with_identifier: usize,
+    /// Packages with license text.
+    pub with_text: usize,
+    /// Packages with license identifier and license text.
+    pub complete: usize,
+}
+
+impl Completeness {
+    /// Percentage of complete packages, rounded down. An empty list is complete.
+    pub fn percent(&self) -> u8 {
+        if self.total == 0 {
+            return 100;
+        }
+        (self.complete * 100 / self.total) as u8
+    }
+}
+
+impl fmt::Display for Completeness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}% complete ({} of {} packages with license identifier and text)",
+            self.percent(),
+            self.complete,
+            self.total
+        )
+    }
+}
+
+impl PackageList {
+    /// Counts the packages with license identifier and license text.
+    ///
+    /// # Example
+    /// ```
+    /// use license_fetcher::{Package, PackageList};
+    ///
+    /// let package_list = PackageList(vec![
+    ///     Package::builder().name("a").license_identifier("MIT").license_text("MIT License").build(),
+    ///     Package::builder().name("b").license_identifier("MIT").build(),
+    /// ]);
+    ///
+    /// assert_eq!(package_list.completeness().percent(), 50);
+    /// ```
+    pub fn completeness(&self) -> Completeness {
+        let mut completeness = Completeness {
+            total: self.len(),
+            ..Default::default()
+        };
+        for package in self.iter() {
+            let identifier = package.license_identifier.is_some();
+            let text = package.license_text.is_some();
+            completeness.with_identifier += identifier as usize;
+            completeness.with_text += text as usize;
+            completeness.complete += (identifier && text) as usize;
+        }
+        completeness
+    }
+}
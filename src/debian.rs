@@ -0,0 +1,184 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::{Package, PackageList};
+
+const FORMAT_URL: &str = "https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/";
+
+/// Converts an SPDX expression into the DEP-5 license syntax.
+fn dep5_license(package: &Package) -> String {
+    package
+        .license_identifier
+        .as_deref()
+        .unwrap_or("unknown")
+        .replace(" OR ", " or ")
+        .replace(" AND ", " and ")
+        .replace(" WITH ", " with ")
+}
+
+/// Writes a multiline field value. Empty lines are written as ` .`.
+fn write_continuation(out: &mut String, text: &str) {
+    for line in text.trim_end().lines() {
+        if line.trim().is_empty() {
+            out.push_str(" .\n");
+        } else {
+            let _ = writeln!(out, " {}", line);
+        }
+    }
+}
+
+fn write_files_paragraph(out: &mut String, files: &[String], packages: &[&Package], license: &str) {
+    let _ = writeln!(out, "\nFiles: {}", files.join("\n "));
+
+    let mut authors: Vec<&str> = packages
+        .iter()
+        .flat_map(|p| p.authors.iter().map(String::as_str))
+        .collect();
+    authors.sort_unstable();
+    authors.dedup();
+    if authors.is_empty() {
+        authors.push("unknown");
+    }
+    let _ = writeln!(out, "Copyright: {}", authors.join("\n "));
+
+    let _ = writeln!(out, "License: {}", license);
+
+    let annotated: Vec<&&Package> = packages
+        .iter()
+        .filter(|p| !p.annotations.is_empty())
+        .collect();
+    if !annotated.is_empty() {
+        out.push_str("Comment:\n");
+        for package in annotated {
+            let text = alloc::format!("{}: {}", package.name, package.annotations.join(" "));
+            write_continuation(out, &text);
+        }
+    }
+}
+
+impl PackageList {
+    /// Renders the list as machine-readable `debian/copyright` file (DEP-5).
+    ///
+    /// The first package is treated as the root package and covers `Files: *`. Dependencies are grouped by license
+    /// with their files given as `vendor/<name>-<version>/*`. For every license a standalone license paragraph is
+    /// added with the first license text found for it.
+    pub fn to_debian_copyright(&self) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(out, "Format: {}", FORMAT_URL);
+
+        let Some((root, dependencies)) = self.split_first() else {
+            return out;
+        };
+
+        let _ = writeln!(out, "Upstream-Name: {}", root.name);
+        if let Some(source) = root.repository.as_ref().or(root.homepage.as_ref()) {
+            let _ = writeln!(out, "Source: {}", source);
+        }
+
+        write_files_paragraph(&mut out, &[String::from("*")], &[root], &dep5_license(root));
+
+        let mut by_license: BTreeMap<String, Vec<&Package>> = BTreeMap::new();
+        for package in dependencies {
+            by_license
+                .entry(dep5_license(package))
+                .or_default()
+                .push(package);
+        }
+
+        for (license, packages) in &by_license {
+            let files: Vec<String> = packages
+                .iter()
+                .map(|p| alloc::format!("vendor/{}-{}/*", p.name, p.version))
+                .collect();
+            write_files_paragraph(&mut out, &files, packages, license);
+        }
+
+        let mut license_texts: BTreeMap<String, &str> = BTreeMap::new();
+        for package in self.iter() {
+            if let Some(text) = &package.license_text {
+                license_texts.entry(dep5_license(package)).or_insert(text);
+            }
+        }
+
+        for (license, text) in license_texts {
+            let _ = writeln!(out, "\nLicense: {}", license);
+            write_continuation(&mut out, text);
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+
+    #[test]
+    fn test_debian_copyright() {
+        let package_list = PackageList(vec![
+            Package::builder()
+                .name("app")
+                .version("1.0.0")
+                .author("Me")
+                .license_identifier("MIT")
+                .license_text("MIT License\n\nCopyright Me")
+                .build(),
+            Package::builder()
+                .name("dep")
+                .version("0.1.0")
+                .author("You")
+                .license_identifier("MIT OR Apache-2.0")
+                .build(),
+        ]);
+
+        let copyright = package_list.to_debian_copyright();
+
+        assert_eq!(
+            copyright,
+            "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/\n\
+             Upstream-Name: app\n\
+             \n\
+             Files: *\n\
+             Copyright: Me\n\
+             License: MIT\n\
+             \n\
+             Files: vendor/dep-0.1.0/*\n\
+             Copyright: You\n\
+             License: MIT or Apache-2.0\n\
+             \n\
+             License: MIT\n \
+             MIT License\n \
+             .\n \
+             Copyright Me\n"
+        );
+    }
+
+    #[test]
+    fn test_debian_copyright_annotations() {
+        let package_list = PackageList(vec![
+            Package::builder().name("app").version("1.0.0").build(),
+            Package::builder()
+                .name("dep")
+                .version("0.1.0")
+                .license_identifier("MIT")
+                .annotation("Statically linked.")
+                .build(),
+        ]);
+
+        assert!(package_list.to_debian_copyright().contains(
+            "License: MIT\n\
+             Comment:\n \
+             dep: Statically linked.\n"
+        ));
+    }
+}
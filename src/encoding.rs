@@ -0,0 +1,357 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Framing of the embedded license data.
+//!
+//! Layout:
+//! ```text
+//! magic (8) | version (1) | flags (1) | payload length (4, LE) | payload | signature (64, if signed) | crc32 (4, LE)
+//! ```
+//! The CRC-32 covers everything in front of it.
+//!
+//! Flags:
+//! ```text
+//! bit 0: signed | bits 1-2: serialization format (0 = bincode, 1 = CBOR, 2 = postcard, 3 = rkyv) | bits 3-4: compression (0 = deflate, 1 = none)
+//! ```
+
+use crate::error::UnpackError;
+
+pub(crate) const MAGIC: &[u8; 8] = b"LICFETCH";
+pub(crate) const FORMAT_VERSION: u8 = 1;
+
+pub(crate) const FLAG_SIGNED: u8 = 0b0000_0001;
+const FLAG_FORMAT_MASK: u8 = 0b0000_0110;
+const FLAG_FORMAT_SHIFT: u8 = 1;
+const FLAG_COMPRESSION_MASK: u8 = 0b0001_1000;
+const FLAG_COMPRESSION_SHIFT: u8 = 3;
+
+pub(crate) const SIGNATURE_LENGTH: usize = 64;
+const HEADER_LENGTH: usize = MAGIC.len() + 1 + 1 + 4;
+const CHECKSUM_LENGTH: usize = 4;
+
+/// Alignment of rkyv archives, see [SerializationFormat::Rkyv].
+#[cfg_attr(not(feature = "rkyv"), allow(dead_code))]
+pub(crate) const ARCHIVE_ALIGNMENT: usize = 16;
+/// Zero bytes in front of an rkyv archive in the payload, so the archive is aligned if the frame is.
+#[cfg_attr(not(feature = "rkyv"), allow(dead_code))]
+pub(crate) const ARCHIVE_PADDING: usize = ARCHIVE_ALIGNMENT - HEADER_LENGTH % ARCHIVE_ALIGNMENT;
+
+/// Maximum length of the serialized license data after decompression.
+///
+/// Bounds the allocations when decoding corrupted data. Far beyond any real dependency tree.
+pub(crate) const MAX_DECODED_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Serialization format of the license data.
+///
+/// The format is recorded in the header of the encoded data, so decoding detects it automatically.
+///
+/// Non-exhaustive, as variants depend on features, which cargo unifies across all crates of a build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum SerializationFormat {
+    /// Compact format used by default.
+    #[default]
+    Bincode,
+    /// [CBOR](https://cbor.io) for parsing the data with non-Rust tooling. Needs the feature `cbor`.
+    ///
+    /// Structs are encoded as maps with their field names as keys. If the payload is compressed,
+    /// it is a raw deflate stream without zlib header.
+    #[cfg(feature = "cbor")]
+    Cbor,
+    /// [Postcard](https://postcard.jamesmunns.com), a compact format with a stable specification.
+    /// Needs the feature `postcard`.
+    #[cfg(feature = "postcard")]
+    Postcard,
+    /// [rkyv](https://rkyv.org) archive, which can be accessed in place without decoding with
+    /// [PackageList::archived](crate::PackageList::archived). Needs the feature `rkyv`.
+    ///
+    /// Never compressed, as compressed data cannot be accessed in place.
+    #[cfg(feature = "rkyv")]
+    Rkyv,
+}
+
+impl SerializationFormat {
+    #[cfg_attr(not(feature = "build"), allow(dead_code))]
+    pub(crate) fn flags(self) -> u8 {
+        let format = match self {
+            Self::Bincode => 0,
+            #[cfg(feature = "cbor")]
+            Self::Cbor => 1,
+            #[cfg(feature = "postcard")]
+            Self::Postcard => 2,
+            #[cfg(feature = "rkyv")]
+            Self::Rkyv => 3,
+        };
+        format << FLAG_FORMAT_SHIFT
+    }
+
+    fn from_flags(flags: u8) -> Result<Self, UnpackError> {
+        match (flags & FLAG_FORMAT_MASK) >> FLAG_FORMAT_SHIFT {
+            0 => Ok(Self::Bincode),
+            #[cfg(feature = "cbor")]
+            1 => Ok(Self::Cbor),
+            #[cfg(not(feature = "cbor"))]
+            1 => Err(UnpackError::UnsupportedFormat),
+            #[cfg(feature = "postcard")]
+            2 => Ok(Self::Postcard),
+            #[cfg(not(feature = "postcard"))]
+            2 => Err(UnpackError::UnsupportedFormat),
+            #[cfg(feature = "rkyv")]
+            3 => Ok(Self::Rkyv),
+            #[cfg(not(feature = "rkyv"))]
+            3 => Err(UnpackError::UnsupportedFormat),
+            _ => unreachable!("The format has two bits."),
+        }
+    }
+}
+
+/// Compression of the license data.
+///
+/// The compression is recorded in the header of the encoded data, so decoding detects it automatically.
+/// Defaults to [Compression::Deflate] if the feature `compress` is enabled.
+///
+/// Non-exhaustive, as [Compression::Deflate] depends on a feature, which cargo unifies across all crates of a build.
+/// [Compression::Best] is a choice of the encoder only. It is never recorded in the header, so decoded data always
+/// has a concrete compression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Compression {
+    /// Raw deflate stream. Needs the feature `compress` for encoding and decoding.
+    #[cfg(feature = "compress")]
+    #[cfg_attr(feature = "compress", default)]
+    Deflate,
+    /// Uncompressed, e.g. if the binary is compressed anyway.
+    #[cfg_attr(not(feature = "compress"), default)]
+    None,
+    /// Tries all available compressions and embeds the smallest output.
+    ///
+    /// Only valid for encoding. The chosen compression is recorded in the header instead.
+    Best,
+}
+
+impl Compression {
+    #[cfg_attr(not(feature = "build"), allow(dead_code))]
+    pub(crate) fn flags(self) -> u8 {
+        let compression = match self {
+            #[cfg(feature = "compress")]
+            Self::Deflate => 0,
+            Self::None => 1,
+            Self::Best => unreachable!("Best is resolved before framing."),
+        };
+        compression << FLAG_COMPRESSION_SHIFT
+    }
+
+    fn from_flags(flags: u8) -> Result<Self, UnpackError> {
+        match (flags & FLAG_COMPRESSION_MASK) >> FLAG_COMPRESSION_SHIFT {
+            #[cfg(feature = "compress")]
+            0 => Ok(Self::Deflate),
+            #[cfg(not(feature = "compress"))]
+            0 => Err(UnpackError::UnsupportedCompression),
+            1 => Ok(Self::None),
+            _ => Err(UnpackError::InvalidFormat),
+        }
+    }
+}
+
+/// Decoded frame borrowing from the embedded bytes.
+#[derive(Debug)]
+pub(crate) struct Frame<'a> {
+    pub payload: &'a [u8],
+    /// ed25519 signature over the payload. `None` if the data is not signed.
+    #[cfg_attr(not(feature = "signature"), allow(dead_code))]
+    pub signature: Option<&'a [u8]>,
+    pub format: SerializationFormat,
+    pub compression: Compression,
+}
+
+/// Updates a CRC-32 state. The state starts as `!0` and is inverted at the end.
+fn crc32_update(mut crc: u32, bytes: &[u8]) -> u32 {
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & 0u32.wrapping_sub(crc & 1));
+        }
+    }
+    crc
+}
+
+/// CRC-32 (IEEE 802.3).
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    !crc32_update(!0, bytes)
+}
+
+#[cfg_attr(not(feature = "build"), allow(dead_code))]
+fn header(flags: u8, payload_length: usize, signed: bool) -> [u8; HEADER_LENGTH] {
+    let mut header = [0; HEADER_LENGTH];
+    header[..MAGIC.len()].copy_from_slice(MAGIC);
+    header[MAGIC.len()] = FORMAT_VERSION;
+    header[MAGIC.len() + 1] = flags | if signed { FLAG_SIGNED } else { 0 };
+    header[MAGIC.len() + 2..].copy_from_slice(&(payload_length as u32).to_le_bytes());
+    header
+}
+
+/// Writes a framed payload without copying it. The signature has to be over the payload.
+#[cfg(feature = "std")]
+#[cfg_attr(not(feature = "build"), allow(dead_code))]
+pub(crate) fn write_frame(
+    writer: &mut impl std::io::Write,
+    flags: u8,
+    payload: &[u8],
+    signature: Option<&[u8]>,
+) -> std::io::Result<()> {
+    let header = header(flags, payload.len(), signature.is_some());
+    let mut crc = crc32_update(!0, &header);
+    writer.write_all(&header)?;
+
+    crc = crc32_update(crc, payload);
+    writer.write_all(payload)?;
+
+    if let Some(signature) = signature {
+        crc = crc32_update(crc, signature);
+        writer.write_all(signature)?;
+    }
+
+    writer.write_all(&(!crc).to_le_bytes())
+}
+
+/// Frames a payload. The signature has to be over the payload.
+#[cfg(test)]
+pub(crate) fn frame(flags: u8, payload: &[u8], signature: Option<&[u8]>) -> alloc::vec::Vec<u8> {
+    let signature_length = signature.map_or(0, |s| s.len());
+    let mut framed = alloc::vec::Vec::with_capacity(
+        HEADER_LENGTH + payload.len() + signature_length + CHECKSUM_LENGTH,
+    );
+
+    framed.extend_from_slice(&header(flags, payload.len(), signature.is_some()));
+    framed.extend_from_slice(payload);
+    if let Some(signature) = signature {
+        framed.extend_from_slice(signature);
+    }
+    let checksum = crc32(&framed);
+    framed.extend_from_slice(&checksum.to_le_bytes());
+
+    framed
+}
+
+/// Returns the length of the frame starting at `bytes` according to its header.
+///
+/// Returns `None` if `bytes` does not start with the magic or is too short to hold the frame.
+pub(crate) fn framed_length(bytes: &[u8]) -> Option<usize> {
+    if bytes.len() < HEADER_LENGTH + CHECKSUM_LENGTH || &bytes[..MAGIC.len()] != MAGIC {
+        return None;
+    }
+    let flags = bytes[MAGIC.len() + 1];
+    let payload_length = u32::from_le_bytes(bytes[MAGIC.len() + 2..HEADER_LENGTH].try_into().ok()?);
+    let signature_length = if flags & FLAG_SIGNED != 0 {
+        SIGNATURE_LENGTH
+    } else {
+        0
+    };
+    let length = HEADER_LENGTH
+        .checked_add(payload_length as usize)?
+        .checked_add(signature_length)?
+        .checked_add(CHECKSUM_LENGTH)?;
+    (length <= bytes.len()).then_some(length)
+}
+
+/// Checks magic, version, length and checksum of a frame.
+pub(crate) fn unframe(bytes: &[u8]) -> Result<Frame<'_>, UnpackError> {
+    if bytes.is_empty() {
+        return Err(UnpackError::Empty);
+    }
+    if bytes.len() < HEADER_LENGTH + CHECKSUM_LENGTH || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(UnpackError::InvalidFormat);
+    }
+
+    let version = bytes[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(UnpackError::UnsupportedVersion(version));
+    }
+
+    let flags = bytes[MAGIC.len() + 1];
+    let format = SerializationFormat::from_flags(flags)?;
+    let compression = Compression::from_flags(flags)?;
+    let payload_length = u32::from_le_bytes(
+        bytes[MAGIC.len() + 2..HEADER_LENGTH]
+            .try_into()
+            .expect("slice of length 4"),
+    ) as usize;
+    let signature_length = if flags & FLAG_SIGNED != 0 {
+        SIGNATURE_LENGTH
+    } else {
+        0
+    };
+
+    let checksum_start = HEADER_LENGTH
+        .checked_add(payload_length)
+        .and_then(|l| l.checked_add(signature_length))
+        .ok_or(UnpackError::InvalidFormat)?;
+    if bytes.len() != checksum_start + CHECKSUM_LENGTH {
+        return Err(UnpackError::InvalidFormat);
+    }
+
+    let checksum = u32::from_le_bytes(
+        bytes[checksum_start..]
+            .try_into()
+            .expect("slice of length 4"),
+    );
+    if checksum != crc32(&bytes[..checksum_start]) {
+        return Err(UnpackError::ChecksumMismatch);
+    }
+
+    let payload_end = HEADER_LENGTH + payload_length;
+    Ok(Frame {
+        payload: &bytes[HEADER_LENGTH..payload_end],
+        signature: (signature_length > 0).then(|| &bytes[payload_end..checksum_start]),
+        format,
+        compression,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_frame_round_trip_and_corruption() {
+        let framed = frame(Compression::None.flags(), b"payload", None);
+        let unframed = unframe(&framed).unwrap();
+        assert_eq!(unframed.payload, b"payload");
+
+        let mut corrupted = framed.clone();
+        corrupted[HEADER_LENGTH] ^= 0xFF;
+        assert!(matches!(
+            unframe(&corrupted),
+            Err(UnpackError::ChecksumMismatch)
+        ));
+
+        assert!(matches!(unframe(&[]), Err(UnpackError::Empty)));
+        assert!(matches!(
+            unframe(&framed[..framed.len() - 1]),
+            Err(UnpackError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn test_format_flags() {
+        let flags = |format: u8| (format << FLAG_FORMAT_SHIFT) | Compression::None.flags();
+
+        #[cfg(not(feature = "postcard"))]
+        assert!(matches!(
+            unframe(&frame(flags(2), b"", None)),
+            Err(UnpackError::UnsupportedFormat)
+        ));
+        #[cfg(feature = "postcard")]
+        assert_eq!(
+            unframe(&frame(flags(2), b"", None)).unwrap().format,
+            SerializationFormat::Postcard
+        );
+    }
+}
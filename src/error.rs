@@ -3,15 +3,103 @@
 //         (See accompanying file LICENSE or copy at
 //          https://www.boost.org/LICENSE_1_0.txt)
 
-use std::error::Error;
-use std::fmt;
+use core::error::Error;
+use core::fmt;
+
+/// Stable, machine readable code of an error. Printed as `LF####`.
+///
+/// Codes of unpacking errors start at `LF0001`, codes of build step errors at `LF1001`.
+/// Codes are never reused or changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// See [UnpackError::Empty].
+    Empty = 1,
+    /// See [UnpackError::InvalidFormat].
+    InvalidFormat = 2,
+    /// See [UnpackError::NotFound].
+    NotFound = 3,
+    /// See [UnpackError::UnsupportedVersion].
+    UnsupportedVersion = 4,
+    /// See [UnpackError::ChecksumMismatch].
+    ChecksumMismatch = 5,
+    /// See [UnpackError::UnsupportedCompression].
+    UnsupportedCompression = 6,
+    /// Decompressing the license data failed.
+    Decompress = 7,
+    /// Deserializing the license data failed.
+    Decode = 8,
+    /// See [UnpackError::SignatureMissing].
+    SignatureMissing = 9,
+    /// See [UnpackError::SignatureInvalid].
+    SignatureInvalid = 10,
+    /// See [UnpackError::UnsupportedFormat].
+    UnsupportedFormat = 11,
+    /// A license file or folder of a package could not be read.
+    Unreadable = 1001,
+    /// The license of a package is not allowed by policy.
+    PolicyViolation = 1002,
+    /// Running cargo failed.
+    CargoFailed = 1003,
+    /// The cargo home folder was not found.
+    CargoHomeMissing = 1004,
+    /// The registry source folder in the cargo home was not found.
+    RegistryMissing = 1005,
+    /// The output of `cargo metadata` could not be parsed.
+    MetadataParse = 1006,
+    /// Writing the license data failed.
+    WriteFailed = 1007,
+    /// Cargo needed network access, which is forbidden by the configured network policy.
+    NetworkForbidden = 1008,
+    /// The cargo executable could not be run.
+    CargoNotFound = 1009,
+    /// Cargo failed on a lock held by another cargo process, even after retrying.
+    CargoLockContention = 1010,
+    /// The package itself is missing in the package list.
+    RootMissing = 1011,
+    /// The env variable `OUT_DIR` is not set, as the build step does not run in a build script.
+    OutDirMissing = 1012,
+}
+
+impl ErrorCode {
+    /// Numeric value of the code.
+    pub fn code(self) -> u16 {
+        self as u16
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LF{:04}", self.code())
+    }
+}
 
 /// Error union representing errors that might occur during unpacking of license data.
 #[derive(Debug)]
 pub enum UnpackError {
+    /// The embedded data is empty, e.g. a dummy file was embedded instead of the build step output.
+    Empty,
+    /// The embedded data is not license data or is truncated.
+    InvalidFormat,
+    /// No license data was found in a binary.
+    NotFound,
+    /// The license data was written by an incompatible version of `license-fetcher`.
+    UnsupportedVersion(u8),
+    /// The checksum of the license data does not match. The data is corrupted or was tampered with.
+    ChecksumMismatch,
+    /// The license data is compressed, but the feature `compress` is disabled.
+    UnsupportedCompression,
+    /// The license data is serialized in a format whose feature, like `cbor`, is disabled. Also returned by
+    /// [PackageList::archived](crate::PackageList::archived) for license data that is not an rkyv archive.
+    UnsupportedFormat,
     #[cfg(feature = "compress")]
     DecompressError(miniz_oxide::inflate::DecompressError),
     DecodeError(bincode::error::DecodeError),
+    /// The license data is not signed, but a signature was required.
+    SignatureMissing,
+    /// The signature of the license data does not match the public key. The data was tampered with or signed with
+    /// another key.
+    SignatureInvalid,
 }
 
 #[cfg(feature = "compress")]
@@ -27,22 +115,75 @@ impl From<bincode::error::DecodeError> for UnpackError {
     }
 }
 
+impl UnpackError {
+    /// Returns the stable [ErrorCode] of this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Empty => ErrorCode::Empty,
+            Self::InvalidFormat => ErrorCode::InvalidFormat,
+            Self::NotFound => ErrorCode::NotFound,
+            Self::UnsupportedVersion(_) => ErrorCode::UnsupportedVersion,
+            Self::ChecksumMismatch => ErrorCode::ChecksumMismatch,
+            Self::UnsupportedCompression => ErrorCode::UnsupportedCompression,
+            Self::UnsupportedFormat => ErrorCode::UnsupportedFormat,
+            #[cfg(feature = "compress")]
+            Self::DecompressError(_) => ErrorCode::Decompress,
+            Self::DecodeError(_) => ErrorCode::Decode,
+            Self::SignatureMissing => ErrorCode::SignatureMissing,
+            Self::SignatureInvalid => ErrorCode::SignatureInvalid,
+        }
+    }
+}
+
 impl fmt::Display for UnpackError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.code())?;
         match self {
+            Self::Empty => writeln!(f, "License data is empty."),
+            Self::InvalidFormat => writeln!(f, "License data has an invalid format."),
+            Self::NotFound => writeln!(f, "No license data found."),
+            Self::UnsupportedVersion(v) => {
+                writeln!(f, "License data has unsupported format version {}.", v)
+            }
+            Self::ChecksumMismatch => writeln!(f, "License data checksum mismatch."),
+            Self::UnsupportedCompression => writeln!(
+                f,
+                "License data is compressed, but the feature `compress` is disabled."
+            ),
+            Self::UnsupportedFormat => {
+                writeln!(f, "License data is serialized in an unsupported format.")
+            }
             #[cfg(feature = "compress")]
             Self::DecompressError(e) => writeln!(f, "{}", e),
             Self::DecodeError(e) => writeln!(f, "{}", e),
+            Self::SignatureMissing => writeln!(f, "License data is not signed."),
+            Self::SignatureInvalid => writeln!(f, "License data has an invalid signature."),
         }
     }
 }
 
 impl Error for UnpackError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
-        Some(match self {
-            #[cfg(feature = "compress")]
-            Self::DecompressError(e) => e,
-            Self::DecodeError(e) => e,
-        })
+        match self {
+            #[cfg(all(feature = "compress", feature = "std"))]
+            Self::DecompressError(e) => Some(e),
+            #[cfg(feature = "std")]
+            Self::DecodeError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_error_code_format() {
+        assert_eq!(ErrorCode::ChecksumMismatch.to_string(), "LF0005");
+        assert_eq!(ErrorCode::MetadataParse.to_string(), "LF1006");
+        assert!(UnpackError::Empty.to_string().starts_with("LF0001: "));
     }
 }
@@ -0,0 +1,130 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use crate::encoding::{self, MAGIC};
+use crate::error::UnpackError;
+use crate::{decode_payload, BuildInfo, PackageList};
+
+/// Locates and decodes license data embedded in a compiled binary.
+///
+/// The binary is scanned for the magic bytes of the license data, so it works regardless of how the data was embedded
+/// ([get_package_list_macro](crate::get_package_list_macro) or [get_package_list_section_macro](crate::get_package_list_section_macro)).
+/// Candidates with an invalid checksum are skipped.
+///
+/// # Example
+/// ```no_run
+/// use license_fetcher::extract_package_list_from_binary;
+///
+/// let binary = std::fs::read("path/to/executable").unwrap();
+/// let (package_list, build_info) = extract_package_list_from_binary(&binary).unwrap();
+/// println!("{}", package_list);
+/// ```
+pub fn extract_package_list_from_binary(
+    binary: &[u8],
+) -> Result<(PackageList, Option<BuildInfo>), UnpackError> {
+    let mut decode_error = None;
+
+    let mut position = 0;
+    while let Some(offset) = binary[position..]
+        .windows(MAGIC.len())
+        .position(|window| window == MAGIC)
+    {
+        let start = position + offset;
+        position = start + 1;
+
+        let Some(length) = encoding::framed_length(&binary[start..]) else {
+            continue;
+        };
+        let Ok(frame) = encoding::unframe(&binary[start..start + length]) else {
+            continue;
+        };
+
+        match decode_payload(&frame) {
+            Ok(decoded) => return Ok(decoded),
+            Err(e) => decode_error = Some(e),
+        }
+    }
+
+    Err(decode_error.unwrap_or(UnpackError::NotFound))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec::Vec;
+
+    const FRAME: &[u8] = include_bytes!("../tests/LICENSE-3RD-PARTY.bincode");
+
+    /// Deterministic filler bytes standing in for the rest of a binary.
+    fn noise(len: usize, seed: u32) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (state >> 16) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_frame_between_noise() {
+        let binary = [&noise(4096, 1), FRAME, &noise(4096, 2)].concat();
+        let (package_list, build_info) = extract_package_list_from_binary(&binary).unwrap();
+        assert_eq!(package_list[0].name, "exported");
+        assert_eq!(build_info, None);
+
+        // The frame may also start or end the binary.
+        assert!(extract_package_list_from_binary(FRAME).is_ok());
+        assert!(extract_package_list_from_binary(&[&noise(7, 3), FRAME].concat()).is_ok());
+    }
+
+    #[test]
+    fn test_false_magic_before_frame() {
+        // A magic with a huge length, a frame with a broken checksum, a partial magic and a magic directly followed by
+        // the real frame.
+        let mut broken = FRAME.to_vec();
+        broken[20] ^= 0xFF;
+        let binary = [
+            &MAGIC[..],
+            &[1, 0, 0xFF, 0xFF, 0xFF, 0xFF],
+            &noise(64, 4),
+            &broken,
+            &MAGIC[..4],
+            MAGIC,
+            FRAME,
+        ]
+        .concat();
+        let (package_list, _) = extract_package_list_from_binary(&binary).unwrap();
+        assert_eq!(package_list[0].name, "exported");
+    }
+
+    #[test]
+    fn test_truncated_frame() {
+        for end in 0..FRAME.len() {
+            let binary = [&noise(256, 5), &FRAME[..end]].concat();
+            assert!(matches!(
+                extract_package_list_from_binary(&binary),
+                Err(UnpackError::NotFound)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_no_frame() {
+        assert!(matches!(
+            extract_package_list_from_binary(&noise(8192, 6)),
+            Err(UnpackError::NotFound)
+        ));
+        assert!(matches!(
+            extract_package_list_from_binary(&[]),
+            Err(UnpackError::NotFound)
+        ));
+        assert!(matches!(
+            extract_package_list_from_binary(MAGIC),
+            Err(UnpackError::NotFound)
+        ));
+    }
+}
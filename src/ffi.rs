@@ -0,0 +1,309 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! C interface to the decoded package list.
+//!
+//! The header `include/license_fetcher.h` is generated with `cbindgen --config cbindgen.toml --output include/license_fetcher.h`.
+//!
+//! All strings returned are NUL terminated and owned by the [LfPackageList]. They stay valid until
+//! [lf_package_list_free] is called.
+
+use std::ffi::{c_char, CString};
+use std::ptr;
+use std::slice;
+
+use crate::{get_package_list, Package};
+
+struct FfiPackage {
+    name: CString,
+    version: CString,
+    authors: Vec<CString>,
+    description: Option<CString>,
+    homepage: Option<CString>,
+    repository: Option<CString>,
+    license_identifier: Option<CString>,
+    license_text: Option<CString>,
+    annotations: Vec<CString>,
+}
+
+fn c_string(s: String) -> CString {
+    CString::new(s).unwrap_or_else(|e| {
+        let mut bytes = e.into_vec();
+        bytes.retain(|&b| b != 0);
+        CString::new(bytes).expect("NUL bytes were removed")
+    })
+}
+
+impl From<Package> for FfiPackage {
+    fn from(package: Package) -> Self {
+        Self {
+            name: c_string(package.name),
+            version: c_string(package.version),
+            authors: package.authors.into_iter().map(c_string).collect(),
+            description: package.description.map(c_string),
+            homepage: package.homepage.map(c_string),
+            repository: package.repository.map(c_string),
+            license_identifier: package.license_identifier.map(c_string),
+            license_text: package
+                .license_text
+                .as_deref()
+                .map(String::from)
+                .map(c_string),
+            annotations: package.annotations.into_iter().map(c_string).collect(),
+        }
+    }
+}
+
+/// Opaque handle to a decoded package list.
+pub struct LfPackageList {
+    packages: Vec<FfiPackage>,
+}
+
+impl LfPackageList {
+    fn package(&self, index: usize) -> Option<&FfiPackage> {
+        self.packages.get(index)
+    }
+}
+
+/// Field of a package that can be read with [lf_package_field].
+#[repr(C)]
+pub enum LfPackageField {
+    Name,
+    Version,
+    Description,
+    Homepage,
+    Repository,
+    LicenseIdentifier,
+    LicenseText,
+}
+
+/// Decodes embedded license data.
+///
+/// Returns `NULL` if the data is empty or corrupted. Free the list with [lf_package_list_free].
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn lf_package_list_decode(data: *const u8, len: usize) -> *mut LfPackageList {
+    if data.is_null() {
+        return ptr::null_mut();
+    }
+    let bytes = slice::from_raw_parts(data, len);
+    match get_package_list(bytes) {
+        Ok(package_list) => Box::into_raw(Box::new(LfPackageList {
+            packages: package_list.0.into_iter().map(FfiPackage::from).collect(),
+        })),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Frees a package list. Passing `NULL` is a no-op.
+///
+/// # Safety
+///
+/// `list` must be `NULL` or returned by [lf_package_list_decode] and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn lf_package_list_free(list: *mut LfPackageList) {
+    if !list.is_null() {
+        drop(Box::from_raw(list));
+    }
+}
+
+/// Returns the number of packages. Returns `0` for `NULL`.
+///
+/// # Safety
+///
+/// `list` must be `NULL` or a valid list.
+#[no_mangle]
+pub unsafe extern "C" fn lf_package_list_len(list: *const LfPackageList) -> usize {
+    list.as_ref().map_or(0, |list| list.packages.len())
+}
+
+/// Returns a field of the package at `index`.
+///
+/// Returns `NULL` if the index is out of bounds or the field is not set.
+///
+/// # Safety
+///
+/// `list` must be `NULL` or a valid list.
+#[no_mangle]
+pub unsafe extern "C" fn lf_package_field(
+    list: *const LfPackageList,
+    index: usize,
+    field: LfPackageField,
+) -> *const c_char {
+    let Some(package) = list.as_ref().and_then(|list| list.package(index)) else {
+        return ptr::null();
+    };
+    let value = match field {
+        LfPackageField::Name => Some(&package.name),
+        LfPackageField::Version => Some(&package.version),
+        LfPackageField::Description => package.description.as_ref(),
+        LfPackageField::Homepage => package.homepage.as_ref(),
+        LfPackageField::Repository => package.repository.as_ref(),
+        LfPackageField::LicenseIdentifier => package.license_identifier.as_ref(),
+        LfPackageField::LicenseText => package.license_text.as_ref(),
+    };
+    value.map_or(ptr::null(), |s| s.as_ptr())
+}
+
+/// Returns the number of authors of the package at `index`.
+///
+/// # Safety
+///
+/// `list` must be `NULL` or a valid list.
+#[no_mangle]
+pub unsafe extern "C" fn lf_package_authors_len(list: *const LfPackageList, index: usize) -> usize {
+    list.as_ref()
+        .and_then(|list| list.package(index))
+        .map_or(0, |package| package.authors.len())
+}
+
+/// Returns author `author_index` of the package at `index`, or `NULL` if out of bounds.
+///
+/// # Safety
+///
+/// `list` must be `NULL` or a valid list.
+#[no_mangle]
+pub unsafe extern "C" fn lf_package_author(
+    list: *const LfPackageList,
+    index: usize,
+    author_index: usize,
+) -> *const c_char {
+    list.as_ref()
+        .and_then(|list| list.package(index))
+        .and_then(|package| package.authors.get(author_index))
+        .map_or(ptr::null(), |s| s.as_ptr())
+}
+
+/// Returns the number of annotations of the package at `index`.
+///
+/// # Safety
+///
+/// `list` must be `NULL` or a valid list.
+#[no_mangle]
+pub unsafe extern "C" fn lf_package_annotations_len(
+    list: *const LfPackageList,
+    index: usize,
+) -> usize {
+    list.as_ref()
+        .and_then(|list| list.package(index))
+        .map_or(0, |package| package.annotations.len())
+}
+
+/// Returns annotation `annotation_index` of the package at `index`, or `NULL` if out of bounds.
+///
+/// # Safety
+///
+/// `list` must be `NULL` or a valid list.
+#[no_mangle]
+pub unsafe extern "C" fn lf_package_annotation(
+    list: *const LfPackageList,
+    index: usize,
+    annotation_index: usize,
+) -> *const c_char {
+    list.as_ref()
+        .and_then(|list| list.package(index))
+        .and_then(|package| package.annotations.get(annotation_index))
+        .map_or(ptr::null(), |s| s.as_ptr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads a string returned by the interface. It must not outlive its list.
+    #[cfg(feature = "build")]
+    unsafe fn read<'a>(s: *const c_char) -> Option<&'a str> {
+        (!s.is_null()).then(|| std::ffi::CStr::from_ptr(s).to_str().unwrap())
+    }
+
+    #[test]
+    fn test_c_string_strips_nul() {
+        assert_eq!(c_string("MIT".to_owned()).as_bytes(), b"MIT");
+        assert_eq!(c_string("M\0I\0T\0".to_owned()).as_bytes(), b"MIT");
+        assert_eq!(c_string("\0".to_owned()).as_bytes(), b"");
+    }
+
+    #[cfg(feature = "build")]
+    #[test]
+    fn test_decode() {
+        use crate::build_script::EncodeOptions;
+        use crate::PackageList;
+
+        let package_list = PackageList(vec![
+            Package::builder()
+                .name("log")
+                .version("0.4.22")
+                .author("Me")
+                .author("You")
+                .description("Logging")
+                .homepage("https://example.com")
+                .repository("https://example.com/log.git")
+                .license_identifier("MIT")
+                .license_text("MIT\0License")
+                .annotation("Vendored")
+                .build(),
+            Package::builder().name("bare").version("1.0.0").build(),
+        ]);
+        let encoded = package_list.encode(&EncodeOptions::default());
+
+        unsafe {
+            let list = lf_package_list_decode(encoded.as_ptr(), encoded.len());
+            assert!(!list.is_null());
+            assert_eq!(lf_package_list_len(list), 2);
+
+            let field = |index, field| read(lf_package_field(list, index, field));
+            assert_eq!(field(0, LfPackageField::Name), Some("log"));
+            assert_eq!(field(0, LfPackageField::Version), Some("0.4.22"));
+            assert_eq!(field(0, LfPackageField::Description), Some("Logging"));
+            assert_eq!(
+                field(0, LfPackageField::Homepage),
+                Some("https://example.com")
+            );
+            assert_eq!(
+                field(0, LfPackageField::Repository),
+                Some("https://example.com/log.git")
+            );
+            assert_eq!(field(0, LfPackageField::LicenseIdentifier), Some("MIT"));
+            assert_eq!(field(0, LfPackageField::LicenseText), Some("MITLicense"));
+            assert_eq!(field(1, LfPackageField::Name), Some("bare"));
+            assert_eq!(field(1, LfPackageField::Description), None);
+            assert_eq!(field(1, LfPackageField::LicenseText), None);
+            assert_eq!(field(2, LfPackageField::Name), None);
+
+            assert_eq!(lf_package_authors_len(list, 0), 2);
+            assert_eq!(read(lf_package_author(list, 0, 0)), Some("Me"));
+            assert_eq!(read(lf_package_author(list, 0, 1)), Some("You"));
+            assert_eq!(read(lf_package_author(list, 0, 2)), None);
+            assert_eq!(lf_package_authors_len(list, 1), 0);
+            assert_eq!(lf_package_authors_len(list, 2), 0);
+            assert_eq!(read(lf_package_author(list, 2, 0)), None);
+
+            assert_eq!(lf_package_annotations_len(list, 0), 1);
+            assert_eq!(read(lf_package_annotation(list, 0, 0)), Some("Vendored"));
+            assert_eq!(read(lf_package_annotation(list, 0, 1)), None);
+            assert_eq!(lf_package_annotations_len(list, 2), 0);
+            assert_eq!(read(lf_package_annotation(list, 2, 0)), None);
+
+            lf_package_list_free(list);
+
+            let mut corrupted = encoded.clone();
+            *corrupted.last_mut().unwrap() ^= 0xFF;
+            assert!(lf_package_list_decode(corrupted.as_ptr(), corrupted.len()).is_null());
+            assert!(lf_package_list_decode(encoded.as_ptr(), 0).is_null());
+            assert!(lf_package_list_decode(ptr::null(), 10).is_null());
+
+            assert_eq!(lf_package_list_len(ptr::null()), 0);
+            assert!(lf_package_field(ptr::null(), 0, LfPackageField::Name).is_null());
+            assert_eq!(lf_package_authors_len(ptr::null(), 0), 0);
+            assert!(lf_package_author(ptr::null(), 0, 0).is_null());
+            assert_eq!(lf_package_annotations_len(ptr::null(), 0), 0);
+            assert!(lf_package_annotation(ptr::null(), 0, 0).is_null());
+            lf_package_list_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use core::fmt;
+
+use crate::AssetKind;
+
+/// Labels of the rendered outputs, for showing attribution in the language of the user.
+///
+/// Defaults to English. Used by [PackageList::to_string_with_labels](crate::PackageList::to_string_with_labels),
+/// [PackageList::to_markdown_with_labels](crate::PackageList::to_markdown_with_labels) and the notice and terminal
+/// renderers. Labels of fields are given without trailing colon.
+///
+/// # Example
+/// ```
+/// use license_fetcher::{Labels, Package, PackageList};
+///
+/// let mut labels = Labels::default();
+/// labels.package = "Paket".to_owned();
+/// labels.authors = "Autoren".to_owned();
+///
+/// let package_list = PackageList(vec![Package::builder().name("log").version("0.4.22").author("Me").build()]);
+/// let text = package_list.to_string_with_labels(&labels);
+/// assert!(text.contains("Paket:       log 0.4.22\nAutoren:     - Me\n"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Labels {
+    pub package: String,
+    pub name: String,
+    pub version: String,
+    pub asset: String,
+    pub description: String,
+    pub authors: String,
+    pub homepage: String,
+    pub repository: String,
+    /// Label of the SPDX license expression.
+    pub license_identifier: String,
+    /// Label of the license chosen from alternatives like `MIT OR Apache-2.0`.
+    pub chosen_license: String,
+    pub license: String,
+    /// Shown instead of a license identifier, if the license text was taken from a `README`.
+    pub license_from_readme: String,
+    pub notes: String,
+    /// Title of documents like Markdown.
+    pub title: String,
+    /// Heading of the crates, if a document groups packages.
+    pub crates: String,
+    /// Prefix of the headings of asset groups.
+    pub assets: String,
+    pub fonts: String,
+    pub icons: String,
+    pub data: String,
+    pub models: String,
+    pub other_assets: String,
+}
+
+impl Default for Labels {
+    fn default() -> Self {
+        Self {
+            package: "Package".to_owned(),
+            name: "Name".to_owned(),
+            version: "Version".to_owned(),
+            asset: "Asset".to_owned(),
+            description: "Description".to_owned(),
+            authors: "Authors".to_owned(),
+            homepage: "Homepage".to_owned(),
+            repository: "Repository".to_owned(),
+            license_identifier: "SPDX Ident".to_owned(),
+            chosen_license: "Chosen".to_owned(),
+            license: "License".to_owned(),
+            license_from_readme: "Taken from README (low confidence)".to_owned(),
+            notes: "Notes".to_owned(),
+            title: "Third Party Licenses".to_owned(),
+            crates: "Crates".to_owned(),
+            assets: "Assets".to_owned(),
+            fonts: "Fonts".to_owned(),
+            icons: "Icons".to_owned(),
+            data: "Data".to_owned(),
+            models: "Models".to_owned(),
+            other_assets: "Other Assets".to_owned(),
+        }
+    }
+}
+
+impl Labels {
+    /// Heading of the group of an asset kind, like `Fonts`.
+    pub fn asset_heading(&self, kind: AssetKind) -> &str {
+        match kind {
+            AssetKind::Font => &self.fonts,
+            AssetKind::Icon => &self.icons,
+            AssetKind::Data => &self.data,
+            AssetKind::Model => &self.models,
+            AssetKind::Other => &self.other_assets,
+        }
+    }
+
+    /// Width of the label column of the plain text output, so all values start in the same column.
+    pub(crate) fn column_width(&self) -> usize {
+        [
+            &self.package,
+            &self.asset,
+            &self.description,
+            &self.authors,
+            &self.homepage,
+            &self.repository,
+            &self.license_identifier,
+            &self.chosen_license,
+            &self.license,
+            &self.notes,
+        ]
+        .iter()
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or_default()
+            + 2
+    }
+
+    /// Writes a label with colon, padded to [Labels::column_width].
+    pub(crate) fn write_field(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        label: &str,
+        width: usize,
+    ) -> fmt::Result {
+        write!(f, "{}:", label)?;
+        let padding = width.saturating_sub(label.chars().count() + 1);
+        write!(f, "{:padding$}", "", padding = padding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use crate::{Package, PackageList};
+
+    fn german() -> Labels {
+        Labels {
+            package: "Paket".to_owned(),
+            authors: "Autoren".to_owned(),
+            license: "Lizenz".to_owned(),
+            license_identifier: "SPDX-Bezeichner".to_owned(),
+            title: "Lizenzen Dritter".to_owned(),
+            crates: "Kisten".to_owned(),
+            fonts: "Schriftarten".to_owned(),
+            ..Labels::default()
+        }
+    }
+
+    fn package_list() -> PackageList {
+        PackageList(vec![
+            Package::builder()
+                .name("log")
+                .version("0.4.22")
+                .author("Me")
+                .license_identifier("MIT")
+                .license_text("MIT License")
+                .build(),
+            Package::builder()
+                .name("Noto Sans")
+                .version("2.0")
+                .asset(AssetKind::Font)
+                .license_identifier("OFL-1.1")
+                .build(),
+        ])
+    }
+
+    #[test]
+    fn test_column_width() {
+        assert_eq!(Labels::default().column_width(), "Description".len() + 2);
+        // Width counts characters, not bytes.
+        let labels = Labels {
+            notes: "Änderungsanmerkungen über alles".to_owned(),
+            ..Labels::default()
+        };
+        assert_eq!(
+            labels.column_width(),
+            "Änderungsanmerkungen über alles".chars().count() + 2
+        );
+    }
+
+    #[test]
+    fn test_asset_heading() {
+        let labels = german();
+        assert_eq!(labels.asset_heading(AssetKind::Font), "Schriftarten");
+        assert_eq!(labels.asset_heading(AssetKind::Other), "Other Assets");
+    }
+
+    #[test]
+    fn test_plain_text_labels() {
+        let text = package_list().to_string_with_labels(&german());
+        assert!(text.contains("Paket:           log 0.4.22\n"));
+        assert!(text.contains("Autoren:         - Me\n"));
+        assert!(text.contains("SPDX-Bezeichner: MIT\n"));
+        assert!(!text.contains("Package:"));
+        assert_eq!(
+            package_list().to_string_with_labels(&Labels::default()),
+            package_list().to_string()
+        );
+    }
+
+    #[test]
+    fn test_markdown_labels() {
+        let markdown = package_list().to_markdown_with_labels(&german());
+        assert!(markdown.starts_with("# Lizenzen Dritter\n\n| Name | Version | Lizenz |\n"));
+        assert!(markdown.contains("\n## Kisten\n"));
+        assert!(markdown.contains("\n## Schriftarten\n"));
+        assert!(markdown.contains("- Lizenz: `MIT`\n- Autoren: Me\n"));
+    }
+
+    #[cfg(feature = "term")]
+    #[test]
+    fn test_term_labels() {
+        let text = package_list().render_colored(80, false, &german());
+        assert!(text.contains("Lizenz: MIT\n"));
+        assert!(text.contains("Autoren: Me\n"));
+        assert!(text.contains("Schriftarten\n\n"));
+        assert!(!text.contains("License:"));
+    }
+}
@@ -54,19 +54,18 @@
 //! fn main() {
 //!     let mut packages = generate_package_list_with_licenses();
 //!
-//!     packages.push(Package {
-//!         name: "other dependency".to_owned(),
-//!         version: "0.1.0".to_owned(),
-//!         authors: vec!["Me".to_owned()],
-//!         description: Some("A dependency that is not a rust crate.".to_owned()),
-//!         homepage: None,
-//!         repository: None,
-//!         license_identifier: None,
-//!         license_text: Some(
-//!             read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/some_dependency/LICENSE"))
-//!             .expect("Failed reading license of other dependency")
-//!         )
-//!     });
+//!     packages.push(
+//!         Package::builder()
+//!             .name("other dependency")
+//!             .version("0.1.0")
+//!             .author("Me")
+//!             .description("A dependency that is not a rust crate.")
+//!             .license_text(
+//!                 read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/some_dependency/LICENSE"))
+//!                 .expect("Failed reading license of other dependency")
+//!             )
+//!             .build()
+//!     );
 //!
 //!     packages.write();
 //!
@@ -78,33 +77,168 @@
 //! ```
 //!
 //! ## Feature Flags
-//! | Feature    | Description                                                             |
-//! | ---------- | ----------------------------------------------------------------------- |
-//! | `compress` | *(default)* Enables compression.                                        |
-//! | `build`    | Used for build script component.                                        |
-//! | `frozen`   | Panics if `Cargo.lock` needs to be updated for `cargo metadata` to run. |
+//! | Feature             | Description                                                             |
+//! | ------------------- | ----------------------------------------------------------------------- |
+//! | `std`               | *(default)* Disable for `no_std` + `alloc` targets (decoding only).     |
+//! | `compress`          | *(default)* Enables compression.                                        |
+//! | `build`             | Used for build script component.                                        |
+//! | `frozen`            | Panics if `Cargo.lock` needs to be updated for `cargo metadata` to run. |
+//! | `signature`         | Enables signing the license data with an ed25519 key and verifying it.  |
+//! | `wasm`              | Exports the decoding to JS via `wasm-bindgen`.                          |
+//! | `ffi`               | Exports the decoding to C. See `include/license_fetcher.h`.             |
+//! | `clearlydefined`    | Queries ClearlyDefined for missing license identifiers.                 |
+//! | `cargo_metadata`    | Parses `cargo metadata` with the `cargo_metadata` crate.                |
+//! | `test-util`         | Synthetic package lists for unit tests, see [test_util].                |
+//! | `license-details`   | Full names and pages of SPDX licenses, see [LicenseDetails].            |
+//! | `semver`            | Version requirement queries, see [PackageList::find_matching].          |
+//! | `term`              | Colored terminal output, see [PackageList::print_colored].              |
+//! | `serde`             | `Serialize` and `Deserialize` for [PackageList] and its fields.         |
+//! | `cbor`              | Encoding and decoding of license data serialized as CBOR.               |
+//! | `postcard`          | Encoding and decoding of license data serialized as postcard.           |
+//! | `rkyv`              | Access to license data in place, see [PackageList::archived].           |
 //!
 
-use std::default::Default;
-use std::fmt;
-use std::ops::{Deref, DerefMut};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
 
 use bincode::{config, Decode, Encode};
 
 #[cfg(feature = "compress")]
-use miniz_oxide::inflate::decompress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
 
 pub mod error;
 use error::UnpackError;
 
+mod author;
+pub use author::AuthorInfo;
+
+#[cfg(feature = "rkyv")]
+mod archive;
+mod build_info;
+mod encoding;
+#[cfg(any(feature = "cbor", feature = "postcard"))]
+mod payload;
+pub use build_info::BuildInfo;
+pub use encoding::{Compression, SerializationFormat};
+
+mod extract;
+pub use extract::extract_package_list_from_binary;
+
+pub mod section;
+
+pub mod spdx;
+use spdx::LicenseInfo;
+
+mod verify;
+pub use verify::VerificationReport;
+
+mod license_file;
+pub use license_file::{LicenseFile, LicenseTranslation};
+
+mod completeness;
+pub use completeness::Completeness;
+
+mod merge;
+pub use merge::MergeConflict;
+
+mod package_key;
+pub use package_key::PackageKey;
+
+mod debian;
+mod markdown;
+mod pager;
+mod table;
+#[cfg(feature = "term")]
+mod term;
+
+mod search;
+pub use search::SearchMatch;
+
+#[cfg(feature = "license-details")]
+mod license_details;
+#[cfg(feature = "license-details")]
+pub use license_details::LicenseDetails;
+
+mod target;
+
+mod asset;
+pub use asset::AssetKind;
+
+mod labels;
+pub use labels::Labels;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
 #[cfg(feature = "build")]
 pub mod build_script;
 
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+/// Where the license text of a [Package] was taken from.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
+pub enum LicenseTextSource {
+    /// Dedicated license files like `LICENSE` or `COPYING`.
+    LicenseFiles,
+    /// `License` section of a `README` file. This is a low confidence heuristic.
+    Readme,
+    /// Standard texts of the licenses named by `SPDX-License-Identifier` tags in source files, taken from the SPDX
+    /// license list. The package ships no license file, so copyright notices are missing.
+    SpdxHeader,
+}
+
+/// Additional package metadata from `Cargo.toml`.
+///
+/// Only captured if enabled with `ConfigBuilder::extended_metadata` in the build step, as it increases the size
+/// of the embedded package list.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Hash, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
+#[non_exhaustive]
+pub struct ExtendedMetadata {
+    pub documentation: Option<String>,
+    pub keywords: Vec<String>,
+    pub categories: Vec<String>,
+    /// Rust edition like `2021`.
+    pub edition: Option<String>,
+    /// Minimum supported Rust version.
+    pub rust_version: Option<String>,
+}
+
 /// Information regarding a crate.
 ///
 /// This struct holds information like package name, authors and of course license text.
-#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
-#[cfg_attr(feature = "build", derive(serde::Serialize))]
+/// New fields may be added in the future, so use [Package::builder] to construct it.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Hash, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
+#[non_exhaustive]
 pub struct Package {
     pub name: String,
     pub version: String,
@@ -113,40 +247,305 @@ pub struct Package {
     pub homepage: Option<String>,
     pub repository: Option<String>,
     pub license_identifier: Option<String>,
-    pub license_text: Option<String>,
+    /// License text. Identical texts of different packages share their allocation after decoding.
+    pub license_text: Option<Arc<str>>,
+    pub license_text_source: Option<LicenseTextSource>,
+    /// Files the [Package::license_text] was assembled from.
+    pub license_files: Vec<LicenseFile>,
+    /// Classification of [Package::license_identifier]. Computed in the build step.
+    pub license_info: Option<LicenseInfo>,
+    /// License chosen from [Package::license_identifier] according to the configured license preference.
+    pub effective_license: Option<String>,
+    /// Path to the `Cargo.toml` of the package on the build machine.
+    ///
+    /// Only available in the build step. It is not embedded, as it would leak paths of the build machine.
+    pub manifest_path: Option<String>,
+    /// The package replaces a registry package via `[patch]` or `[replace]`.
+    ///
+    /// Its license is fetched from the replacement instead of the registry.
+    pub patched: bool,
+    /// Translations of license files found next to the original ones.
+    ///
+    /// If a package ships translations, only the English or original files make up the [Package::license_text].
+    pub license_translations: Vec<LicenseTranslation>,
+    /// Source of the replacement like `git+https://github.com/user/repo#<commit>`. `None` for path replacements.
+    pub replacement_source: Option<String>,
+    /// Documentation URL, keywords, categories, edition and minimum supported Rust version.
+    ///
+    /// `None` unless enabled in the build step. See [ExtendedMetadata].
+    pub extended_metadata: Option<ExtendedMetadata>,
+    /// Page of the package on crates.io, like `https://crates.io/crates/log/0.4.22`.
+    ///
+    /// Only set for packages from crates.io.
+    pub registry_url: Option<String>,
+    /// Names the package is imported under, like `foo` for `foo = { package = "bar" }` in `Cargo.toml`.
+    ///
+    /// Empty if the package is not renamed. [Package::name] always holds the real package name.
+    pub renamed_as: Vec<String>,
+    /// Features that pulled the package in as optional dependency, like `reqwest/native-tls`.
+    ///
+    /// The package is only included while one of these features is enabled. Empty if it is included regardless of
+    /// features. Dependencies of an optional package inherit its features. As `cargo metadata` unifies the features
+    /// of normal and build dependencies, features only enabled for build scripts may be listed too.
+    pub enabled_by_features: Vec<String>,
+    /// Target triples the package is used on, like `x86_64-pc-windows-msvc`.
+    ///
+    /// Empty if the package is used on all targets the list was generated for. See
+    /// [PackageList::for_current_target].
+    pub targets: Vec<String>,
+    /// Notes for legal review, like `statically linked` or `exception granted by vendor`.
+    ///
+    /// Set in the build step with `ConfigBuilder::annotate` or [PackageBuilder::annotation].
+    /// Rendered by all output formats.
+    pub annotations: Vec<String>,
+    /// Kind of asset like a font or icon set, which is not a crate. `None` for crates.
+    ///
+    /// Assets are added with [asset_package] or `ConfigBuilder::import_assets`. Text outputs list them apart from
+    /// the crates, grouped by kind.
+    pub asset: Option<AssetKind>,
+}
+
+/// Builder for [Package].
+///
+/// # Example
+/// ```
+/// use license_fetcher::Package;
+///
+/// let package = Package::builder()
+///     .name("other dependency")
+///     .version("0.1.0")
+///     .author("Me")
+///     .license_identifier("MIT")
+///     .build();
+///
+/// assert_eq!(package.name, "other dependency");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PackageBuilder {
+    package: Package,
+}
+
+impl PackageBuilder {
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.package.name = name.into();
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.package.version = version.into();
+        self
+    }
+
+    /// Appends an author.
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.package.authors.push(author.into());
+        self
+    }
+
+    /// Replaces all authors.
+    pub fn authors(mut self, authors: Vec<String>) -> Self {
+        self.package.authors = authors;
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.package.description = Some(description.into());
+        self
+    }
+
+    pub fn homepage(mut self, homepage: impl Into<String>) -> Self {
+        self.package.homepage = Some(homepage.into());
+        self
+    }
+
+    pub fn repository(mut self, repository: impl Into<String>) -> Self {
+        self.package.repository = Some(repository.into());
+        self
+    }
+
+    /// Sets the SPDX license expression.
+    pub fn license_identifier(mut self, license_identifier: impl Into<String>) -> Self {
+        self.package.license_identifier = Some(license_identifier.into());
+        self
+    }
+
+    /// Sets the license chosen out of the alternatives of the license identifier.
+    pub fn effective_license(mut self, effective_license: impl Into<String>) -> Self {
+        self.package.effective_license = Some(effective_license.into());
+        self
+    }
+
+    pub fn license_text(mut self, license_text: impl Into<Arc<str>>) -> Self {
+        self.package.license_text = Some(license_text.into());
+        self
+    }
+
+    /// Appends the content of a license file to the license text and records the file.
+    pub fn license_file(mut self, file_name: impl Into<String>, text: &str) -> Self {
+        let mut license_text =
+            String::from(self.package.license_text.as_deref().unwrap_or_default());
+        if !license_text.is_empty() {
+            license_text.push_str("\n\n");
+        }
+        let start = license_text.len();
+        license_text.push_str(text);
+        self.package
+            .license_files
+            .push(LicenseFile::new(file_name, start..license_text.len()));
+        self.package.license_text = Some(license_text.into());
+        self
+    }
+
+    /// Appends a translation of a license file.
+    pub fn license_translation(mut self, license_translation: LicenseTranslation) -> Self {
+        self.package.license_translations.push(license_translation);
+        self
+    }
+
+    pub fn license_text_source(mut self, license_text_source: LicenseTextSource) -> Self {
+        self.package.license_text_source = Some(license_text_source);
+        self
+    }
+
+    pub fn manifest_path(mut self, manifest_path: impl Into<String>) -> Self {
+        self.package.manifest_path = Some(manifest_path.into());
+        self
+    }
+
+    pub fn extended_metadata(mut self, extended_metadata: ExtendedMetadata) -> Self {
+        self.package.extended_metadata = Some(extended_metadata);
+        self
+    }
+
+    pub fn registry_url(mut self, registry_url: impl Into<String>) -> Self {
+        self.package.registry_url = Some(registry_url.into());
+        self
+    }
+
+    /// Appends a name the package is imported under. See [Package::renamed_as].
+    pub fn renamed_as(mut self, rename: impl Into<String>) -> Self {
+        self.package.renamed_as.push(rename.into());
+        self
+    }
+
+    /// Appends a feature that pulled the package in. See [Package::enabled_by_features].
+    pub fn enabled_by_feature(mut self, feature: impl Into<String>) -> Self {
+        self.package.enabled_by_features.push(feature.into());
+        self
+    }
+
+    /// Appends a note for legal review. See [Package::annotations].
+    pub fn annotation(mut self, annotation: impl Into<String>) -> Self {
+        self.package.annotations.push(annotation.into());
+        self
+    }
+
+    /// Marks the package as asset of a kind. See [Package::asset].
+    pub fn asset(mut self, kind: AssetKind) -> Self {
+        self.package.asset = Some(kind);
+        self
+    }
+
+    /// Appends a target triple the package is used on. See [Package::targets].
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.package.targets.push(target.into());
+        self
+    }
+
+    /// Marks the package as replacement of a registry package. See [Package::patched].
+    pub fn patched(mut self, replacement_source: Option<String>) -> Self {
+        self.package.patched = true;
+        self.package.replacement_source = replacement_source;
+        self
+    }
+
+    /// Builds the package and classifies its license identifier.
+    pub fn build(mut self) -> Package {
+        self.package.license_info = self
+            .package
+            .license_identifier
+            .as_deref()
+            .and_then(LicenseInfo::from_expression);
+        self.package
+    }
 }
 
 impl Package {
-    fn fmt_package(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    /// Returns a [PackageBuilder] for constructing a package manually.
+    pub fn builder() -> PackageBuilder {
+        PackageBuilder::default()
+    }
+
+    fn fmt_package(&self, f: &mut fmt::Formatter<'_>, labels: &Labels) -> fmt::Result {
+        self.fmt_package_with_text(f, self.license_text.as_deref(), labels)
+    }
+
+    /// Same as [Package::fmt_package], but with a replaced license text.
+    pub(crate) fn fmt_package_with_text(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        license_text: Option<&str>,
+        labels: &Labels,
+    ) -> fmt::Result {
         const SEPERATOR_WIDTH: usize = 80;
         let separator: String = "=".repeat(SEPERATOR_WIDTH);
         let separator_light: String = "-".repeat(SEPERATOR_WIDTH);
+        let width = labels.column_width();
+        let indent = " ".repeat(width);
 
-        writeln!(f, "Package:     {} {}", self.name, self.version)?;
+        labels.write_field(f, &labels.package, width)?;
+        write!(f, "{} {}", self.name, self.version)?;
+        if !self.renamed_as.is_empty() {
+            write!(f, " (as {})", self.renamed_as.join(", "))?;
+        }
+        writeln!(f)?;
+        if let Some(asset) = &self.asset {
+            labels.write_field(f, &labels.asset, width)?;
+            writeln!(f, "{}", asset)?;
+        }
         if let Some(description) = &self.description {
-            writeln!(f, "Description: {}", description)?;
+            labels.write_field(f, &labels.description, width)?;
+            writeln!(f, "{}", description)?;
         }
-        if !self.authors.is_empty() {
-            writeln!(
-                f,
-                "Authors:     - {}",
-                self.authors.get(0).unwrap_or(&"".to_owned())
-            )?;
-            for author in self.authors.iter().skip(1) {
-                writeln!(f, "             - {}", author)?;
+        if let Some((first, rest)) = self.authors.split_first() {
+            labels.write_field(f, &labels.authors, width)?;
+            writeln!(f, "- {}", first)?;
+            for author in rest {
+                writeln!(f, "{}- {}", indent, author)?;
             }
         }
         if let Some(homepage) = &self.homepage {
-            writeln!(f, "Homepage:    {}", homepage)?;
+            labels.write_field(f, &labels.homepage, width)?;
+            writeln!(f, "{}", homepage)?;
         }
         if let Some(repository) = &self.repository {
-            writeln!(f, "Repository:  {}", repository)?;
+            labels.write_field(f, &labels.repository, width)?;
+            writeln!(f, "{}", repository)?;
         }
         if let Some(license_identifier) = &self.license_identifier {
-            writeln!(f, "SPDX Ident:  {}", license_identifier)?;
+            labels.write_field(f, &labels.license_identifier, width)?;
+            writeln!(f, "{}", license_identifier)?;
+        }
+        if let Some(effective_license) = &self.effective_license {
+            if Some(effective_license) != self.license_identifier.as_ref() {
+                labels.write_field(f, &labels.chosen_license, width)?;
+                writeln!(f, "{}", effective_license)?;
+            }
+        }
+
+        if self.license_text_source == Some(LicenseTextSource::Readme) {
+            labels.write_field(f, &labels.license, width)?;
+            writeln!(f, "{}", labels.license_from_readme)?;
+        }
+        if let Some((first, rest)) = self.annotations.split_first() {
+            labels.write_field(f, &labels.notes, width)?;
+            writeln!(f, "- {}", first)?;
+            for annotation in rest {
+                writeln!(f, "{}- {}", indent, annotation)?;
+            }
         }
 
-        if let Some(license_text) = &self.license_text {
+        if let Some(license_text) = license_text {
             writeln!(f, "\n{}\n{}", separator_light, license_text)?;
         }
 
@@ -163,13 +562,18 @@ impl fmt::Display for Package {
 
         writeln!(f, "{}\n", separator)?;
 
-        self.fmt_package(f)
+        self.fmt_package(f, &Labels::default())
     }
 }
 
 /// Holds information of all crates and licenses used for release build.
 #[derive(Encode, Decode, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "build", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
 pub struct PackageList(pub Vec<Package>);
 
 impl Deref for PackageList {
@@ -186,24 +590,72 @@ impl DerefMut for PackageList {
     }
 }
 
-impl fmt::Display for PackageList {
+/// [PackageList] rendered like its `Display`, but with custom [Labels].
+struct LabeledPackageList<'a>(&'a PackageList, &'a Labels);
+
+impl fmt::Display for LabeledPackageList<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let LabeledPackageList(package_list, labels) = self;
         const SEPERATOR_WIDTH: usize = 80;
         let separator: String = "=".repeat(SEPERATOR_WIDTH);
 
         writeln!(f, "{}\n", separator)?;
 
-        for package in self.iter() {
-            package.fmt_package(f)?;
+        for (kind, packages) in package_list.asset_groups() {
+            if let Some(kind) = kind {
+                writeln!(
+                    f,
+                    "{}: {}\n\n{}\n",
+                    labels.assets,
+                    labels.asset_heading(kind),
+                    separator
+                )?;
+            }
+            for package in packages {
+                package.fmt_package(f, labels)?;
+            }
         }
 
         Ok(())
     }
 }
 
+impl fmt::Display for PackageList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        LabeledPackageList(self, &Labels::default()).fmt(f)
+    }
+}
+
+impl PackageList {
+    /// Same as [Display](core::fmt::Display), but with custom [Labels], e.g. in the language of the user.
+    pub fn to_string_with_labels(&self, labels: &Labels) -> String {
+        use alloc::string::ToString;
+
+        LabeledPackageList(self, labels).to_string()
+    }
+
+    /// Lets packages with identical license texts share a single allocation.
+    ///
+    /// Called when decoding, as many packages ship the same license texts.
+    pub fn share_license_texts(&mut self) {
+        let mut texts: BTreeSet<Arc<str>> = BTreeSet::new();
+        for package in self.iter_mut() {
+            let Some(text) = &mut package.license_text else {
+                continue;
+            };
+            match texts.get(&**text) {
+                Some(shared) => *text = Arc::clone(shared),
+                None => {
+                    texts.insert(Arc::clone(text));
+                }
+            }
+        }
+    }
+}
+
 /// Decopresses and deserializes the crate and license information.
 ///
-/// Thise function decompresses the input, if `compress` feature was not disabled and
+/// Thise function verifies the checksum of the input, decompresses it, if it was compressed in the build step and
 /// then deserializes the input. The input should be the embeded license information from
 /// the build step.
 ///
@@ -220,15 +672,152 @@ impl fmt::Display for PackageList {
 /// }
 /// ```
 pub fn get_package_list(bytes: &[u8]) -> Result<PackageList, UnpackError> {
+    Ok(get_package_list_with_build_info(bytes)?.0)
+}
+
+/// Same as [get_package_list], but additionally returns the [BuildInfo] if it was written in the build step.
+pub fn get_package_list_with_build_info(
+    bytes: &[u8],
+) -> Result<(PackageList, Option<BuildInfo>), UnpackError> {
+    let frame = encoding::unframe(bytes)?;
+    decode_payload(&frame)
+}
+
+/// Decodes license data exported by [export_package_list_symbol_macro] from another library.
+///
+/// For programs loading a `cdylib` at runtime and looking up its `LICENSE_FETCHER_DATA` and
+/// `LICENSE_FETCHER_DATA_LEN` symbols. Files of such libraries can also be read with
+/// [extract_package_list_from_binary].
+///
+/// # Safety
+/// `data` must point to `len` readable bytes that stay valid during the call.
+pub unsafe fn get_package_list_from_raw_parts(
+    data: *const u8,
+    len: usize,
+) -> Result<PackageList, UnpackError> {
+    get_package_list(core::slice::from_raw_parts(data, len))
+}
+
+/// Same as [get_package_list], but returns an empty list if no license data was embedded.
+///
+/// Meant for builds that write an empty dummy file instead of fetching licenses, e.g. with `LICENSE_FETCHER_SKIP`.
+/// The returned `bool` is `false` if the data was not embedded, so a program can tell the user that attribution
+/// is missing instead of listing no packages. Any other [UnpackError] is returned as is.
+pub fn get_package_list_or_empty(bytes: &[u8]) -> Result<(PackageList, bool), UnpackError> {
+    match get_package_list(bytes) {
+        Ok(package_list) => Ok((package_list, true)),
+        Err(UnpackError::Empty) => Ok((PackageList(Vec::new()), false)),
+        Err(e) => Err(e),
+    }
+}
+
+impl PackageList {
+    /// Decodes license data encoded in the build step, same as [get_package_list].
+    ///
+    /// Has no side effects, so `PackageList::from_encoded(&package_list.encode(&options))` returning `package_list`
+    /// can serve as round trip property in tests. Malformed or truncated input returns an [UnpackError].
+    /// Allocations are bounded by the length of the input and a fixed limit on the decompressed size, so corrupted
+    /// data does not exhaust memory.
+    ///
+    /// # Example
+    /// ```
+    /// use license_fetcher::PackageList;
+    ///
+    /// assert!(PackageList::from_encoded(b"LICFETCH garbage").is_err());
+    /// ```
+    pub fn from_encoded(bytes: &[u8]) -> Result<Self, UnpackError> {
+        get_package_list(bytes)
+    }
+
+    /// Decodes license data encoded in the build step, same as [get_package_list_or_empty].
+    ///
+    /// # Example
+    /// ```
+    /// use license_fetcher::PackageList;
+    ///
+    /// let (package_list, embedded) = PackageList::from_encoded_or_empty(b"").unwrap();
+    /// assert!(package_list.is_empty() && !embedded);
+    /// ```
+    pub fn from_encoded_or_empty(bytes: &[u8]) -> Result<(Self, bool), UnpackError> {
+        get_package_list_or_empty(bytes)
+    }
+
+    /// Same as [get_package_list], but fails unless the data was signed with the secret key belonging to
+    /// `public_key`.
+    ///
+    /// Returns [UnpackError::SignatureMissing] for unsigned data and [UnpackError::SignatureInvalid] if the
+    /// signature does not match, so programs can refuse to show attribution data that was tampered with.
+    /// The data is signed in the build step with `EncodeOptions::signing_key`. Needs the feature `signature`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use license_fetcher::PackageList;
+    ///
+    /// // Public key of the secret key used in the build step.
+    /// const PUBLIC_KEY: [u8; 32] = [0; 32];
+    ///
+    /// fn main() {
+    ///     let package_list = PackageList::from_encoded_verified(
+    ///         std::include_bytes!(std::concat!(env!("OUT_DIR"), "/LICENSE-3RD-PARTY.bincode")),
+    ///         &PUBLIC_KEY,
+    ///     )
+    ///     .unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "signature")]
+    pub fn from_encoded_verified(bytes: &[u8], public_key: &[u8; 32]) -> Result<Self, UnpackError> {
+        use ed25519_dalek::{Signature, VerifyingKey};
+
+        let frame = encoding::unframe(bytes)?;
+        let signature = frame.signature.ok_or(UnpackError::SignatureMissing)?;
+        let signature =
+            Signature::from_slice(signature).map_err(|_| UnpackError::SignatureInvalid)?;
+        VerifyingKey::from_bytes(public_key)
+            .and_then(|key| key.verify_strict(frame.payload, &signature))
+            .map_err(|_| UnpackError::SignatureInvalid)?;
+
+        Ok(decode_payload(&frame)?.0)
+    }
+}
+
+fn decode_payload(
+    frame: &encoding::Frame,
+) -> Result<(PackageList, Option<BuildInfo>), UnpackError> {
     #[cfg(feature = "compress")]
-    let uncompressed_bytes = decompress_to_vec(bytes).expect("Failed decompressing license data.");
-    #[cfg(not(feature = "compress"))]
-    let uncompressed_bytes = bytes;
+    let decompressed;
+    let uncompressed_bytes: &[u8] = match frame.compression {
+        #[cfg(feature = "compress")]
+        Compression::Deflate => {
+            decompressed =
+                decompress_to_vec_with_limit(frame.payload, encoding::MAX_DECODED_LENGTH)?;
+            &decompressed
+        }
+        Compression::None if frame.payload.len() > encoding::MAX_DECODED_LENGTH => {
+            return Err(UnpackError::InvalidFormat)
+        }
+        Compression::None => frame.payload,
+        Compression::Best => unreachable!("Best is resolved when encoding."),
+    };
 
-    let (package_list, _) =
-        bincode::decode_from_slice(&uncompressed_bytes[..], config::standard())?;
+    let (mut package_list, build_info): (PackageList, Option<BuildInfo>) = match frame.format {
+        SerializationFormat::Bincode => {
+            // The limit keeps corrupted lengths from preallocating huge vectors.
+            let ((build_info, package_list), _) = bincode::decode_from_slice(
+                uncompressed_bytes,
+                config::standard().with_limit::<{ encoding::MAX_DECODED_LENGTH }>(),
+            )?;
+            (package_list, build_info)
+        }
+        #[cfg(feature = "cbor")]
+        SerializationFormat::Cbor => payload::decode_cbor(uncompressed_bytes)?,
+        #[cfg(feature = "postcard")]
+        SerializationFormat::Postcard => payload::decode_postcard(uncompressed_bytes)?,
+        #[cfg(feature = "rkyv")]
+        SerializationFormat::Rkyv => archive::decode(uncompressed_bytes)?,
+    };
+    package_list.share_license_texts();
 
-    Ok(package_list)
+    Ok((package_list, build_info))
 }
 
 /// Calls [get_package_list] with parameters expected from a call from `main.rs`.
@@ -243,9 +832,171 @@ pub fn get_package_list(bytes: &[u8]) -> Result<PackageList, UnpackError> {
 #[macro_export]
 macro_rules! get_package_list_macro {
     () => {
-        license_fetcher::get_package_list(std::include_bytes!(std::concat!(
+        license_fetcher::get_package_list(core::include_bytes!(core::concat!(
             env!("OUT_DIR"),
             "/LICENSE-3RD-PARTY.bincode"
         )))
     };
 }
+
+/// Calls [get_package_list] with license data included from a custom path.
+///
+/// For build scripts that write the license data somewhere else than `OUT_DIR`, e.g. with
+/// `PackageList::encode_to_writer` into a shared asset folder. Like with [core::include_bytes], relative paths are
+/// resolved relative to the file the macro is called in, so `concat!` and `env!` are usually needed.
+///
+/// # Example
+/// ```ignore
+/// use license_fetcher::get_package_list_from_path_macro;
+/// fn main() {
+///     let package_list = get_package_list_from_path_macro!(concat!(
+///         env!("CARGO_MANIFEST_DIR"),
+///         "/assets/LICENSE-3RD-PARTY.bincode"
+///     ))
+///     .unwrap();
+/// }
+/// ```
+#[macro_export]
+macro_rules! get_package_list_from_path_macro {
+    ($path:expr) => {
+        license_fetcher::get_package_list(core::include_bytes!($path))
+    };
+}
+
+/// Calls [get_package_list_or_empty] with parameters expected from a call from `main.rs`.
+///
+/// # Example
+/// ```no_run
+/// use license_fetcher::get_package_list_or_empty_macro;
+/// fn main() {
+///     let (package_list, embedded) = get_package_list_or_empty_macro!().unwrap();
+///     if !embedded {
+///         eprintln!("License information was not embedded into this build.");
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! get_package_list_or_empty_macro {
+    () => {
+        license_fetcher::get_package_list_or_empty(core::include_bytes!(core::concat!(
+            env!("OUT_DIR"),
+            "/LICENSE-3RD-PARTY.bincode"
+        )))
+    };
+}
+
+/// Same as [get_package_list_macro], but additionally places the license data in a dedicated linker section.
+///
+/// The section is named after the constants in [section], so external tools can extract the license data
+/// from a shipped binary without running it. The data is marked as used and is kept by the linker.
+///
+/// # Example
+/// ```no_run
+/// use license_fetcher::get_package_list_section_macro;
+/// fn main() {
+///     let package_list = get_package_list_section_macro!();
+/// }
+/// ```
+#[macro_export]
+macro_rules! get_package_list_section_macro {
+    () => {{
+        #[cfg_attr(
+            any(target_os = "macos", target_os = "ios"),
+            link_section = "__DATA,__lic_fetcher"
+        )]
+        #[cfg_attr(target_os = "windows", link_section = ".lcfetch")]
+        #[cfg_attr(
+            not(any(target_os = "macos", target_os = "ios", target_os = "windows")),
+            link_section = ".license_fetcher"
+        )]
+        #[used]
+        static LICENSE_FETCHER_DATA: [u8; core::include_bytes!(core::concat!(
+            env!("OUT_DIR"),
+            "/LICENSE-3RD-PARTY.bincode"
+        ))
+        .len()] =
+            *core::include_bytes!(core::concat!(env!("OUT_DIR"), "/LICENSE-3RD-PARTY.bincode"));
+
+        license_fetcher::get_package_list(&LICENSE_FETCHER_DATA)
+    }};
+}
+
+/// Exports the license data as C symbols, for `cdylib` and `staticlib` crates shipping attribution in the artifact.
+///
+/// Call it once at module level. It defines the exported statics `LICENSE_FETCHER_DATA`, holding the encoded
+/// license data, and `LICENSE_FETCHER_DATA_LEN`, holding its length in bytes. Like with
+/// [get_package_list_from_path_macro], a custom path to the license data can be passed.
+///
+/// Users of the library can decode the data with [get_package_list_from_raw_parts], with
+/// `lf_package_list_decode` of the feature `ffi`, or from the library file with
+/// [extract_package_list_from_binary].
+///
+/// # Example
+/// In `src/lib.rs` of a crate with `crate-type = ["cdylib"]`:
+/// ```ignore
+/// license_fetcher::export_package_list_symbol_macro!();
+/// ```
+///
+/// Declarations in C:
+/// ```c
+/// extern const uint8_t LICENSE_FETCHER_DATA[];
+/// extern const size_t LICENSE_FETCHER_DATA_LEN;
+/// ```
+#[macro_export]
+macro_rules! export_package_list_symbol_macro {
+    () => {
+        $crate::export_package_list_symbol_macro!(core::concat!(
+            env!("OUT_DIR"),
+            "/LICENSE-3RD-PARTY.bincode"
+        ));
+    };
+    ($path:expr) => {
+        #[no_mangle]
+        #[used]
+        pub static LICENSE_FETCHER_DATA: [u8; core::include_bytes!($path).len()] =
+            *core::include_bytes!($path);
+
+        #[no_mangle]
+        #[used]
+        pub static LICENSE_FETCHER_DATA_LEN: usize = core::include_bytes!($path).len();
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    export_package_list_symbol_macro!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/LICENSE-3RD-PARTY.bincode"
+    ));
+
+    #[test]
+    fn test_export_package_list_symbol() {
+        assert_eq!(LICENSE_FETCHER_DATA_LEN, LICENSE_FETCHER_DATA.len());
+
+        let package_list = unsafe {
+            get_package_list_from_raw_parts(LICENSE_FETCHER_DATA.as_ptr(), LICENSE_FETCHER_DATA_LEN)
+        }
+        .unwrap();
+        assert_eq!(package_list.len(), 1);
+        assert_eq!(package_list[0].name, "exported");
+        assert_eq!(package_list[0].license_text.as_deref(), Some("MIT License"));
+
+        let truncated = unsafe {
+            get_package_list_from_raw_parts(
+                LICENSE_FETCHER_DATA.as_ptr(),
+                LICENSE_FETCHER_DATA_LEN - 1,
+            )
+        };
+        assert!(truncated.is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_extract_exported_symbol() {
+        let binary = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let (package_list, _) = extract_package_list_from_binary(&binary).unwrap();
+        assert_eq!(package_list[0].name, "exported");
+    }
+}
@@ -0,0 +1,225 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::spdx::{lookup, Copyleft, Expression};
+use crate::Package;
+
+/// `(identifier, full name)` of the licenses known to [LicenseInfo](crate::spdx::LicenseInfo).
+const LICENSE_NAMES: &[(&str, &str)] = &[
+    ("0BSD", "BSD Zero Clause License"),
+    ("AGPL-3.0", "GNU Affero General Public License v3.0"),
+    (
+        "AGPL-3.0-only",
+        "GNU Affero General Public License v3.0 only",
+    ),
+    (
+        "AGPL-3.0-or-later",
+        "GNU Affero General Public License v3.0 or later",
+    ),
+    ("Apache-2.0", "Apache License 2.0"),
+    ("Artistic-2.0", "Artistic License 2.0"),
+    ("BlueOak-1.0.0", "Blue Oak Model License 1.0.0"),
+    ("BSD-1-Clause", "BSD 1-Clause License"),
+    ("BSD-2-Clause", "BSD 2-Clause \"Simplified\" License"),
+    (
+        "BSD-3-Clause",
+        "BSD 3-Clause \"New\" or \"Revised\" License",
+    ),
+    ("BSL-1.0", "Boost Software License 1.0"),
+    ("BUSL-1.1", "Business Source License 1.1"),
+    (
+        "CC-BY-4.0",
+        "Creative Commons Attribution 4.0 International",
+    ),
+    (
+        "CC-BY-SA-4.0",
+        "Creative Commons Attribution Share Alike 4.0 International",
+    ),
+    ("CC0-1.0", "Creative Commons Zero v1.0 Universal"),
+    (
+        "CDDL-1.0",
+        "Common Development and Distribution License 1.0",
+    ),
+    ("EPL-1.0", "Eclipse Public License 1.0"),
+    ("EPL-2.0", "Eclipse Public License 2.0"),
+    ("EUPL-1.2", "European Union Public License 1.2"),
+    ("GPL-2.0", "GNU General Public License v2.0 only"),
+    ("GPL-2.0-only", "GNU General Public License v2.0 only"),
+    (
+        "GPL-2.0-or-later",
+        "GNU General Public License v2.0 or later",
+    ),
+    ("GPL-3.0", "GNU General Public License v3.0 only"),
+    ("GPL-3.0-only", "GNU General Public License v3.0 only"),
+    (
+        "GPL-3.0-or-later",
+        "GNU General Public License v3.0 or later",
+    ),
+    ("ISC", "ISC License"),
+    ("LGPL-2.1", "GNU Lesser General Public License v2.1 only"),
+    (
+        "LGPL-2.1-only",
+        "GNU Lesser General Public License v2.1 only",
+    ),
+    (
+        "LGPL-2.1-or-later",
+        "GNU Lesser General Public License v2.1 or later",
+    ),
+    ("LGPL-3.0", "GNU Lesser General Public License v3.0 only"),
+    (
+        "LGPL-3.0-only",
+        "GNU Lesser General Public License v3.0 only",
+    ),
+    (
+        "LGPL-3.0-or-later",
+        "GNU Lesser General Public License v3.0 or later",
+    ),
+    ("MIT", "MIT License"),
+    ("MIT-0", "MIT No Attribution"),
+    ("MPL-1.1", "Mozilla Public License 1.1"),
+    ("MPL-2.0", "Mozilla Public License 2.0"),
+    ("NCSA", "University of Illinois/NCSA Open Source License"),
+    ("OFL-1.1", "SIL Open Font License 1.1"),
+    ("OpenSSL", "OpenSSL License"),
+    ("Python-2.0", "Python License 2.0"),
+    ("Unicode-3.0", "Unicode License v3"),
+    (
+        "Unicode-DFS-2016",
+        "Unicode License Agreement - Data Files and Software (2016)",
+    ),
+    ("Unlicense", "The Unlicense"),
+    ("WTFPL", "Do What The F*ck You Want To Public License"),
+    ("X11", "X11 License"),
+    ("Zlib", "zlib License"),
+];
+
+/// Full name, classification and canonical page of a single SPDX license, for display in about dialogs.
+///
+/// Requires the feature `license-details`.
+///
+/// # Example
+/// ```
+/// use license_fetcher::LicenseDetails;
+///
+/// let mit = LicenseDetails::from_id("MIT").unwrap();
+/// assert_eq!(mit.to_string(), "MIT License (OSI approved, FSF libre)");
+/// assert_eq!(mit.url(), "https://spdx.org/licenses/MIT.html");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct LicenseDetails {
+    /// SPDX identifier like `Apache-2.0`.
+    pub id: &'static str,
+    /// Full name like `Apache License 2.0`.
+    pub name: &'static str,
+    pub osi_approved: bool,
+    pub fsf_libre: bool,
+    pub copyleft: Copyleft,
+}
+
+impl LicenseDetails {
+    /// Looks up a license by SPDX identifier, ignoring case and a trailing `+`.
+    ///
+    /// Returns `None` for licenses missing in the table of this crate.
+    pub fn from_id(id: &str) -> Option<Self> {
+        let info = lookup(id)?;
+        let id = id.strip_suffix('+').unwrap_or(id);
+        let &(id, name) = LICENSE_NAMES
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(id))?;
+        Some(Self {
+            id,
+            name,
+            osi_approved: info.osi_approved,
+            fsf_libre: info.fsf_libre,
+            copyleft: info.copyleft,
+        })
+    }
+
+    /// Canonical page of the license on `spdx.org`, like `https://spdx.org/licenses/MIT.html`.
+    pub fn url(&self) -> String {
+        format!("https://spdx.org/licenses/{}.html", self.id)
+    }
+}
+
+impl fmt::Display for LicenseDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        match (self.osi_approved, self.fsf_libre) {
+            (true, true) => write!(f, " (OSI approved, FSF libre)"),
+            (true, false) => write!(f, " (OSI approved)"),
+            (false, true) => write!(f, " (FSF libre)"),
+            (false, false) => Ok(()),
+        }
+    }
+}
+
+impl Package {
+    /// Details of the licenses of the package, from [Package::effective_license] if chosen,
+    /// else from [Package::license_identifier].
+    ///
+    /// Licenses are listed in order of appearance in the expression. Unknown licenses are left out.
+    /// Requires the feature `license-details`.
+    pub fn license_details(&self) -> Vec<LicenseDetails> {
+        self.effective_license
+            .as_deref()
+            .or(self.license_identifier.as_deref())
+            .and_then(Expression::parse)
+            .map(|expression| {
+                let mut details: Vec<LicenseDetails> = vec![];
+                for found in expression
+                    .license_ids()
+                    .into_iter()
+                    .filter_map(LicenseDetails::from_id)
+                {
+                    if !details.iter().any(|d| d.id == found.id) {
+                        details.push(found);
+                    }
+                }
+                details
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_license_names_cover_license_table() {
+        for (id, _) in LICENSE_NAMES {
+            assert!(lookup(id).is_some(), "{} is not classified", id);
+        }
+    }
+
+    #[test]
+    fn test_license_details() {
+        let package = Package::builder()
+            .license_identifier("(MIT OR Apache-2.0) AND Unknown-1.0 AND mit")
+            .build();
+        let details = package.license_details();
+        assert_eq!(
+            details.iter().map(|d| d.id).collect::<Vec<_>>(),
+            ["MIT", "Apache-2.0"]
+        );
+        assert_eq!(
+            LicenseDetails::from_id("busl-1.1").unwrap().to_string(),
+            "Business Source License 1.1"
+        );
+        assert_eq!(
+            LicenseDetails::from_id("GPL-2.0+").unwrap().url(),
+            "https://spdx.org/licenses/GPL-2.0.html"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::string::String;
+use core::ops::Range;
+
+use bincode::{Decode, Encode};
+
+use crate::Package;
+
+/// A file the [Package::license_text] was assembled from.
+///
+/// The content is not stored twice. Instead the file references its part of the license text.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Hash, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
+#[non_exhaustive]
+pub struct LicenseFile {
+    /// Original file name, like `LICENSE-MIT` or `NOTICE`.
+    pub file_name: String,
+    /// Byte range of the file content inside of [Package::license_text].
+    pub range: Range<usize>,
+}
+
+impl LicenseFile {
+    pub fn new(file_name: impl Into<String>, range: Range<usize>) -> Self {
+        Self {
+            file_name: file_name.into(),
+            range,
+        }
+    }
+
+    /// Returns `true` for `NOTICE` files, which licenses like `Apache-2.0` require to be redistributed.
+    pub fn is_notice(&self) -> bool {
+        self.file_name.to_ascii_uppercase().contains("NOTICE")
+    }
+}
+
+/// A translation of a license file, kept apart from [Package::license_text].
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Hash, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
+#[non_exhaustive]
+pub struct LicenseTranslation {
+    /// Original file name, like `LICENSE.ja.md`.
+    pub file_name: String,
+    /// ISO 639-1 code like `ja`, or `und` if only the script was recognized.
+    pub language: String,
+    pub text: String,
+}
+
+impl LicenseTranslation {
+    pub fn new(
+        file_name: impl Into<String>,
+        language: impl Into<String>,
+        text: impl Into<String>,
+    ) -> Self {
+        Self {
+            file_name: file_name.into(),
+            language: language.into(),
+            text: text.into(),
+        }
+    }
+}
+
+impl Package {
+    /// Iterates over the [LicenseFile]s of this package together with their content.
+    ///
+    /// Files with a range outside of the license text are skipped.
+    pub fn license_file_texts(&self) -> impl Iterator<Item = (&LicenseFile, &str)> {
+        let text = self.license_text.as_deref().unwrap_or_default();
+        self.license_files
+            .iter()
+            .filter_map(move |file| Some((file, text.get(file.range.clone())?)))
+    }
+}
@@ -0,0 +1,148 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::{Labels, Package, PackageList};
+
+/// Escapes a value for a table cell.
+fn table_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+/// Fence of backticks longer than any run of backticks in the text.
+fn code_fence(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn write_package(out: &mut String, package: &Package, labels: &Labels) {
+    let _ = writeln!(out, "\n### {} {}\n", package.name, package.version);
+    if let Some(license_identifier) = &package.license_identifier {
+        let _ = writeln!(out, "- {}: `{}`", labels.license, license_identifier);
+    }
+    if !package.authors.is_empty() {
+        let _ = writeln!(out, "- {}: {}", labels.authors, package.authors.join(", "));
+    }
+    if let Some(homepage) = &package.homepage {
+        let _ = writeln!(out, "- {}: <{}>", labels.homepage, homepage);
+    }
+    if let Some(repository) = &package.repository {
+        let _ = writeln!(out, "- {}: <{}>", labels.repository, repository);
+    }
+    if !package.annotations.is_empty() {
+        let _ = writeln!(
+            out,
+            "- {}: {}",
+            labels.notes,
+            package.annotations.join("; ")
+        );
+    }
+    if let Some(license_text) = &package.license_text {
+        let fence = code_fence(license_text);
+        let _ = writeln!(
+            out,
+            "\n{}text\n{}\n{}",
+            fence,
+            license_text.trim_end(),
+            fence
+        );
+    }
+}
+
+impl PackageList {
+    /// Renders the list as Markdown document, e.g. for publishing next to release artifacts.
+    ///
+    /// An overview table is followed by a section per package with its license text in a code block.
+    /// Crates come first, followed by the assets grouped by kind.
+    ///
+    /// # Example
+    /// ```
+    /// use license_fetcher::{Package, PackageList};
+    ///
+    /// let package_list = PackageList(vec![Package::builder()
+    ///     .name("log")
+    ///     .version("0.4.22")
+    ///     .license_identifier("MIT OR Apache-2.0")
+    ///     .build()]);
+    ///
+    /// assert!(package_list
+    ///     .to_markdown()
+    ///     .contains("| log | 0.4.22 | MIT OR Apache-2.0 |"));
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        self.to_markdown_with_labels(&Labels::default())
+    }
+
+    /// Same as [PackageList::to_markdown], but with custom [Labels], e.g. in the language of the user.
+    pub fn to_markdown_with_labels(&self, labels: &Labels) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}\n", labels.title);
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} |\n| --- | --- | --- |",
+            table_cell(&labels.name),
+            table_cell(&labels.version),
+            table_cell(&labels.license)
+        );
+
+        let groups = self.asset_groups();
+        for package in groups.iter().flat_map(|(_, group)| group) {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} |",
+                table_cell(&package.name),
+                table_cell(&package.version),
+                table_cell(package.license_identifier.as_deref().unwrap_or_default())
+            );
+        }
+
+        for (kind, packages) in groups {
+            let heading = kind.map_or(labels.crates.as_str(), |kind| labels.asset_heading(kind));
+            let _ = writeln!(out, "\n## {}", heading);
+            for package in packages {
+                write_package(&mut out, package, labels);
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+
+    use crate::AssetKind;
+
+    #[test]
+    fn test_markdown() {
+        let package_list = PackageList(vec![
+            Package::builder()
+                .name("a")
+                .version("1.0.0")
+                .license_identifier("MIT")
+                .license_text("Use ``` freely.")
+                .build(),
+            Package::builder()
+                .name("Fira Sans")
+                .license_identifier("OFL-1.1")
+                .asset(AssetKind::Font)
+                .build(),
+        ]);
+        let markdown = package_list.to_markdown();
+
+        assert!(markdown.contains("| a | 1.0.0 | MIT |\n| Fira Sans |  | OFL-1.1 |\n"));
+        assert!(markdown.contains("\n````text\nUse ``` freely.\n````\n"));
+        assert!(markdown.find("## Crates").unwrap() < markdown.find("## Fonts").unwrap());
+    }
+}
@@ -0,0 +1,142 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{PackageKey, PackageList};
+
+/// Package that occurs in several merged lists with differing license texts.
+///
+/// See [PackageList::merge_all].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MergeConflict {
+    pub key: PackageKey,
+    /// Index of the list whose entry was kept.
+    pub kept: usize,
+    /// Index of the list whose entry was dropped.
+    pub dropped: usize,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "License texts of {} differ between list {} and {}. Keeping the one of list {}.",
+            self.key, self.kept, self.dropped, self.kept
+        )
+    }
+}
+
+impl PackageList {
+    /// Merges several package lists into their union, deduplicated by name and version.
+    ///
+    /// Meant for a launcher binary that ships other binaries and has to embed their licenses as well.
+    /// Packages keep the order in which they first occur. If the same package occurs with a different
+    /// license text, the first one is kept and the difference is reported as [MergeConflict].
+    ///
+    /// # Example
+    /// ```
+    /// use license_fetcher::{Package, PackageList};
+    ///
+    /// let package = |text: &str| Package::builder().name("a").version("1.0.0").license_text(text).build();
+    /// let (merged, conflicts) = PackageList::merge_all([
+    ///     PackageList(vec![package("MIT License")]),
+    ///     PackageList(vec![package("Apache License")]),
+    /// ]);
+    ///
+    /// assert_eq!(merged.len(), 1);
+    /// assert_eq!(conflicts.len(), 1);
+    /// ```
+    pub fn merge_all(
+        package_lists: impl IntoIterator<Item = PackageList>,
+    ) -> (PackageList, Vec<MergeConflict>) {
+        let mut merged = PackageList(Vec::new());
+        let mut origins: BTreeMap<PackageKey, (usize, usize)> = BTreeMap::new();
+        let mut conflicts = Vec::new();
+
+        for (list_index, package_list) in package_lists.into_iter().enumerate() {
+            for package in package_list.0 {
+                let key = package.key();
+                match origins.get(&key) {
+                    Some(&(index, kept)) => {
+                        if merged[index].license_text != package.license_text {
+                            conflicts.push(MergeConflict {
+                                key,
+                                kept,
+                                dropped: list_index,
+                            });
+                        }
+                    }
+                    None => {
+                        origins.insert(key, (merged.len(), list_index));
+                        merged.push(package);
+                    }
+                }
+            }
+        }
+        merged.share_license_texts();
+
+        (merged, conflicts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use crate::Package;
+
+    fn package(name: &str, version: &str, text: &str) -> Package {
+        Package::builder()
+            .name(name)
+            .version(version)
+            .license_text(text)
+            .build()
+    }
+
+    #[test]
+    fn test_merge_all() {
+        let (merged, conflicts) = PackageList::merge_all([
+            PackageList(vec![
+                package("a", "1.0.0", "MIT"),
+                package("b", "1.0.0", "MIT"),
+            ]),
+            PackageList(vec![
+                package("c", "1.0.0", "MIT"),
+                package("a", "1.0.0", "MIT"),
+            ]),
+            PackageList(vec![
+                package("a", "2.0.0", "MIT"),
+                package("b", "1.0.0", "Apache"),
+            ]),
+        ]);
+
+        let keys: Vec<_> = merged.iter().map(|p| p.key().to_string()).collect();
+        assert_eq!(keys, ["a 1.0.0", "b 1.0.0", "c 1.0.0", "a 2.0.0"]);
+        assert_eq!(merged[1].license_text.as_deref(), Some("MIT"));
+        assert_eq!(
+            conflicts,
+            [MergeConflict {
+                key: PackageKey::new("b", "1.0.0"),
+                kept: 0,
+                dropped: 2,
+            }]
+        );
+        assert_eq!(
+            conflicts[0].to_string(),
+            "License texts of b 1.0.0 differ between list 0 and 2. Keeping the one of list 0."
+        );
+
+        let (merged, conflicts) = PackageList::merge_all([]);
+        assert!(merged.is_empty());
+        assert!(conflicts.is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::string::String;
+#[cfg(feature = "semver")]
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{Package, PackageList};
+
+/// Name and version identifying a [Package]. Usable as key of maps.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Default)]
+pub struct PackageKey {
+    pub name: String,
+    pub version: String,
+}
+
+impl PackageKey {
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+        }
+    }
+}
+
+impl fmt::Display for PackageKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.version)
+    }
+}
+
+impl From<&Package> for PackageKey {
+    fn from(package: &Package) -> Self {
+        Self::new(package.name.clone(), package.version.clone())
+    }
+}
+
+impl Package {
+    /// Returns the [PackageKey] of this package.
+    pub fn key(&self) -> PackageKey {
+        self.into()
+    }
+}
+
+impl PackageList {
+    /// Finds a package by name and version without allocating a [PackageKey].
+    pub fn get_package(&self, name: &str, version: &str) -> Option<&Package> {
+        self.iter()
+            .find(|package| package.name == name && package.version == version)
+    }
+
+    /// Finds all versions of a package matching a version requirement like `>=1.2, <2` or `^0.4`.
+    ///
+    /// Requirements follow the syntax of `Cargo.toml`, so pre-releases only match requirements naming a pre-release
+    /// of the same version. Packages whose version is not valid semver are skipped. Fails if the requirement can
+    /// not be parsed. Needs the feature `semver`.
+    ///
+    /// # Example
+    /// ```
+    /// use license_fetcher::{Package, PackageList};
+    ///
+    /// let package_list = PackageList(vec![
+    ///     Package::builder().name("log").version("0.4.22").build(),
+    ///     Package::builder().name("log").version("1.2.0").build(),
+    /// ]);
+    ///
+    /// let matches = package_list.find_matching("log", ">=1.2, <2").unwrap();
+    /// assert_eq!(matches.len(), 1);
+    /// assert_eq!(matches[0].version, "1.2.0");
+    /// ```
+    #[cfg(feature = "semver")]
+    pub fn find_matching(
+        &self,
+        name: &str,
+        requirement: &str,
+    ) -> Result<Vec<&Package>, semver::Error> {
+        let requirement = semver::VersionReq::parse(requirement)?;
+        Ok(self
+            .iter()
+            .filter(|package| package.name == name)
+            .filter(|package| {
+                semver::Version::parse(&package.version)
+                    .is_ok_and(|version| requirement.matches(&version))
+            })
+            .collect())
+    }
+
+    /// Builds a map from [PackageKey] to a clone of each package.
+    #[cfg(feature = "std")]
+    pub fn to_map(&self) -> HashMap<PackageKey, Package> {
+        self.iter()
+            .map(|package| (package.key(), package.clone()))
+            .collect()
+    }
+
+    /// Same as [PackageList::to_map], but moves the packages into the map instead of cloning them.
+    #[cfg(feature = "std")]
+    pub fn into_map(self) -> HashMap<PackageKey, Package> {
+        self.0
+            .into_iter()
+            .map(|package| (package.key(), package))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+
+    #[test]
+    fn test_get_package() {
+        let package_list = PackageList(vec![
+            Package::builder().name("log").version("0.4.22").build(),
+            Package::builder().name("log").version("1.2.0").build(),
+        ]);
+        assert_eq!(
+            package_list.get_package("log", "1.2.0").map(Package::key),
+            Some(PackageKey::new("log", "1.2.0"))
+        );
+        assert!(package_list.get_package("log", "1.2").is_none());
+    }
+
+    #[cfg(feature = "semver")]
+    #[test]
+    fn test_find_matching() {
+        let package_list = PackageList(
+            [
+                ("log", "0.4.22"),
+                ("log", "1.2.0"),
+                ("log", "1.9.1"),
+                ("log", "2.0.0"),
+                ("log", "2.0.0-beta.1"),
+                ("log", "1.5"),
+                ("other", "1.2.0"),
+            ]
+            .into_iter()
+            .map(|(name, version)| Package::builder().name(name).version(version).build())
+            .collect(),
+        );
+        let versions = |name: &str, requirement: &str| -> Vec<&str> {
+            package_list
+                .find_matching(name, requirement)
+                .unwrap()
+                .into_iter()
+                .map(|package| package.version.as_str())
+                .collect()
+        };
+
+        assert_eq!(versions("log", ">=1.2, <2"), ["1.2.0", "1.9.1"]);
+        assert_eq!(versions("log", "^0.4"), ["0.4.22"]);
+        // The version 1.5 is not valid semver and never matches.
+        assert_eq!(versions("log", "*"), ["0.4.22", "1.2.0", "1.9.1", "2.0.0"]);
+        assert_eq!(versions("log", "=1.2.0"), ["1.2.0"]);
+        assert_eq!(versions("other", "1"), ["1.2.0"]);
+        assert!(versions("missing", "*").is_empty());
+
+        // Pre-releases only match requirements naming a pre-release of the same version.
+        assert_eq!(versions("log", ">=2.0.0-alpha"), ["2.0.0", "2.0.0-beta.1"]);
+        assert_eq!(versions("log", ">=1.9.0-alpha"), ["1.9.1", "2.0.0"]);
+
+        assert!(package_list.find_matching("log", ">=1.2,, <2").is_err());
+        assert!(package_list.find_matching("log", "latest").is_err());
+    }
+}
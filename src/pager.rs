@@ -0,0 +1,150 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Labels, Package, PackageList};
+
+/// Renders a package like [PackageList]'s `Display`, without the leading separator.
+struct PackageText<'a>(&'a Package);
+
+impl fmt::Display for PackageText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0
+            .fmt_package_with_text(f, self.0.license_text.as_deref(), &Labels::default())
+    }
+}
+
+fn is_separator(line: &str) -> bool {
+    !line.is_empty() && (line.chars().all(|c| c == '=') || line.chars().all(|c| c == '-'))
+}
+
+/// Wraps a single line at whitespace. Words longer than `width` are split.
+pub(crate) fn wrap_line(line: &str, width: usize, out: &mut Vec<String>) {
+    let line = line.trim_end();
+    if line.chars().count() <= width {
+        out.push(line.to_string());
+        return;
+    }
+    if is_separator(line) {
+        out.push(line.chars().take(width).collect());
+        return;
+    }
+
+    let mut current = String::new();
+    let mut current_len = 0;
+    for word in line.split_whitespace() {
+        let mut word = word;
+        let mut word_len = word.chars().count();
+        if current_len > 0 && current_len + 1 + word_len > width {
+            out.push(core::mem::take(&mut current));
+            current_len = 0;
+        }
+        while word_len > width {
+            let split = word
+                .char_indices()
+                .nth(width)
+                .map_or(word.len(), |(i, _)| i);
+            if current_len > 0 {
+                out.push(core::mem::take(&mut current));
+                current_len = 0;
+            }
+            out.push(word[..split].to_string());
+            word = &word[split..];
+            word_len -= width;
+        }
+        if word_len == 0 {
+            continue;
+        }
+        if current_len > 0 {
+            current.push(' ');
+            current_len += 1;
+        }
+        current.push_str(word);
+        current_len += word_len;
+    }
+    if current_len > 0 {
+        out.push(current);
+    }
+}
+
+impl PackageList {
+    /// Renders the package list as pages of at most `height` lines with at most `width` characters each.
+    ///
+    /// Every package starts on a new page. Long lines are wrapped at whitespace, and separators are
+    /// shortened to `width`. Lines are joined with `\n`. Useful for built-in pagers of CLI applications.
+    /// Width and height are at least `1`.
+    ///
+    /// # Example
+    /// ```
+    /// use license_fetcher::{Package, PackageList};
+    ///
+    /// let package_list = PackageList(vec![
+    ///     Package::builder().name("a").version("1.0.0").license_text("MIT License").build(),
+    ///     Package::builder().name("b").version("2.0.0").build(),
+    /// ]);
+    ///
+    /// let pages: Vec<String> = package_list.render_pages(40, 24).collect();
+    /// assert_eq!(pages.len(), 2);
+    /// assert!(pages[0].lines().all(|line| line.chars().count() <= 40));
+    /// ```
+    pub fn render_pages(&self, width: usize, height: usize) -> impl Iterator<Item = String> + '_ {
+        let width = width.max(1);
+        let height = height.max(1);
+        self.asset_groups()
+            .into_iter()
+            .flat_map(|(_, group)| group)
+            .flat_map(move |package| {
+                let mut lines = Vec::new();
+                for line in PackageText(package).to_string().lines() {
+                    wrap_line(line, width, &mut lines);
+                }
+                while lines.last().is_some_and(|line| line.is_empty()) {
+                    lines.pop();
+                }
+                lines
+                    .chunks(height)
+                    .map(|page| page.join("\n"))
+                    .collect::<Vec<_>>()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+
+    #[test]
+    fn test_wrap_line() {
+        let mut lines = Vec::new();
+        wrap_line("aaa bbb ccc", 7, &mut lines);
+        wrap_line("abcdefghij", 4, &mut lines);
+        wrap_line(&"=".repeat(80), 5, &mut lines);
+        assert_eq!(
+            lines,
+            ["aaa bbb", "ccc", "abcd", "efgh", "ij", "====="].map(String::from)
+        );
+    }
+
+    #[test]
+    fn test_render_pages() {
+        let package_list = PackageList(vec![Package::builder()
+            .name("a")
+            .version("1.0.0")
+            .license_text("word ".repeat(100))
+            .build()]);
+
+        let pages: Vec<String> = package_list.render_pages(20, 5).collect();
+        assert!(pages.len() > 1);
+        for page in &pages {
+            assert!(page.lines().count() <= 5);
+            assert!(page.lines().all(|line| line.chars().count() <= 20));
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Payload of the serde based serialization formats CBOR and postcard.
+
+use alloc::format;
+
+use bincode::error::DecodeError;
+use serde::{Deserialize, Serialize};
+
+use crate::{BuildInfo, PackageList};
+
+/// Build info and package list. Encoded as map with the field names as keys in CBOR.
+#[derive(Serialize)]
+#[cfg_attr(not(feature = "build"), allow(dead_code))]
+pub(crate) struct Payload<'a> {
+    pub build_info: &'a Option<BuildInfo>,
+    pub packages: &'a PackageList,
+}
+
+#[derive(Deserialize)]
+struct OwnedPayload {
+    build_info: Option<BuildInfo>,
+    packages: PackageList,
+}
+
+#[cfg(feature = "cbor")]
+pub(crate) fn decode_cbor(bytes: &[u8]) -> Result<(PackageList, Option<BuildInfo>), DecodeError> {
+    let payload: OwnedPayload = ciborium::from_reader(bytes)
+        .map_err(|e| DecodeError::OtherString(format!("Invalid CBOR: {}", e)))?;
+    Ok((payload.packages, payload.build_info))
+}
+
+#[cfg(feature = "postcard")]
+pub(crate) fn decode_postcard(
+    bytes: &[u8],
+) -> Result<(PackageList, Option<BuildInfo>), DecodeError> {
+    let payload: OwnedPayload = postcard::from_bytes(bytes)
+        .map_err(|e| DecodeError::OtherString(format!("Invalid postcard: {}", e)))?;
+    Ok((payload.packages, payload.build_info))
+}
@@ -0,0 +1,99 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use core::ops::Range;
+
+use crate::{Package, PackageList};
+
+/// Occurrence of a search term in the license text of a package.
+///
+/// See [PackageList::search].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch<'a> {
+    pub package: &'a Package,
+    /// Byte range of the match in [Package::license_text].
+    pub range: Range<usize>,
+}
+
+impl SearchMatch<'_> {
+    /// The matched part of the license text.
+    pub fn text(&self) -> &str {
+        self.package
+            .license_text
+            .as_deref()
+            .map(|text| &text[self.range.clone()])
+            .unwrap_or_default()
+    }
+}
+
+/// Non-overlapping starts of `needle` in `haystack`, ignoring ASCII case.
+fn find_ignore_ascii_case<'a>(
+    haystack: &'a str,
+    needle: &'a str,
+) -> impl Iterator<Item = usize> + 'a {
+    let (haystack, needle) = (haystack.as_bytes(), needle.as_bytes());
+    let mut position = 0;
+    core::iter::from_fn(move || {
+        if needle.is_empty() {
+            return None;
+        }
+        while position + needle.len() <= haystack.len() {
+            let start = position;
+            if haystack[start..start + needle.len()].eq_ignore_ascii_case(needle) {
+                position += needle.len();
+                return Some(start);
+            }
+            position += 1;
+        }
+        None
+    })
+}
+
+impl PackageList {
+    /// Searches the license texts of all packages, ignoring ASCII case.
+    ///
+    /// Returns the matches in order of the packages and their position in the text.
+    /// An empty needle matches nothing.
+    ///
+    /// # Example
+    /// ```
+    /// use license_fetcher::{Package, PackageList};
+    ///
+    /// let package_list = PackageList(vec![
+    ///     Package::builder().name("a").license_text("MIT License\n\nPermission is hereby granted").build(),
+    /// ]);
+    ///
+    /// let matches: Vec<_> = package_list.search("permission").collect();
+    /// assert_eq!(matches.len(), 1);
+    /// assert_eq!(matches[0].package.name, "a");
+    /// assert_eq!(matches[0].range, 13..23);
+    /// assert_eq!(matches[0].text(), "Permission");
+    /// ```
+    pub fn search<'a>(&'a self, needle: &'a str) -> impl Iterator<Item = SearchMatch<'a>> + 'a {
+        self.iter().flat_map(move |package| {
+            let text = package.license_text.as_deref().unwrap_or_default();
+            find_ignore_ascii_case(text, needle).map(move |start| SearchMatch {
+                package,
+                range: start..start + needle.len(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_find_ignore_ascii_case() {
+        let starts: Vec<usize> = find_ignore_ascii_case("aAaA Ärger", "aa").collect();
+        assert_eq!(starts, [0, 2]);
+        assert_eq!(find_ignore_ascii_case("Ärger", "ärger").count(), 0);
+        assert_eq!(find_ignore_ascii_case("Ärger", "Ärger").count(), 1);
+        assert_eq!(find_ignore_ascii_case("abc", "").count(), 0);
+    }
+}
@@ -0,0 +1,18 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Names of the linker sections used by [get_package_list_section_macro](crate::get_package_list_section_macro).
+//!
+//! External tools can read these sections from a shipped binary to extract the license data
+//! without running it.
+
+/// Section name in ELF binaries (Linux, BSDs, ...).
+pub const ELF_SECTION: &str = ".license_fetcher";
+
+/// Segment and section name in Mach-O binaries (macOS, iOS).
+pub const MACHO_SECTION: &str = "__DATA,__lic_fetcher";
+
+/// Section name in PE binaries (Windows). PE section names are limited to 8 bytes.
+pub const PE_SECTION: &str = ".lcfetch";
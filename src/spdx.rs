@@ -0,0 +1,498 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Minimal SPDX license expression handling and classification of common licenses.
+
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use bincode::{Decode, Encode};
+
+/// Strength of the copyleft obligations of a license.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
+pub enum Copyleft {
+    /// Permissive license.
+    #[default]
+    None,
+    /// File or library level copyleft, e.g. `MPL-2.0` or `LGPL-3.0-only`.
+    Weak,
+    /// Copyleft covering the whole program, e.g. `GPL-3.0-only`.
+    Strong,
+}
+
+/// Classification of a license expression.
+///
+/// For `OR` expressions the most permissive choice is assumed, for `AND` expressions all
+/// obligations are combined.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
+pub struct LicenseInfo {
+    pub osi_approved: bool,
+    pub fsf_libre: bool,
+    pub copyleft: Copyleft,
+    /// The license text has to be shipped with binary distributions.
+    pub requires_attribution: bool,
+}
+
+/// `(identifier, osi approved, fsf libre, copyleft, requires attribution)`
+const LICENSE_TABLE: &[(&str, bool, bool, Copyleft, bool)] = &[
+    ("0BSD", true, false, Copyleft::None, false),
+    ("AGPL-3.0", true, true, Copyleft::Strong, true),
+    ("AGPL-3.0-only", true, true, Copyleft::Strong, true),
+    ("AGPL-3.0-or-later", true, true, Copyleft::Strong, true),
+    ("Apache-2.0", true, true, Copyleft::None, true),
+    ("Artistic-2.0", true, true, Copyleft::None, true),
+    ("BlueOak-1.0.0", true, false, Copyleft::None, true),
+    ("BSD-1-Clause", true, false, Copyleft::None, true),
+    ("BSD-2-Clause", true, true, Copyleft::None, true),
+    ("BSD-3-Clause", true, true, Copyleft::None, true),
+    ("BSL-1.0", true, true, Copyleft::None, false),
+    ("BUSL-1.1", false, false, Copyleft::None, true),
+    ("CC-BY-4.0", false, true, Copyleft::None, true),
+    ("CC-BY-SA-4.0", false, true, Copyleft::Strong, true),
+    ("CC0-1.0", false, true, Copyleft::None, false),
+    ("CDDL-1.0", true, true, Copyleft::Weak, true),
+    ("EPL-1.0", true, true, Copyleft::Weak, true),
+    ("EPL-2.0", true, true, Copyleft::Weak, true),
+    ("EUPL-1.2", true, true, Copyleft::Strong, true),
+    ("GPL-2.0", true, true, Copyleft::Strong, true),
+    ("GPL-2.0-only", true, true, Copyleft::Strong, true),
+    ("GPL-2.0-or-later", true, true, Copyleft::Strong, true),
+    ("GPL-3.0", true, true, Copyleft::Strong, true),
+    ("GPL-3.0-only", true, true, Copyleft::Strong, true),
+    ("GPL-3.0-or-later", true, true, Copyleft::Strong, true),
+    ("ISC", true, true, Copyleft::None, true),
+    ("LGPL-2.1", true, true, Copyleft::Weak, true),
+    ("LGPL-2.1-only", true, true, Copyleft::Weak, true),
+    ("LGPL-2.1-or-later", true, true, Copyleft::Weak, true),
+    ("LGPL-3.0", true, true, Copyleft::Weak, true),
+    ("LGPL-3.0-only", true, true, Copyleft::Weak, true),
+    ("LGPL-3.0-or-later", true, true, Copyleft::Weak, true),
+    ("MIT", true, true, Copyleft::None, true),
+    ("MIT-0", true, false, Copyleft::None, false),
+    ("MPL-1.1", true, true, Copyleft::Weak, true),
+    ("MPL-2.0", true, true, Copyleft::Weak, true),
+    ("NCSA", true, true, Copyleft::None, true),
+    ("OFL-1.1", true, true, Copyleft::Weak, true),
+    ("OpenSSL", false, true, Copyleft::None, true),
+    ("Python-2.0", true, true, Copyleft::None, true),
+    ("Unicode-3.0", true, false, Copyleft::None, true),
+    ("Unicode-DFS-2016", true, false, Copyleft::None, true),
+    ("Unlicense", true, true, Copyleft::None, false),
+    ("WTFPL", false, true, Copyleft::None, false),
+    ("X11", false, true, Copyleft::None, true),
+    ("Zlib", true, true, Copyleft::None, false),
+];
+
+/// Parsed SPDX license expression.
+///
+/// Besides the SPDX syntax, `/` is accepted as `OR` and operators are case insensitive,
+/// as both are common in older crates.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) enum Expression {
+    License {
+        id: String,
+        exception: Option<String>,
+    },
+    And(Vec<Expression>),
+    Or(Vec<Expression>),
+}
+
+impl Expression {
+    /// Parses an SPDX license expression. Returns `None` on syntax errors.
+    pub(crate) fn parse(expression: &str) -> Option<Self> {
+        let spaced = expression
+            .replace('(', " ( ")
+            .replace(')', " ) ")
+            .replace('/', " OR ");
+        let tokens: Vec<&str> = spaced.split_whitespace().collect();
+
+        let mut position = 0;
+        let parsed = Self::parse_or(&tokens, &mut position)?;
+        if position != tokens.len() {
+            return None;
+        }
+        Some(parsed)
+    }
+
+    fn parse_or(tokens: &[&str], position: &mut usize) -> Option<Self> {
+        let mut alternatives = vec![Self::parse_and(tokens, position)?];
+        while tokens
+            .get(*position)
+            .is_some_and(|t| t.eq_ignore_ascii_case("OR"))
+        {
+            *position += 1;
+            alternatives.push(Self::parse_and(tokens, position)?);
+        }
+        Some(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Self::Or(alternatives)
+        })
+    }
+
+    fn parse_and(tokens: &[&str], position: &mut usize) -> Option<Self> {
+        let mut conjunction = vec![Self::parse_atom(tokens, position)?];
+        while tokens
+            .get(*position)
+            .is_some_and(|t| t.eq_ignore_ascii_case("AND"))
+        {
+            *position += 1;
+            conjunction.push(Self::parse_atom(tokens, position)?);
+        }
+        Some(if conjunction.len() == 1 {
+            conjunction.pop().unwrap()
+        } else {
+            Self::And(conjunction)
+        })
+    }
+
+    fn parse_atom(tokens: &[&str], position: &mut usize) -> Option<Self> {
+        let token = *tokens.get(*position)?;
+        *position += 1;
+
+        if token == "(" {
+            let inner = Self::parse_or(tokens, position)?;
+            if tokens.get(*position) != Some(&")") {
+                return None;
+            }
+            *position += 1;
+            return Some(inner);
+        }
+
+        if token == ")" || is_operator(token) {
+            return None;
+        }
+
+        let mut exception = None;
+        if tokens
+            .get(*position)
+            .is_some_and(|t| t.eq_ignore_ascii_case("WITH"))
+        {
+            let exception_token = *tokens.get(*position + 1)?;
+            if exception_token == "(" || exception_token == ")" || is_operator(exception_token) {
+                return None;
+            }
+            exception = Some(exception_token.to_owned());
+            *position += 2;
+        }
+
+        Some(Self::License {
+            id: token.to_owned(),
+            exception,
+        })
+    }
+
+    /// Chooses alternatives of `OR` expressions according to a preference order of license identifiers.
+    ///
+    /// An alternative ranks by its least preferred license. Alternatives containing unlisted licenses
+    /// are only chosen over each other if none is fully listed, in which case the `OR` is kept.
+    pub(crate) fn resolve(&self, preference: &[String]) -> Self {
+        match self {
+            Self::License { .. } => self.clone(),
+            Self::And(conjunction) => {
+                Self::And(conjunction.iter().map(|e| e.resolve(preference)).collect())
+            }
+            Self::Or(alternatives) => {
+                let resolved: Vec<_> = alternatives.iter().map(|e| e.resolve(preference)).collect();
+                resolved
+                    .iter()
+                    .filter_map(|e| e.rank(preference).map(|rank| (rank, e)))
+                    .min_by_key(|(rank, _)| *rank)
+                    .map(|(_, e)| e.clone())
+                    .unwrap_or(Self::Or(resolved))
+            }
+        }
+    }
+
+    fn rank(&self, preference: &[String]) -> Option<usize> {
+        match self {
+            Self::License { id, .. } => preference.iter().position(|p| p.eq_ignore_ascii_case(id)),
+            Self::And(conjunction) => conjunction
+                .iter()
+                .map(|e| e.rank(preference))
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .max(),
+            Self::Or(alternatives) => alternatives.iter().filter_map(|e| e.rank(preference)).min(),
+        }
+    }
+
+    /// Returns `true` if the expression can be fulfilled using only the `allowed` licenses.
+    #[cfg_attr(not(feature = "build"), allow(dead_code))]
+    pub(crate) fn is_allowed(&self, allowed: &[String]) -> bool {
+        match self {
+            Self::License { id, .. } => allowed.iter().any(|a| a.eq_ignore_ascii_case(id)),
+            Self::And(conjunction) => conjunction.iter().all(|e| e.is_allowed(allowed)),
+            Self::Or(alternatives) => alternatives.iter().any(|e| e.is_allowed(allowed)),
+        }
+    }
+
+    /// Identifiers of all licenses in the expression, in order of appearance and without duplicates.
+    #[cfg(feature = "license-details")]
+    pub(crate) fn license_ids(&self) -> Vec<&str> {
+        let mut ids = vec![];
+        self.collect_license_ids(&mut ids);
+        ids
+    }
+
+    #[cfg(feature = "license-details")]
+    fn collect_license_ids<'a>(&'a self, ids: &mut Vec<&'a str>) {
+        match self {
+            Self::License { id, .. } => {
+                if !ids.contains(&id.as_str()) {
+                    ids.push(id);
+                }
+            }
+            Self::And(expressions) | Self::Or(expressions) => {
+                for expression in expressions {
+                    expression.collect_license_ids(ids);
+                }
+            }
+        }
+    }
+
+    /// Classifies the expression. Returns `None` if a required license is unknown.
+    pub(crate) fn license_info(&self) -> Option<LicenseInfo> {
+        match self {
+            Self::License { id, .. } => lookup(id),
+            Self::And(conjunction) => {
+                let infos = conjunction
+                    .iter()
+                    .map(|e| e.license_info())
+                    .collect::<Option<Vec<_>>>()?;
+                Some(LicenseInfo {
+                    osi_approved: infos.iter().all(|i| i.osi_approved),
+                    fsf_libre: infos.iter().all(|i| i.fsf_libre),
+                    copyleft: infos.iter().map(|i| i.copyleft).max()?,
+                    requires_attribution: infos.iter().any(|i| i.requires_attribution),
+                })
+            }
+            Self::Or(alternatives) => {
+                let infos: Vec<_> = alternatives
+                    .iter()
+                    .filter_map(|e| e.license_info())
+                    .collect();
+                Some(LicenseInfo {
+                    osi_approved: infos.iter().any(|i| i.osi_approved),
+                    fsf_libre: infos.iter().any(|i| i.fsf_libre),
+                    copyleft: infos.iter().map(|i| i.copyleft).min()?,
+                    requires_attribution: infos.iter().all(|i| i.requires_attribution),
+                })
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::License { id, exception } => {
+                write!(f, "{}", id)?;
+                if let Some(exception) = exception {
+                    write!(f, " WITH {}", exception)?;
+                }
+                Ok(())
+            }
+            Self::And(conjunction) => {
+                for (i, e) in conjunction.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " AND ")?;
+                    }
+                    match e {
+                        Self::Or(_) => write!(f, "({})", e)?,
+                        _ => write!(f, "{}", e)?,
+                    }
+                }
+                Ok(())
+            }
+            Self::Or(alternatives) => {
+                for (i, e) in alternatives.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " OR ")?;
+                    }
+                    write!(f, "{}", e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Resolves the alternatives of an SPDX expression according to a preference order.
+///
+/// Returns `None` if the expression can't be parsed.
+///
+/// # Example
+/// ```
+/// use license_fetcher::spdx::effective_license;
+///
+/// let preference = vec!["MIT".to_owned()];
+/// assert_eq!(
+///     effective_license("Apache-2.0 OR MIT", &preference).unwrap(),
+///     "MIT"
+/// );
+/// ```
+pub fn effective_license(expression: &str, preference: &[String]) -> Option<String> {
+    Some(
+        Expression::parse(expression)?
+            .resolve(preference)
+            .to_string(),
+    )
+}
+
+fn is_operator(token: &str) -> bool {
+    ["AND", "OR", "WITH"]
+        .iter()
+        .any(|op| token.eq_ignore_ascii_case(op))
+}
+
+pub(crate) fn lookup(id: &str) -> Option<LicenseInfo> {
+    let id = id.strip_suffix('+').unwrap_or(id);
+    LICENSE_TABLE
+        .iter()
+        .find(|(known, ..)| known.eq_ignore_ascii_case(id))
+        .map(
+            |&(_, osi_approved, fsf_libre, copyleft, requires_attribution)| LicenseInfo {
+                osi_approved,
+                fsf_libre,
+                copyleft,
+                requires_attribution,
+            },
+        )
+}
+
+impl LicenseInfo {
+    /// Classifies an SPDX license expression like `MIT OR Apache-2.0`.
+    ///
+    /// Returns `None` if the expression can't be parsed or contains unknown licenses.
+    ///
+    /// # Example
+    /// ```
+    /// use license_fetcher::spdx::{Copyleft, LicenseInfo};
+    ///
+    /// let info = LicenseInfo::from_expression("MIT OR Apache-2.0").unwrap();
+    /// assert!(info.osi_approved);
+    /// assert_eq!(info.copyleft, Copyleft::None);
+    /// ```
+    pub fn from_expression(expression: &str) -> Option<Self> {
+        Expression::parse(expression)?.license_info()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_expression() {
+        assert_eq!(
+            Expression::parse("(MIT OR Apache-2.0) AND Unicode-3.0"),
+            Some(Expression::And(vec![
+                Expression::Or(vec![
+                    Expression::License {
+                        id: "MIT".to_owned(),
+                        exception: None
+                    },
+                    Expression::License {
+                        id: "Apache-2.0".to_owned(),
+                        exception: None
+                    },
+                ]),
+                Expression::License {
+                    id: "Unicode-3.0".to_owned(),
+                    exception: None
+                },
+            ]))
+        );
+        assert_eq!(
+            Expression::parse("MIT/Apache-2.0"),
+            Expression::parse("MIT OR Apache-2.0")
+        );
+        assert_eq!(Expression::parse("MIT OR"), None);
+        assert_eq!(Expression::parse("(MIT"), None);
+    }
+
+    #[test]
+    fn test_effective_license() {
+        let resolve = |expression: &str, preference: &[&str]| {
+            let preference: Vec<String> = preference.iter().map(|p| p.to_string()).collect();
+            effective_license(expression, &preference)
+        };
+
+        assert_eq!(resolve("Apache-2.0 OR MIT", &["MIT"]).unwrap(), "MIT");
+        assert_eq!(resolve("Apache-2.0 OR MIT", &["mit"]).unwrap(), "MIT");
+        assert_eq!(
+            resolve("Apache-2.0 OR MIT", &["Apache-2.0", "MIT"]).unwrap(),
+            "Apache-2.0"
+        );
+        assert_eq!(
+            resolve("(MIT OR Apache-2.0) AND Unicode-3.0", &["Apache-2.0"]).unwrap(),
+            "Apache-2.0 AND Unicode-3.0"
+        );
+        assert_eq!(
+            resolve(
+                "GPL-2.0-only WITH Classpath-exception-2.0 OR MIT",
+                &["GPL-2.0-only"]
+            )
+            .unwrap(),
+            "GPL-2.0-only WITH Classpath-exception-2.0"
+        );
+
+        // Alternatives rank by their least preferred license.
+        assert_eq!(
+            resolve(
+                "(MIT AND Apache-2.0) OR BSD-3-Clause",
+                &["MIT", "BSD-3-Clause", "Apache-2.0"]
+            )
+            .unwrap(),
+            "BSD-3-Clause"
+        );
+
+        // Alternatives with unlisted licenses are kept.
+        assert_eq!(
+            resolve("Zlib OR (MIT AND Unlisted)", &["MIT"]).unwrap(),
+            "Zlib OR MIT AND Unlisted"
+        );
+        assert_eq!(
+            resolve("Zlib OR (MIT AND Unlisted)", &["MIT"]).map(|e| Expression::parse(&e)),
+            Some(Expression::parse("Zlib OR (MIT AND Unlisted)"))
+        );
+        assert_eq!(
+            resolve("Apache-2.0 OR MIT", &[]).unwrap(),
+            "Apache-2.0 OR MIT"
+        );
+        assert_eq!(resolve("MIT OR", &["MIT"]), None);
+    }
+
+    #[test]
+    fn test_license_info() {
+        let dual = LicenseInfo::from_expression("MIT OR GPL-3.0-only").unwrap();
+        assert_eq!(dual.copyleft, Copyleft::None);
+
+        let combined = LicenseInfo::from_expression("MIT AND LGPL-2.1-or-later").unwrap();
+        assert_eq!(combined.copyleft, Copyleft::Weak);
+        assert!(combined.requires_attribution);
+
+        assert_eq!(
+            LicenseInfo::from_expression("MIT AND Unknown-License"),
+            None
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::PackageList;
+
+const HEADER: [&str; 7] = [
+    "name",
+    "version",
+    "license",
+    "repository",
+    "authors",
+    "annotations",
+    "asset",
+];
+
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(alloc::format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+fn tsv_field(field: &str) -> Cow<'_, str> {
+    if field.contains(['\t', '\n', '\r']) {
+        Cow::Owned(field.replace(['\t', '\n', '\r'], " "))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+impl PackageList {
+    fn to_table(&self, separator: &str, escape: fn(&str) -> Cow<'_, str>) -> String {
+        let mut out = HEADER.join(separator);
+        out.push('\n');
+
+        for package in self.asset_groups().into_iter().flat_map(|(_, group)| group) {
+            let authors = package.authors.join("; ");
+            let annotations = package.annotations.join("; ");
+            let asset = package
+                .asset
+                .map(|kind| alloc::format!("{}", kind))
+                .unwrap_or_default();
+            let row: Vec<Cow<'_, str>> = [
+                package.name.as_str(),
+                package.version.as_str(),
+                package.license_identifier.as_deref().unwrap_or_default(),
+                package.repository.as_deref().unwrap_or_default(),
+                authors.as_str(),
+                annotations.as_str(),
+                asset.as_str(),
+            ]
+            .into_iter()
+            .map(escape)
+            .collect();
+            out.push_str(&row.join(separator));
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Renders name, version, license identifier, repository, authors, annotations and asset kind as CSV with
+    /// header row.
+    ///
+    /// Crates come first, followed by the assets grouped by kind. Fields are quoted according to RFC 4180. Multiple authors and annotations are separated by `; `.
+    pub fn to_csv(&self) -> String {
+        self.to_table(",", csv_field)
+    }
+
+    /// Same as [PackageList::to_csv], but tab separated.
+    ///
+    /// Tabs and line breaks inside of fields are replaced with spaces.
+    pub fn to_tsv(&self) -> String {
+        self.to_table("\t", tsv_field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+
+    use crate::{AssetKind, Package};
+
+    #[test]
+    fn test_csv() {
+        let package_list = PackageList(vec![Package::builder()
+            .name("a")
+            .version("1.0.0")
+            .license_identifier("MIT")
+            .author("Jane \"J\" Doe")
+            .author("John")
+            .annotation("Statically linked, on Windows only.")
+            .build()]);
+        let mut with_asset = PackageList(vec![Package::builder()
+            .name("Fira Sans")
+            .license_identifier("OFL-1.1")
+            .asset(AssetKind::Font)
+            .build()]);
+        with_asset.insert(0, package_list[0].clone());
+
+        assert_eq!(
+            package_list.to_csv(),
+            "name,version,license,repository,authors,annotations,asset\n\
+            a,1.0.0,MIT,,\"Jane \"\"J\"\" Doe; John\",\"Statically linked, on Windows only.\",\n"
+        );
+        assert_eq!(
+            package_list.to_tsv(),
+            "name\tversion\tlicense\trepository\tauthors\tannotations\tasset\n\
+            a\t1.0.0\tMIT\t\tJane \"J\" Doe; John\tStatically linked, on Windows only.\t\n"
+        );
+        assert!(with_asset
+            .to_csv()
+            .ends_with("\nFira Sans,,OFL-1.1,,,,Font\n"));
+    }
+}
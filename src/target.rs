@@ -0,0 +1,208 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use crate::{Package, PackageList};
+
+/// Architecture the program was compiled for, named like `cfg(target_arch)`.
+const ARCH: &str = if cfg!(target_arch = "x86_64") {
+    "x86_64"
+} else if cfg!(target_arch = "x86") {
+    "x86"
+} else if cfg!(target_arch = "aarch64") {
+    "aarch64"
+} else if cfg!(target_arch = "arm") {
+    "arm"
+} else if cfg!(target_arch = "riscv64") {
+    "riscv64"
+} else if cfg!(target_arch = "riscv32") {
+    "riscv32"
+} else if cfg!(target_arch = "powerpc64") {
+    "powerpc64"
+} else if cfg!(target_arch = "s390x") {
+    "s390x"
+} else if cfg!(target_arch = "loongarch64") {
+    "loongarch64"
+} else if cfg!(target_arch = "wasm32") {
+    "wasm32"
+} else if cfg!(target_arch = "wasm64") {
+    "wasm64"
+} else {
+    "unknown"
+};
+
+/// Operating system the program was compiled for, named like `cfg(target_os)`.
+const OS: &str = if cfg!(target_os = "windows") {
+    "windows"
+} else if cfg!(target_os = "android") {
+    "android"
+} else if cfg!(target_os = "linux") {
+    "linux"
+} else if cfg!(target_os = "macos") {
+    "macos"
+} else if cfg!(target_os = "ios") {
+    "ios"
+} else if cfg!(target_os = "freebsd") {
+    "freebsd"
+} else if cfg!(target_os = "netbsd") {
+    "netbsd"
+} else if cfg!(target_os = "openbsd") {
+    "openbsd"
+} else if cfg!(target_os = "illumos") {
+    "illumos"
+} else if cfg!(target_os = "wasi") {
+    "wasi"
+} else if cfg!(target_os = "none") {
+    "none"
+} else {
+    "unknown"
+};
+
+/// Environment the program was compiled for, named like `cfg(target_env)`.
+const ENV: &str = if cfg!(target_env = "msvc") {
+    "msvc"
+} else if cfg!(target_env = "gnu") {
+    "gnu"
+} else if cfg!(target_env = "musl") {
+    "musl"
+} else {
+    ""
+};
+
+/// Architecture, operating system and environment of a target triple like `armv7-unknown-linux-gnueabihf`,
+/// named like the corresponding `cfg` values.
+fn parse_triple(triple: &str) -> (&str, &str, &str) {
+    let mut components = triple.split('-');
+    let arch = components.next().unwrap_or_default();
+    let arch = match arch {
+        "i386" | "i586" | "i686" => "x86",
+        "arm64" => "aarch64",
+        "powerpc64le" => "powerpc64",
+        _ if arch.starts_with("armv") || arch.starts_with("thumbv") => "arm",
+        _ if arch.starts_with("riscv64") => "riscv64",
+        _ if arch.starts_with("riscv32") => "riscv32",
+        _ => arch,
+    };
+
+    let components: alloc::vec::Vec<&str> = components.collect();
+    let has = |prefix: &str| components.iter().any(|c| c.starts_with(prefix));
+    let os = if has("windows") {
+        "windows"
+    } else if has("android") {
+        "android"
+    } else if has("linux") {
+        "linux"
+    } else if has("darwin") || has("macos") {
+        "macos"
+    } else if has("ios") {
+        "ios"
+    } else {
+        ["freebsd", "netbsd", "openbsd", "illumos", "wasi", "none"]
+            .into_iter()
+            .find(|os| has(os))
+            .unwrap_or("unknown")
+    };
+    let env = if has("msvc") {
+        "msvc"
+    } else if has("gnu") {
+        "gnu"
+    } else if has("musl") {
+        "musl"
+    } else {
+        ""
+    };
+    (arch, os, env)
+}
+
+/// Returns `true` if the target triple denotes the target the program was compiled for.
+fn is_current_target(triple: &str) -> bool {
+    parse_triple(triple) == (ARCH, OS, ENV)
+}
+
+impl Package {
+    /// Returns `true` if the package is used on the target triple, see [Package::targets].
+    pub fn is_used_on(&self, target: &str) -> bool {
+        self.targets.is_empty() || self.targets.iter().any(|t| t == target)
+    }
+}
+
+impl PackageList {
+    /// Packages used on a target triple like `x86_64-pc-windows-msvc`, see [Package::targets].
+    pub fn for_target(&self, target: &str) -> PackageList {
+        PackageList(
+            self.iter()
+                .filter(|package| package.is_used_on(target))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Packages used on the target the program was compiled for, see [Package::targets].
+    ///
+    /// Targets are compared by architecture, operating system and environment, like `aarch64`, `macos` and no
+    /// environment for `aarch64-apple-darwin`.
+    pub fn for_current_target(&self) -> PackageList {
+        PackageList(
+            self.iter()
+                .filter(|package| {
+                    package.targets.is_empty()
+                        || package.targets.iter().any(|t| is_current_target(t))
+                })
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_triple() {
+        assert_eq!(
+            parse_triple("x86_64-pc-windows-msvc"),
+            ("x86_64", "windows", "msvc")
+        );
+        assert_eq!(
+            parse_triple("armv7-unknown-linux-gnueabihf"),
+            ("arm", "linux", "gnu")
+        );
+        assert_eq!(
+            parse_triple("aarch64-linux-android"),
+            ("aarch64", "android", "")
+        );
+        assert_eq!(
+            parse_triple("aarch64-apple-darwin"),
+            ("aarch64", "macos", "")
+        );
+        assert_eq!(
+            parse_triple("wasm32-unknown-unknown"),
+            ("wasm32", "unknown", "")
+        );
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_os = "linux", target_env = "gnu"))]
+    #[test]
+    fn test_for_current_target() {
+        let package_list = PackageList(alloc::vec![
+            Package::builder().name("a").build(),
+            Package::builder()
+                .name("b")
+                .target("x86_64-unknown-linux-gnu")
+                .build(),
+            Package::builder()
+                .name("c")
+                .target("x86_64-pc-windows-msvc")
+                .build(),
+        ]);
+        let names: alloc::vec::Vec<_> = package_list
+            .for_current_target()
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(package_list.for_target("x86_64-pc-windows-msvc").len(), 2);
+    }
+}
@@ -0,0 +1,224 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use std::env::var_os;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::pager::wrap_line;
+use crate::{Labels, Package, PackageList};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const CYAN: &str = "\x1b[36m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const MAGENTA: &str = "\x1b[35m";
+
+/// Writes ANSI escape codes, unless colors are disabled.
+struct Painter {
+    colored: bool,
+}
+
+impl Painter {
+    fn paint(&self, out: &mut String, style: &str, text: &str) {
+        if self.colored {
+            let _ = write!(out, "{}{}{}", style, text, RESET);
+        } else {
+            out.push_str(text);
+        }
+    }
+
+    /// Writes an OSC 8 hyperlink, which terminals without support show as plain text.
+    fn link(&self, out: &mut String, url: &str) {
+        if self.colored {
+            let _ = write!(out, "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, url);
+        } else {
+            out.push_str(url);
+        }
+    }
+
+    fn field(&self, out: &mut String, label: &str) {
+        self.paint(out, BOLD, &format!("{}:", label));
+        out.push(' ');
+    }
+}
+
+/// Wraps a license text to `width`.
+///
+/// Paragraphs are reflowed. Paragraphs with indented lines, like lists or ASCII art, are kept line by line.
+fn wrap_license_text(text: &str, width: usize, out: &mut Vec<String>) {
+    let text = text.replace("\r\n", "\n");
+    for (i, paragraph) in text.trim_end().split("\n\n").enumerate() {
+        let paragraph = paragraph.trim_matches('\n');
+        if paragraph.trim().is_empty() {
+            continue;
+        }
+        if i > 0 {
+            out.push(String::new());
+        }
+        let preformatted = paragraph
+            .lines()
+            .any(|line| line.starts_with(char::is_whitespace));
+        if preformatted {
+            for line in paragraph.lines() {
+                wrap_line(line, width, out);
+            }
+        } else {
+            let reflowed: Vec<&str> = paragraph.lines().map(str::trim).collect();
+            wrap_line(&reflowed.join(" "), width, out);
+        }
+    }
+}
+
+fn render_package(
+    out: &mut String,
+    package: &Package,
+    width: usize,
+    painter: &Painter,
+    labels: &Labels,
+) {
+    let mut heading = format!("{} {}", package.name, package.version);
+    if !package.renamed_as.is_empty() {
+        let _ = write!(heading, " (as {})", package.renamed_as.join(", "));
+    }
+    painter.paint(out, &format!("{}{}", BOLD, CYAN), heading.trim_end());
+    out.push('\n');
+
+    if let Some(description) = &package.description {
+        let mut lines = vec![];
+        wrap_line(description, width, &mut lines);
+        for line in lines {
+            painter.paint(out, DIM, &line);
+            out.push('\n');
+        }
+    }
+    if let Some(license_identifier) = &package.license_identifier {
+        painter.field(out, &labels.license);
+        painter.paint(out, GREEN, license_identifier);
+        if let Some(effective_license) = &package.effective_license {
+            if effective_license != license_identifier {
+                out.push_str(" (chosen: ");
+                painter.paint(out, GREEN, effective_license);
+                out.push(')');
+            }
+        }
+        out.push('\n');
+    }
+    if !package.authors.is_empty() {
+        painter.field(out, &labels.authors);
+        out.push_str(&package.authors.join(", "));
+        out.push('\n');
+    }
+    for (label, url) in [
+        (&labels.homepage, &package.homepage),
+        (&labels.repository, &package.repository),
+    ] {
+        if let Some(url) = url {
+            painter.field(out, label);
+            painter.link(out, url);
+            out.push('\n');
+        }
+    }
+    if !package.annotations.is_empty() {
+        painter.field(out, &labels.notes);
+        painter.paint(out, YELLOW, &package.annotations.join("; "));
+        out.push('\n');
+    }
+
+    if let Some(license_text) = &package.license_text {
+        out.push('\n');
+        let mut lines = vec![];
+        wrap_license_text(license_text, width, &mut lines);
+        for line in lines {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+
+    out.push('\n');
+    painter.paint(out, DIM, &"─".repeat(width));
+    out.push_str("\n\n");
+}
+
+impl PackageList {
+    /// Renders the list for terminals with colored headings, license texts wrapped to `width` and clickable
+    /// repository links.
+    ///
+    /// Links use OSC 8 escape sequences, which terminals without support show as plain URLs. With `colored` set
+    /// to `false`, no escape sequences are written at all. Width is at least `1`. Fields are labeled with
+    /// [Labels]. Needs the feature `term`.
+    ///
+    /// # Example
+    /// ```
+    /// use license_fetcher::{Labels, Package, PackageList};
+    ///
+    /// let package_list = PackageList(vec![Package::builder()
+    ///     .name("log")
+    ///     .version("0.4.22")
+    ///     .license_identifier("MIT OR Apache-2.0")
+    ///     .build()]);
+    ///
+    /// let text = package_list.render_colored(60, false, &Labels::default());
+    /// assert!(text.starts_with("log 0.4.22\nLicense: MIT OR Apache-2.0\n"));
+    /// ```
+    pub fn render_colored(&self, width: usize, colored: bool, labels: &Labels) -> String {
+        let width = width.max(1);
+        let painter = Painter { colored };
+        let mut out = String::new();
+        for (kind, packages) in self.asset_groups() {
+            if let Some(kind) = kind {
+                let heading = labels.asset_heading(kind);
+                painter.paint(&mut out, &format!("{}{}", BOLD, MAGENTA), heading);
+                out.push_str("\n\n");
+            }
+            for package in packages {
+                render_package(&mut out, package, width, &painter, labels);
+            }
+        }
+        out
+    }
+
+    /// Prints [PackageList::render_colored] with default [Labels] to stdout, e.g. for a `--licenses` flag of a CLI
+    /// application.
+    ///
+    /// Colors and links are disabled if the env variable `NO_COLOR` is set. Needs the feature `term`.
+    pub fn print_colored(&self, width: usize) -> io::Result<()> {
+        let colored = var_os("NO_COLOR").is_none_or(|value| value.is_empty());
+        io::stdout().lock().write_all(
+            self.render_colored(width, colored, &Labels::default())
+                .as_bytes(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_colored() {
+        let package_list = PackageList(vec![Package::builder()
+            .name("a")
+            .version("1.0.0")
+            .repository("https://example.com/a")
+            .license_text(
+                "Permission is hereby\ngranted, free of charge.\n\n  1. Keep\n  2. Share\n",
+            )
+            .build()]);
+
+        let plain = package_list.render_colored(20, false, &Labels::default());
+        assert_eq!(
+            plain,
+            "a 1.0.0\nRepository: https://example.com/a\n\nPermission is hereby\ngranted, free of\ncharge.\n\n  1. Keep\n  2. Share\n\n────────────────────\n\n"
+        );
+
+        let colored = package_list.render_colored(20, true, &Labels::default());
+        assert!(colored
+            .contains("\x1b]8;;https://example.com/a\x1b\\https://example.com/a\x1b]8;;\x1b\\"));
+        assert!(colored.contains("\x1b[1m\x1b[36ma 1.0.0\x1b[0m"));
+    }
+}
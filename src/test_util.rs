@@ -0,0 +1,166 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! Synthetic package lists for unit tests of applications, like about dialogs or license policies.
+//!
+//! Requires the feature `test-util`. The generated lists are deterministic.
+//!
+//! # Example
+//! ```
+//! use license_fetcher::test_util::PackageListBuilder;
+//!
+//! let package_list = PackageListBuilder::new()
+//!     .packages(100)
+//!     .licenses(["MIT", "MIT", "GPL-3.0-only"])
+//!     .text_size(64 * 1024)
+//!     .build();
+//!
+//! assert_eq!(package_list.len(), 100);
+//! assert_eq!(package_list[2].license_identifier.as_deref(), Some("GPL-3.0-only"));
+//! ```
+
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Package, PackageList};
+
+const FILLER: &str =
+    "Permission is hereby granted, free of charge, to any person obtaining a copy of this \
+software and associated documentation files, to deal in the Software without restriction. ";
+
+/// Builder of synthetic [PackageList]s.
+///
+/// Packages are named `package-0`, `package-1` and so on. Licenses are assigned from the license mix in turn.
+#[derive(Debug, Clone)]
+pub struct PackageListBuilder {
+    count: usize,
+    licenses: Vec<String>,
+    text_size: usize,
+    authors: bool,
+}
+
+impl Default for PackageListBuilder {
+    fn default() -> Self {
+        Self {
+            count: 10,
+            licenses: vec![
+                "MIT".to_owned(),
+                "Apache-2.0".to_owned(),
+                "MIT OR Apache-2.0".to_owned(),
+            ],
+            text_size: 1024,
+            authors: true,
+        }
+    }
+}
+
+impl PackageListBuilder {
+    /// Ten packages licensed under `MIT`, `Apache-2.0` or both, with license texts of 1 KiB.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of packages.
+    pub fn packages(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Sets the license identifiers, assigned to the packages in turn.
+    ///
+    /// Repeat an identifier to weight it. An empty mix generates packages without license identifier.
+    pub fn licenses<I, S>(mut self, licenses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.licenses = licenses.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the size of each license text in bytes. `0` generates packages without license text.
+    pub fn text_size(mut self, bytes: usize) -> Self {
+        self.text_size = bytes;
+        self
+    }
+
+    /// Whether packages have an author with email address.
+    pub fn authors(mut self, enable: bool) -> Self {
+        self.authors = enable;
+        self
+    }
+
+    fn license_text(&self, license_identifier: Option<&str>) -> String {
+        let mut text = format!("{} License\n\n", license_identifier.unwrap_or("Unknown"));
+        while text.len() < self.text_size {
+            text.push_str(FILLER);
+        }
+        // The text is ASCII.
+        text.truncate(self.text_size);
+        text
+    }
+
+    fn package(&self, index: usize) -> Package {
+        let license_identifier = (!self.licenses.is_empty())
+            .then(|| self.licenses[index % self.licenses.len()].as_str());
+
+        let mut builder = Package::builder()
+            .name(format!("package-{}", index))
+            .version(format!("1.{}.0", index))
+            .description(format!("Synthetic package number {}.", index));
+        if let Some(license_identifier) = license_identifier {
+            builder = builder.license_identifier(license_identifier);
+        }
+        if self.text_size > 0 {
+            builder = builder.license_text(self.license_text(license_identifier));
+        }
+        if self.authors {
+            builder = builder.author(format!("Author {} <author{}@example.com>", index, index));
+        }
+        builder.build()
+    }
+
+    pub fn build(self) -> PackageList {
+        PackageList((0..self.count).map(|index| self.package(index)).collect())
+    }
+}
+
+/// A list of `count` packages with the defaults of [PackageListBuilder].
+pub fn package_list(count: usize) -> PackageList {
+    PackageListBuilder::new().packages(count).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_list_builder() {
+        let package_list = PackageListBuilder::new()
+            .packages(3)
+            .licenses(["GPL-3.0-only"])
+            .text_size(10_000)
+            .authors(false)
+            .build();
+
+        assert_eq!(package_list.len(), 3);
+        for package in package_list.iter() {
+            assert_eq!(package.license_text.as_deref().map(str::len), Some(10_000));
+            assert!(package.license_info.is_some());
+            assert!(package.authors.is_empty());
+        }
+
+        let package_list = PackageListBuilder::new()
+            .licenses(Vec::<String>::new())
+            .text_size(0)
+            .build();
+        assert!(package_list
+            .iter()
+            .all(|p| p.license_identifier.is_none() && p.license_text.is_none()));
+    }
+}
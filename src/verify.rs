@@ -0,0 +1,133 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Package, PackageList};
+
+/// Result of [PackageList::verify].
+///
+/// Packages are listed as `name version`.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct VerificationReport {
+    pub missing_license_text: Vec<String>,
+    pub missing_license_identifier: Vec<String>,
+    pub missing_authors: Vec<String>,
+}
+
+impl VerificationReport {
+    /// Returns `true` if every package has license text, license identifier and authors.
+    pub fn is_complete(&self) -> bool {
+        self.missing_license_text.is_empty()
+            && self.missing_license_identifier.is_empty()
+            && self.missing_authors.is_empty()
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_complete() {
+            return writeln!(f, "All packages are complete.");
+        }
+
+        for (label, packages) in [
+            ("Missing license text:", &self.missing_license_text),
+            (
+                "Missing license identifier:",
+                &self.missing_license_identifier,
+            ),
+            ("Missing authors:", &self.missing_authors),
+        ] {
+            if packages.is_empty() {
+                continue;
+            }
+            writeln!(f, "{}", label)?;
+            for package in packages {
+                writeln!(f, "  - {}", package)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn package_label(package: &Package) -> String {
+    format!("{} {}", package.name, package.version)
+}
+
+impl PackageList {
+    /// Checks every package for missing license text, license identifier and authors.
+    ///
+    /// # Example
+    /// In `build.rs`:
+    /// ```ignore
+    /// use license_fetcher::build_script::generate_package_list_with_licenses;
+    ///
+    /// fn main() {
+    ///     let package_list = generate_package_list_with_licenses();
+    ///     let report = package_list.verify();
+    ///     if !report.is_complete() {
+    ///         println!("cargo::warning=Incomplete license information.");
+    ///     }
+    ///     package_list.write();
+    /// }
+    /// ```
+    pub fn verify(&self) -> VerificationReport {
+        let mut report = VerificationReport::default();
+
+        for package in self.iter() {
+            if package.license_text.is_none() {
+                report.missing_license_text.push(package_label(package));
+            }
+            if package.license_identifier.is_none() {
+                report
+                    .missing_license_identifier
+                    .push(package_label(package));
+            }
+            if package.authors.is_empty() {
+                report.missing_authors.push(package_label(package));
+            }
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::borrow::ToOwned;
+    use alloc::vec;
+
+    #[test]
+    fn test_verify_lists_incomplete_packages() {
+        let package_list = PackageList(vec![
+            Package {
+                name: "complete".to_owned(),
+                version: "1.0.0".to_owned(),
+                authors: vec!["Me".to_owned()],
+                license_identifier: Some("MIT".to_owned()),
+                license_text: Some("MIT License".into()),
+                ..Default::default()
+            },
+            Package {
+                name: "incomplete".to_owned(),
+                version: "0.1.0".to_owned(),
+                ..Default::default()
+            },
+        ]);
+
+        let report = package_list.verify();
+
+        assert!(!report.is_complete());
+        assert_eq!(report.missing_license_text, vec!["incomplete 0.1.0"]);
+        assert_eq!(report.missing_license_identifier, vec!["incomplete 0.1.0"]);
+        assert_eq!(report.missing_authors, vec!["incomplete 0.1.0"]);
+    }
+}
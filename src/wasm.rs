@@ -0,0 +1,66 @@
+//               Copyright Adam McKellar 2025
+// Distributed under the Boost Software License, Version 1.0.
+//         (See accompanying file LICENSE or copy at
+//          https://www.boost.org/LICENSE_1_0.txt)
+
+//! `wasm-bindgen` exports for rendering the embedded license data in web frontends.
+//!
+//! ```js
+//! import { decodePackageList } from "./pkg/my_app.js";
+//!
+//! for (const pkg of decodePackageList(bytes)) {
+//!     console.log(pkg.name, pkg.version, pkg.licenseIdentifier);
+//! }
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{get_package_list, Package};
+
+/// A [Package] as JS object.
+#[wasm_bindgen(js_name = Package, getter_with_clone)]
+pub struct WasmPackage {
+    pub name: String,
+    pub version: String,
+    pub authors: Vec<String>,
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    #[wasm_bindgen(js_name = licenseIdentifier)]
+    pub license_identifier: Option<String>,
+    #[wasm_bindgen(js_name = licenseText)]
+    pub license_text: Option<String>,
+    /// Notes for legal review, see [Package::annotations].
+    pub annotations: Vec<String>,
+    /// Kind of asset like `Font`, see [Package::asset]. `undefined` for crates.
+    pub asset: Option<String>,
+}
+
+impl From<Package> for WasmPackage {
+    fn from(package: Package) -> Self {
+        Self {
+            name: package.name,
+            version: package.version,
+            authors: package.authors,
+            description: package.description,
+            homepage: package.homepage,
+            repository: package.repository,
+            license_identifier: package.license_identifier,
+            license_text: package.license_text.as_deref().map(String::from),
+            annotations: package.annotations,
+            asset: package.asset.map(|kind| kind.to_string()),
+        }
+    }
+}
+
+/// Decodes embedded license data into an array of [WasmPackage].
+///
+/// Throws if the data is empty or corrupted.
+#[wasm_bindgen(js_name = decodePackageList)]
+pub fn decode_package_list(bytes: &[u8]) -> Result<Vec<WasmPackage>, JsError> {
+    let package_list = get_package_list(bytes).map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(package_list.0.into_iter().map(WasmPackage::from).collect())
+}